@@ -0,0 +1,93 @@
+//! Compares the allocations and time of serializing a `Message` built from
+//! borrowed values against a `MessageRef`, run with `cargo bench`.
+
+use pushover_api::{Message, MessagePriority, MessageRef};
+use std::{
+  alloc::{GlobalAlloc, Layout, System},
+  sync::atomic::{AtomicUsize, Ordering},
+  time::{Duration, Instant},
+};
+
+/// The number of messages to serialize for each measurement.
+const ITERATIONS: usize = 100_000;
+
+/// An allocator that counts its allocations.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    System.alloc(layout)
+  }
+
+  unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+    System.dealloc(pointer, layout)
+  }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Runs `serialize` for every iteration, returning the allocations per
+/// iteration and the total time.
+fn measure(mut serialize: impl FnMut(&str) -> String) -> (usize, Duration) {
+  let users = (0..ITERATIONS)
+    .map(|index| format!("user key {}", index))
+    .collect::<Vec<_>>();
+
+  let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+  let started = Instant::now();
+  let mut length = 0;
+  for user in &users {
+    length += serialize(user).len();
+  }
+
+  let elapsed = started.elapsed();
+  assert!(length > 0);
+  let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+  (allocations / ITERATIONS, elapsed)
+}
+
+fn main() {
+  let token = "application token";
+  let text = "The nightly backup finished without any errors.";
+  let devices = ["phone", "desktop"];
+
+  let (owned_allocations, owned_time) = measure(|user| {
+    let message = Message {
+      token: token.into(),
+      user: user.into(),
+      message: text.to_string(),
+      title: Some("Backups".to_string()),
+      device: devices.iter().map(ToString::to_string).collect(),
+      priority: Some(MessagePriority::High),
+      ..Message::default()
+    };
+    serde_json::to_string(&message).unwrap()
+  });
+
+  let (borrowed_allocations, borrowed_time) = measure(|user| {
+    let message = MessageRef {
+      token,
+      user,
+      message: text,
+      title: Some("Backups"),
+      device: &devices,
+      priority: Some(MessagePriority::High),
+      ..MessageRef::default()
+    };
+    serde_json::to_string(&message).unwrap()
+  });
+
+  println!(
+    "Message:    {} allocations per message, {:?} for {} messages",
+    owned_allocations, owned_time, ITERATIONS
+  );
+  println!(
+    "MessageRef: {} allocations per message, {:?} for {} messages",
+    borrowed_allocations, borrowed_time, ITERATIONS
+  );
+  assert!(borrowed_allocations < owned_allocations);
+}
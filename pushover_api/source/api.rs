@@ -0,0 +1,194 @@
+//! The envelope shared by Pushover API responses.
+
+use crate::{
+  api_errors, ApiError, HttpResponse, PushoverError, RequestId, ResponseExtras,
+  Result,
+};
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+use crate::{HttpRequest, Transport};
+use http::StatusCode;
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{str::FromStr, time::Duration};
+
+/// The maximum number of bytes of a response body to include in an error.
+const MAX_ERROR_BODY_LENGTH: usize = 300;
+
+/// The keys every Pushover API response has, with the keys specific to an
+/// endpoint in `payload`.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ApiResponse<T> {
+  #[serde(default, deserialize_with = "api_errors::deserialize")]
+  pub errors: Vec<ApiError>,
+  #[serde(default)]
+  pub request: Option<RequestId>,
+  #[serde(default)]
+  pub status: i32,
+  #[serde(flatten)]
+  pub payload: T,
+}
+
+/// A successful Pushover API response with its HTTP status and the limits of
+/// the application.
+#[derive(Debug)]
+pub(crate) struct ApiReply<T> {
+  pub http_status: StatusCode,
+  pub limit: Option<u32>,
+  pub remaining: Option<u32>,
+  pub reset: Option<i64>,
+  pub response: ApiResponse<T>,
+}
+
+/// Sends a request to the Pushover API and parses its response, see
+/// [`parse_reply()`](fn.parse_reply.html).
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub(crate) fn execute<T: DeserializeOwned>(
+  transport: &impl Transport,
+  request: HttpRequest,
+) -> Result<ApiReply<T>> {
+  #[cfg(feature = "tracing")]
+  let _span = crate::trace::request_span(&request).entered();
+
+  let reply = transport.execute(request).and_then(parse_reply);
+  #[cfg(feature = "tracing")]
+  if let Err(error) = &reply {
+    crate::trace::failure(error);
+  }
+
+  reply
+}
+
+/// Parses a response of the Pushover API, converting any failure into a
+/// [`PushoverError`](enum.PushoverError.html).
+///
+/// A response is only successful when its HTTP status is 2xx and its `status`
+/// is `1`, a response without errors that doesn't meet both is still an
+/// [`Api`](enum.PushoverError.html#variant.Api) error.
+pub(crate) fn parse_reply<T: DeserializeOwned>(
+  response: HttpResponse,
+) -> Result<ApiReply<T>> {
+  let limit = parse_header(&response, "x-limit-app-limit");
+  let remaining = parse_header(&response, "x-limit-app-remaining");
+  let reset = parse_header(&response, "x-limit-app-reset");
+  #[cfg(feature = "tracing")]
+  crate::trace::response(&response, limit, remaining, reset);
+
+  let retry_after = parse_header(&response, "retry-after");
+  let body = ResponseBody::from(response);
+  if body.http_status == StatusCode::TOO_MANY_REQUESTS {
+    return Err(body.into_rate_limited(retry_after, reset));
+  }
+
+  let envelope: ApiResponse<ResponseExtras> = body.parse()?;
+  if !envelope.errors.is_empty()
+    || !body.http_status.is_success()
+    || envelope.status != 1
+  {
+    return Err(body.into_api_error(envelope));
+  }
+
+  Ok(ApiReply {
+    http_status: body.http_status,
+    limit,
+    remaining,
+    reset,
+    response: body.parse()?,
+  })
+}
+
+/// Parses a header of a response, returning `None` when it's missing or
+/// invalid.
+fn parse_header<T: FromStr>(response: &HttpResponse, name: &str) -> Option<T> {
+  response
+    .header(name)
+    .and_then(|value| value.trim().parse().ok())
+}
+
+/// The HTTP status, content type and body of a Pushover API response, kept
+/// around so errors can include them.
+struct ResponseBody {
+  http_status: StatusCode,
+  content_type: Option<String>,
+  body: String,
+}
+
+impl From<HttpResponse> for ResponseBody {
+  fn from(response: HttpResponse) -> Self {
+    Self {
+      http_status: response.status,
+      content_type: parse_header(&response, "content-type"),
+      body: response.body,
+    }
+  }
+}
+
+impl ResponseBody {
+  /// Parses the body as JSON. When the body isn't JSON, like the HTML error
+  /// page of a proxy, the error includes the HTTP status, the content type and
+  /// the start of the body.
+  fn parse<T: DeserializeOwned>(&self) -> Result<T> {
+    serde_json::from_str(&self.body).map_err(|_| {
+      PushoverError::UnexpectedResponse {
+        http_status: self.http_status,
+        content_type: self.content_type.clone(),
+        body: self.start().to_string(),
+      }
+    })
+  }
+
+  /// Returns the start of the body to include in an error, cut off on a
+  /// character boundary.
+  fn start(&self) -> &str {
+    let mut end = self.body.len().min(MAX_ERROR_BODY_LENGTH);
+    while !self.body.is_char_boundary(end) {
+      end -= 1;
+    }
+
+    &self.body[..end]
+  }
+
+  /// Creates an [`Api`](enum.PushoverError.html#variant.Api) error from the
+  /// parsed envelope of this body.
+  fn into_api_error(
+    self,
+    envelope: ApiResponse<ResponseExtras>,
+  ) -> PushoverError {
+    PushoverError::Api {
+      errors: envelope.errors,
+      http_status: self.http_status,
+      status: envelope.status,
+      request: envelope.request,
+      extras: Box::new(envelope.payload),
+      body: self.body,
+    }
+  }
+
+  /// Creates a [`RateLimited`](enum.PushoverError.html#variant.RateLimited)
+  /// error from a response with the 429 status code, with the errors and
+  /// extras of its body when it's JSON.
+  fn into_rate_limited(
+    self,
+    retry_after: Option<u64>,
+    reset_at: Option<i64>,
+  ) -> PushoverError {
+    let (errors, request, extras, body) = match self
+      .parse::<ApiResponse<ResponseExtras>>()
+    {
+      Ok(envelope) => (
+        envelope.errors,
+        envelope.request,
+        envelope.payload,
+        self.body,
+      ),
+      Err(_) => (vec![], None, ResponseExtras::default(), self.start().into()),
+    };
+
+    PushoverError::RateLimited {
+      retry_after: retry_after.map(Duration::from_secs),
+      reset_at,
+      errors,
+      request,
+      extras: Box::new(extras),
+      body,
+    }
+  }
+}
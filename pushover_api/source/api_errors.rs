@@ -0,0 +1,55 @@
+//! Serde helpers for the errors of Pushover API responses.
+
+use crate::ApiError;
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+
+/// Deserializes the errors of a Pushover API response, which are usually a
+/// list of messages but can also be an object of messages keyed by the
+/// parameter they're about. Any other shape results in a single error with
+/// the raw JSON as its message, so it isn't lost.
+pub(crate) fn deserialize<'de, D>(
+  deserializer: D,
+) -> Result<Vec<ApiError>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  #[derive(Deserialize)]
+  #[serde(untagged)]
+  enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+  }
+
+  #[derive(Deserialize)]
+  #[serde(untagged)]
+  enum Errors {
+    List(Vec<String>),
+    Map(BTreeMap<String, OneOrMany>),
+    Other(serde_json::Value),
+  }
+
+  let errors = match Errors::deserialize(deserializer)? {
+    Errors::List(messages) => messages
+      .into_iter()
+      .map(|message| ApiError::new(None, message))
+      .collect(),
+    Errors::Map(map) => map
+      .into_iter()
+      .flat_map(|(field, messages)| {
+        let messages = match messages {
+          OneOrMany::One(message) => vec![message],
+          OneOrMany::Many(messages) => messages,
+        };
+
+        messages
+          .into_iter()
+          .map(move |message| ApiError::new(Some(field.clone()), message))
+      })
+      .collect(),
+    Errors::Other(serde_json::Value::Null) => vec![],
+    Errors::Other(value) => vec![ApiError::new(None, value.to_string())],
+  };
+
+  Ok(errors)
+}
@@ -0,0 +1,122 @@
+//! Checking the message limits of applications.
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+use crate::{
+  api, api_base, http_client, join_url, HttpMethod, HttpRequest,
+  PushoverClient, Result, Transport,
+};
+use serde::Deserialize;
+
+/// The monthly message limits of an application, as returned by
+/// [`get_limits()`](fn.get_limits.html).
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct AppLimits {
+  /// The number of messages the application can send per month.
+  #[serde(default)]
+  pub limit: u32,
+  /// The number of messages the application has left this month.
+  #[serde(default)]
+  pub remaining: u32,
+  /// When the limit resets as a Unix timestamp.
+  #[serde(default)]
+  pub reset: i64,
+}
+
+impl AppLimits {
+  /// Returns when the limit resets as a chrono
+  /// [`DateTime`](https://docs.rs/chrono/0.4/chrono/struct.DateTime.html), or
+  /// `None` when the timestamp is `0` or out of its range.
+  ///
+  /// ```rust
+  /// use pushover_api::AppLimits;
+  ///
+  /// let limits = AppLimits {
+  ///   limit: 10000,
+  ///   remaining: 7496,
+  ///   reset: 1_600_000_000,
+  /// };
+  /// assert_eq!(
+  ///   limits.reset_datetime().unwrap().to_rfc3339(),
+  ///   "2020-09-13T12:26:40+00:00"
+  /// );
+  ///
+  /// assert_eq!(AppLimits::default().reset_datetime(), None);
+  /// ```
+  #[cfg(feature = "chrono")]
+  pub fn reset_datetime(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+    crate::epoch::to_datetime(self.reset)
+  }
+}
+
+/// Gets the monthly message limits of an application.
+///
+/// ```rust,no_run
+/// let limits = pushover_api::get_limits("application token").unwrap();
+/// println!("{} of {} messages left", limits.remaining, limits.limit);
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn get_limits(token: &str) -> Result<AppLimits> {
+  get_limits_using(&http_client()?, &api_base(), token)
+}
+
+/// Gets the limits of an application with the given transport and base URL.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+fn get_limits_using(
+  transport: &impl Transport,
+  base: &str,
+  token: &str,
+) -> Result<AppLimits> {
+  let query = serde_urlencoded::to_string([("token", token)])?;
+  let request = HttpRequest::new(
+    HttpMethod::Get,
+    format!("{}?{}", join_url(base, "apps/limits.json"), query),
+  );
+
+  Ok(api::execute(transport, request)?.response.payload)
+}
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+impl<T: Transport> PushoverClient<T> {
+  /// Gets the monthly message limits of the application, see
+  /// [`get_limits()`](fn.get_limits.html).
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, ApiErrorKind, AppLimits, PushoverClient,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"limit":10000,"remaining":7496,"reset":1393653600,"status":1,
+  ///     "request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"token":"invalid","errors":["application token is invalid"],
+  ///     "status":0,"request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport);
+  ///
+  /// assert_eq!(
+  ///   client.get_limits().unwrap(),
+  ///   AppLimits {
+  ///     limit: 10000,
+  ///     remaining: 7496,
+  ///     reset: 1393653600,
+  ///   }
+  /// );
+  ///
+  /// let error = client.get_limits().unwrap_err();
+  /// assert_eq!(error.api_errors()[0].kind(), ApiErrorKind::InvalidToken);
+  ///
+  /// let request = &transport.requests()[0];
+  /// assert!(request
+  ///   .url
+  ///   .ends_with("/1/apps/limits.json?token=application+token"));
+  /// ```
+  pub fn get_limits(&self) -> Result<AppLimits> {
+    get_limits_using(&self.transport, &self.base_url, self.token())
+  }
+}
@@ -0,0 +1,438 @@
+//! Sending messages asynchronously, which also works on WebAssembly.
+
+use crate::{
+  api::{self, ApiReply},
+  api_base, HttpBody, HttpMethod, HttpRequest, HttpResponse, Message,
+  MessagePriority, MessageResponse, Result,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{PushoverError, RetryPolicy};
+use reqwest::{multipart::Form, Client};
+use serde::de::DeserializeOwned;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{Duration, Instant};
+
+/// Creates a Reqwest client for asynchronous requests, with the default
+/// timeouts.
+#[cfg(not(target_arch = "wasm32"))]
+fn async_client() -> Result<Client> {
+  crate::check_proxy_env()?;
+  Ok(
+    Client::builder()
+      .user_agent(crate::UserAgent::current_default().as_str())
+      .connect_timeout(crate::DEFAULT_CONNECT_TIMEOUT)
+      .timeout(crate::DEFAULT_TIMEOUT)
+      .build()?,
+  )
+}
+
+/// Creates a Reqwest client for asynchronous requests, which uses the
+/// browser's `fetch` on WebAssembly.
+#[cfg(target_arch = "wasm32")]
+fn async_client() -> Result<Client> {
+  Ok(Client::new())
+}
+
+/// Sends a request to the Pushover API with an asynchronous Reqwest client and
+/// parses its response like the blocking client does.
+pub(crate) async fn execute<T: DeserializeOwned>(
+  client: &Client,
+  request: HttpRequest,
+) -> Result<ApiReply<T>> {
+  #[cfg(feature = "tracing")]
+  let span = crate::trace::request_span(&request);
+  let reply = async { api::parse_reply(fetch(client, request).await?) };
+  #[cfg(feature = "tracing")]
+  let reply = tracing::Instrument::instrument(reply, span);
+
+  let reply = reply.await;
+  #[cfg(feature = "tracing")]
+  if let Err(error) = &reply {
+    crate::trace::failure(error);
+  }
+
+  reply
+}
+
+/// Sends a request with an asynchronous Reqwest client.
+async fn fetch(client: &Client, request: HttpRequest) -> Result<HttpResponse> {
+  let builder = match request.method {
+    HttpMethod::Get => client.get(&request.url),
+    HttpMethod::Post => client.post(&request.url),
+    HttpMethod::Delete => client.delete(&request.url),
+  };
+
+  let builder = match request.body {
+    HttpBody::Empty => builder,
+    HttpBody::Json(json) => builder
+      .header("content-type", "application/json")
+      .body(json),
+    HttpBody::Form(fields) => builder.form(&fields),
+    HttpBody::Multipart { fields, attachment } => {
+      let form = fields
+        .into_iter()
+        .fold(Form::new(), |form, (key, value)| form.text(key, value));
+      builder.multipart(form.part("attachment", attachment.to_async_part()?))
+    }
+  };
+
+  let response = builder.send().await?;
+  let status = response.status();
+  let headers = response.headers().clone();
+  Ok(HttpResponse::from_parts(
+    status,
+    &headers,
+    response.text().await?,
+  ))
+}
+
+impl Message {
+  /// Send this message to the Pushover API like [`send()`](#method.send), but
+  /// asynchronously. This is also available on WebAssembly, where the request
+  /// is made with the browser's `fetch`.
+  ///
+  /// A new Reqwest client is created for every message, use
+  /// [`send_async_with()`](#method.send_async_with) to reuse one.
+  ///
+  /// ```rust
+  /// use pushover_api::Message;
+  ///
+  /// # #[tokio::main]
+  /// # async fn main() {
+  /// # pushover_api::test_support::MockServer::respond(vec![]).set_api_url_var();
+  /// let response = Message {
+  ///   token: "application token".into(),
+  ///   user: "user key".into(),
+  ///   message: "Message".to_string(),
+  ///   ..Message::default()
+  /// }
+  /// .send_async()
+  /// .await
+  /// .unwrap();
+  ///
+  /// assert!(response.is_success());
+  /// # }
+  /// ```
+  pub async fn send_async(&self) -> Result<MessageResponse> {
+    self.send_async_with(&async_client()?).await
+  }
+
+  /// Send this message to the Pushover API asynchronously like
+  /// [`send_async()`](#method.send_async), using your own asynchronous
+  /// Reqwest client.
+  pub async fn send_async_with(
+    &self,
+    client: &Client,
+  ) -> Result<MessageResponse> {
+    let request = self.checked()?.to_request(&api_base())?;
+    let response = async {
+      Ok(MessageResponse::from_reply(execute(client, request).await?))
+    };
+    #[cfg(feature = "tracing")]
+    let response = tracing::Instrument::instrument(
+      response,
+      crate::trace::message_span(self.user.expose_secret(), self.priority),
+    );
+
+    response.await
+  }
+  /// Send this message to the Pushover API asynchronously like
+  /// [`send_async_with()`](#method.send_async_with), giving up when it takes
+  /// longer than `deadline`.
+  ///
+  /// When the deadline passes, a
+  /// [`DeadlineExceeded`](enum.PushoverError.html#variant.DeadlineExceeded)
+  /// error is returned. This has to run inside a Tokio runtime.
+  #[cfg(not(target_arch = "wasm32"))]
+  pub async fn send_async_with_deadline(
+    &self,
+    client: &Client,
+    deadline: Duration,
+  ) -> Result<MessageResponse> {
+    self
+      .send_async_with_retries(client, &RetryPolicy::never(), deadline)
+      .await
+  }
+
+  /// Send this message to the Pushover API asynchronously, retrying according
+  /// to `policy` when it fails because of a transient problem, and giving up
+  /// when all attempts together take longer than `deadline`.
+  ///
+  /// The deadline caps the total time, including the delays between
+  /// attempts. An attempt that is still running when it passes is cancelled,
+  /// and no attempt is started when the delay before it would end after the
+  /// deadline. Either way a
+  /// [`DeadlineExceeded`](enum.PushoverError.html#variant.DeadlineExceeded)
+  /// error is returned, with the error of the last failed attempt if there
+  /// was one. Errors that aren't retried are returned as they are.
+  ///
+  /// Sending is cancellation-safe: dropping the future, for example when it
+  /// loses a `tokio::select!`, only cancels the request that's in flight.
+  /// The client can keep being used and nothing is left behind.
+  ///
+  /// This has to run inside a Tokio runtime.
+  ///
+  /// ```rust
+  /// use pushover_api::{Message, PushoverError, RetryPolicy};
+  /// use std::time::Duration;
+  ///
+  /// # #[tokio::main]
+  /// # async fn main() {
+  /// # use pushover_api::{test_support::MockServer, HttpResponse};
+  /// # use std::sync::atomic::{AtomicBool, Ordering};
+  /// # // The first request gets a response that's too slow, the others are
+  /// # // answered right away.
+  /// # let first = AtomicBool::new(true);
+  /// # MockServer::with_handler(move |_| {
+  /// #   if first.swap(false, Ordering::SeqCst) {
+  /// #     std::thread::sleep(Duration::from_secs(2));
+  /// #   }
+  /// #   let body = r#"{"status":1,"request":"5042853c"}"#;
+  /// #   HttpResponse::json(http::StatusCode::OK, body)
+  /// # })
+  /// # .set_api_url_var();
+  /// let client = reqwest::Client::new();
+  /// let message = Message {
+  ///   token: "application token".into(),
+  ///   user: "user key".into(),
+  ///   message: "Message".to_string(),
+  ///   ..Message::default()
+  /// };
+  ///
+  /// let error = message
+  ///   .send_async_with_retries(
+  ///     &client,
+  ///     &RetryPolicy::default(),
+  ///     Duration::from_millis(200),
+  ///   )
+  ///   .await
+  ///   .unwrap_err();
+  /// assert!(matches!(
+  ///   error,
+  ///   PushoverError::DeadlineExceeded { attempts: 1, .. }
+  /// ));
+  /// assert!(error.is_timeout());
+  ///
+  /// // The cancelled request didn't break the client.
+  /// let response = message
+  ///   .send_async_with_deadline(&client, Duration::from_secs(5))
+  ///   .await
+  ///   .unwrap();
+  /// assert!(response.is_success());
+  /// # }
+  /// ```
+  ///
+  /// A retry that would start after the deadline isn't made:
+  ///
+  /// ```rust
+  /// use pushover_api::{Message, PushoverError, RetryPolicy};
+  /// use std::time::Duration;
+  ///
+  /// # #[tokio::main]
+  /// # async fn main() {
+  /// # use pushover_api::{test_support::MockServer, HttpResponse};
+  /// # MockServer::with_handler(|_| {
+  /// #   let body = r#"{"status":0,"request":"5042853c","errors":["down"]}"#;
+  /// #   HttpResponse::json(http::StatusCode::SERVICE_UNAVAILABLE, body)
+  /// # })
+  /// # .set_api_url_var();
+  /// let policy = RetryPolicy {
+  ///   max_attempts: 5,
+  ///   base_delay: Duration::from_secs(1),
+  ///   jitter: false,
+  ///   ..RetryPolicy::default()
+  /// };
+  ///
+  /// let error = Message {
+  ///   token: "application token".into(),
+  ///   user: "user key".into(),
+  ///   message: "Message".to_string(),
+  ///   ..Message::default()
+  /// }
+  /// .send_async_with_retries(
+  ///   &reqwest::Client::new(),
+  ///   &policy,
+  ///   Duration::from_millis(500),
+  /// )
+  /// .await
+  /// .unwrap_err();
+  ///
+  /// match error {
+  ///   PushoverError::DeadlineExceeded {
+  ///     attempts,
+  ///     last_error: Some(last_error),
+  ///     ..
+  ///   } => {
+  ///     assert_eq!(attempts, 1);
+  ///     assert!(matches!(*last_error, PushoverError::Api { .. }));
+  ///   }
+  ///   error => panic!("Unexpected error: {}", error),
+  /// }
+  /// # }
+  /// ```
+  #[cfg(not(target_arch = "wasm32"))]
+  pub async fn send_async_with_retries(
+    &self,
+    client: &Client,
+    policy: &RetryPolicy,
+    deadline: Duration,
+  ) -> Result<MessageResponse> {
+    let end = Instant::now() + deadline;
+    let exceeded = |attempts, last_error: Option<PushoverError>| {
+      PushoverError::DeadlineExceeded {
+        deadline,
+        attempts,
+        last_error: last_error.map(Box::new),
+      }
+    };
+
+    let mut attempts = 0;
+    let mut last_error = None;
+    loop {
+      let remaining = end.saturating_duration_since(Instant::now());
+      if remaining == Duration::from_secs(0) {
+        return Err(exceeded(attempts, last_error));
+      }
+
+      attempts += 1;
+      let attempt = self.send_async_with(client);
+      let error = match tokio::time::timeout(remaining, attempt).await {
+        Ok(Ok(response)) => return Ok(response),
+        Ok(Err(error)) => error,
+        Err(_) => return Err(exceeded(attempts, last_error)),
+      };
+
+      let (delay, error) = policy.next_attempt(attempts, error)?;
+      if Instant::now() + delay >= end {
+        return Err(exceeded(attempts, Some(error)));
+      }
+
+      last_error = Some(error);
+      tokio::time::delay_for(delay).await;
+    }
+  }
+}
+
+/// Convenience function to send a simple message asynchronously, like
+/// [`send_simple_message()`](fn.send_simple_message.html).
+///
+/// ```rust
+/// use pushover_api::{
+///   send_message_with_priority_async, send_simple_message_async,
+///   send_simple_message_with_title_async, send_simple_message_with_url_async,
+///   MessagePriority,
+/// };
+/// use serde_json::json;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// # let server = pushover_api::test_support::MockServer::respond(vec![]);
+/// # server.set_api_url_var();
+/// let (token, user) = ("application token", "user key");
+///
+/// send_simple_message_async(token, user, "Message").await.unwrap();
+/// send_simple_message_with_title_async(token, user, "Message", "Title")
+///   .await
+///   .unwrap();
+/// send_message_with_priority_async(
+///   token,
+///   user,
+///   "Message",
+///   MessagePriority::High,
+/// )
+/// .await
+/// .unwrap();
+/// send_simple_message_with_url_async(
+///   token,
+///   user,
+///   "Message",
+///   "https://example.com",
+///   Some("Example"),
+/// )
+/// .await
+/// .unwrap();
+///
+/// let required = json!({"token":token,"user":user,"message":"Message"});
+/// let mut expected = vec![required.clone(); 4];
+/// expected[1]["title"] = json!("Title");
+/// expected[2]["priority"] = json!(1);
+/// expected[3]["url"] = json!("https://example.com");
+/// expected[3]["url_title"] = json!("Example");
+/// # let bodies = server.requests().iter().map(|r| r.json()).collect::<Vec<_>>();
+/// assert_eq!(bodies, expected);
+/// # }
+/// ```
+pub async fn send_simple_message_async(
+  token: &str,
+  user: &str,
+  message: &str,
+) -> Result<MessageResponse> {
+  Message {
+    token: token.into(),
+    user: user.into(),
+    message: message.to_string(),
+    ..Message::default()
+  }
+  .send_async()
+  .await
+}
+
+/// Convenience function to send a message with a title asynchronously, like
+/// [`send_simple_message_with_title()`](fn.send_simple_message_with_title.html).
+pub async fn send_simple_message_with_title_async(
+  token: &str,
+  user: &str,
+  message: &str,
+  title: &str,
+) -> Result<MessageResponse> {
+  Message {
+    token: token.into(),
+    user: user.into(),
+    message: message.to_string(),
+    title: Some(title.to_string()),
+    ..Message::default()
+  }
+  .send_async()
+  .await
+}
+
+/// Convenience function to send a message with a priority asynchronously,
+/// like [`send_message_with_priority()`](fn.send_message_with_priority.html).
+pub async fn send_message_with_priority_async(
+  token: &str,
+  user: &str,
+  message: &str,
+  priority: MessagePriority,
+) -> Result<MessageResponse> {
+  Message {
+    token: token.into(),
+    user: user.into(),
+    message: message.to_string(),
+    priority: Some(priority),
+    ..Message::default()
+  }
+  .send_async()
+  .await
+}
+
+/// Convenience function to send a message with a supplementary URL and an
+/// optional title for it asynchronously, like
+/// [`send_simple_message_with_url()`](fn.send_simple_message_with_url.html).
+pub async fn send_simple_message_with_url_async(
+  token: &str,
+  user: &str,
+  message: &str,
+  url: &str,
+  url_title: Option<&str>,
+) -> Result<MessageResponse> {
+  Message {
+    token: token.into(),
+    user: user.into(),
+    message: message.to_string(),
+    url: Some(url.to_string()),
+    url_title: url_title.map(String::from),
+    ..Message::default()
+  }
+  .send_async()
+  .await
+}
@@ -0,0 +1,287 @@
+//! Image attachments for messages.
+
+use crate::{PushoverError, Result};
+#[cfg(feature = "blocking")]
+use reqwest::blocking::multipart::Part;
+use std::path::PathBuf;
+#[cfg(feature = "blocking")]
+use std::{
+  fs::File,
+  io::{self, Read},
+  path::Path,
+};
+
+/// The maximum size in bytes of an attachment that the Pushover API accepts.
+pub const MAX_ATTACHMENT_SIZE: u64 = 2_621_440;
+
+/// An image to send along with a [`Message`](struct.Message.html).
+///
+/// ```rust,no_run
+/// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+/// # {
+/// use pushover_api::{Attachment, Message};
+///
+/// Message {
+///   token: "application token".into(),
+///   user: "user key".into(),
+///   message: "Look at this!".to_string(),
+///   attachment: Some(Attachment::from_path("screenshot.png")),
+///   ..Message::default()
+/// }
+/// .send()
+/// .unwrap();
+/// # }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Attachment {
+  source: AttachmentSource,
+}
+
+/// Where the attachment's data comes from.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum AttachmentSource {
+  /// A file on disk, streamed when the message is sent.
+  Path(PathBuf),
+  /// Data that is already in memory.
+  Bytes {
+    /// The file name to send the data with.
+    name: String,
+    /// The data itself.
+    bytes: Vec<u8>,
+    /// The content type of the data.
+    mime: String,
+  },
+}
+
+impl Attachment {
+  /// Creates an attachment from a file path. The file is read when the
+  /// message is sent, and its content type is detected from its magic bytes
+  /// like with [`from_bytes()`](#method.from_bytes), whatever its extension
+  /// is.
+  ///
+  /// ```rust
+  /// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+  /// # {
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, Attachment, HttpBody, Message,
+  ///   PushoverClient,
+  /// };
+  ///
+  /// let path = std::env::temp_dir().join("pushover-attachment-doctest.png");
+  /// std::fs::write(&path, b"\x89PNG\r\n\x1a\n...").unwrap();
+  ///
+  /// let transport = FakeTransport::new();
+  /// let client = PushoverClient::with_transport("token", &transport)
+  ///   .default_user("user key");
+  /// client
+  ///   .send(&Message {
+  ///     message: "Look at this!".to_string(),
+  ///     attachment: Some(Attachment::from_path(&path)),
+  ///     ..Message::default()
+  ///   })
+  ///   .unwrap();
+  ///
+  /// let request = &transport.requests()[0];
+  /// assert_eq!(request.field("message").as_deref(), Some("Look at this!"));
+  /// assert_eq!(request.field("user").as_deref(), Some("user key"));
+  /// match &request.body {
+  ///   HttpBody::Multipart { attachment, .. } => {
+  ///     assert_eq!(attachment, &Attachment::from_path(&path));
+  ///   }
+  ///   body => panic!("Expected a multipart body, got {:?}", body),
+  /// }
+  /// # std::fs::remove_file(&path).unwrap();
+  /// # }
+  /// ```
+  pub fn from_path<P: Into<PathBuf>>(path: P) -> Self {
+    Self {
+      source: AttachmentSource::Path(path.into()),
+    }
+  }
+
+  /// Creates an attachment from data that is already in memory.
+  ///
+  /// When `mime` is `None`, the content type is detected from the data's
+  /// magic bytes for PNG, JPEG, GIF, BMP and WebP images, falling back to
+  /// `application/octet-stream`.
+  ///
+  /// ```rust
+  /// use pushover_api::Attachment;
+  ///
+  /// let png = b"\x89PNG\r\n\x1a\n...".to_vec();
+  /// let detected = Attachment::from_bytes("screenshot.png", png, None);
+  ///
+  /// let jpeg = vec![0xFF, 0xD8, 0xFF, 0xE0];
+  /// let explicit = Attachment::from_bytes("photo", jpeg, Some("image/jpeg"));
+  ///
+  /// assert_eq!(detected.mime(), Some("image/png"));
+  /// assert_eq!(explicit.mime(), Some("image/jpeg"));
+  /// ```
+  pub fn from_bytes<N, B>(name: N, bytes: B, mime: Option<&str>) -> Self
+  where
+    N: Into<String>,
+    B: Into<Vec<u8>>,
+  {
+    let bytes = bytes.into();
+    let mime = mime.unwrap_or_else(|| detect_mime(&bytes)).to_string();
+
+    Self {
+      source: AttachmentSource::Bytes {
+        name: name.into(),
+        bytes,
+        mime,
+      },
+    }
+  }
+
+  /// Returns the content type of an attachment from memory, detected or
+  /// given to [`from_bytes()`](#method.from_bytes). Attachments from a path
+  /// return `None`, their content type is only detected when they're sent.
+  ///
+  /// ```rust
+  /// use pushover_api::Attachment;
+  ///
+  /// let jpeg = vec![0xFF, 0xD8, 0xFF, 0xE0];
+  /// let detected = Attachment::from_bytes("photo.jpg", jpeg.clone(), None);
+  /// assert_eq!(detected.mime(), Some("image/jpeg"));
+  ///
+  /// let overridden = Attachment::from_bytes("photo", jpeg, Some("image/webp"));
+  /// assert_eq!(overridden.mime(), Some("image/webp"));
+  ///
+  /// let unknown = Attachment::from_bytes("notes.txt", "Hello", None);
+  /// assert_eq!(unknown.mime(), Some("application/octet-stream"));
+  ///
+  /// assert_eq!(Attachment::from_path("screenshot.png").mime(), None);
+  /// ```
+  pub fn mime(&self) -> Option<&str> {
+    match &self.source {
+      AttachmentSource::Path(_) => None,
+      AttachmentSource::Bytes { mime, .. } => Some(mime),
+    }
+  }
+
+  /// Creates the curl `-F` argument for this attachment. Attachments from
+  /// memory can't be included in the command, so their file name is used as
+  /// a placeholder path.
+  pub(crate) fn to_curl_field(&self) -> String {
+    match &self.source {
+      AttachmentSource::Path(path) => format!("attachment=@{}", path.display()),
+      AttachmentSource::Bytes { name, mime, .. } => {
+        format!("attachment=@{};type={}", name, mime)
+      }
+    }
+  }
+
+  /// Returns an error when the attachment is larger than
+  /// [`MAX_ATTACHMENT_SIZE`](constant.MAX_ATTACHMENT_SIZE.html) or its file
+  /// can't be read.
+  pub(crate) fn check_size(&self) -> Result<()> {
+    let (name, size) = match &self.source {
+      AttachmentSource::Path(path) => {
+        (path.display().to_string(), path.metadata()?.len())
+      }
+      AttachmentSource::Bytes { name, bytes, .. } => {
+        (name.clone(), bytes.len() as u64)
+      }
+    };
+
+    if size > MAX_ATTACHMENT_SIZE {
+      return Err(PushoverError::AttachmentTooLarge {
+        name,
+        size,
+        limit: MAX_ATTACHMENT_SIZE,
+      });
+    }
+
+    Ok(())
+  }
+
+  /// Creates the multipart form part for this attachment.
+  ///
+  /// Returns an error when the attachment is larger than
+  /// [`MAX_ATTACHMENT_SIZE`](constant.MAX_ATTACHMENT_SIZE.html).
+  #[cfg(feature = "blocking")]
+  pub(crate) fn to_part(&self) -> Result<Part> {
+    self.check_size()?;
+    match &self.source {
+      AttachmentSource::Path(path) => {
+        let mime = detect_file_mime(path)?;
+        Part::file(path)?.mime_str(mime).map_err(Into::into)
+      }
+      AttachmentSource::Bytes { name, bytes, mime } => {
+        Part::bytes(bytes.clone())
+          .file_name(name.clone())
+          .mime_str(mime)
+          .map_err(Into::into)
+      }
+    }
+  }
+
+  /// Returns the file name, contents and content type of this attachment,
+  /// reading the whole file into memory when it's a path.
+  ///
+  /// Returns an error when the attachment is larger than
+  /// [`MAX_ATTACHMENT_SIZE`](constant.MAX_ATTACHMENT_SIZE.html) or its file
+  /// can't be read.
+  #[cfg(any(feature = "async", feature = "ureq"))]
+  pub(crate) fn contents(&self) -> Result<(String, Vec<u8>, &str)> {
+    self.check_size()?;
+    Ok(match &self.source {
+      AttachmentSource::Path(path) => {
+        let bytes = std::fs::read(path)?;
+        let name = path
+          .file_name()
+          .map(|name| name.to_string_lossy().to_string())
+          .unwrap_or_else(|| "attachment".to_string());
+        let mime = detect_mime(&bytes);
+        (name, bytes, mime)
+      }
+      AttachmentSource::Bytes { name, bytes, mime } => {
+        (name.clone(), bytes.clone(), mime.as_str())
+      }
+    })
+  }
+
+  /// Creates the asynchronous multipart form part for this attachment.
+  ///
+  /// Returns an error when the attachment is larger than
+  /// [`MAX_ATTACHMENT_SIZE`](constant.MAX_ATTACHMENT_SIZE.html) or its file
+  /// can't be read.
+  #[cfg(feature = "async")]
+  pub(crate) fn to_async_part(&self) -> Result<reqwest::multipart::Part> {
+    let (name, bytes, mime) = self.contents()?;
+    reqwest::multipart::Part::bytes(bytes)
+      .file_name(name)
+      .mime_str(mime)
+      .map_err(Into::into)
+  }
+}
+
+/// Detects the content type of an image file from its magic bytes, reading
+/// only the start of the file.
+#[cfg(feature = "blocking")]
+fn detect_file_mime(path: &Path) -> io::Result<&'static str> {
+  let mut start = Vec::with_capacity(12);
+  File::open(path)?.take(12).read_to_end(&mut start)?;
+  Ok(detect_mime(&start))
+}
+
+/// Detects the content type of an image from its magic bytes.
+fn detect_mime(bytes: &[u8]) -> &'static str {
+  if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+    "image/png"
+  } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+    "image/jpeg"
+  } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+    "image/gif"
+  } else if bytes.starts_with(b"BM") {
+    "image/bmp"
+  } else if bytes.len() >= 12
+    && &bytes[0..4] == b"RIFF"
+    && &bytes[8..12] == b"WEBP"
+  {
+    "image/webp"
+  } else {
+    "application/octet-stream"
+  }
+}
@@ -0,0 +1,382 @@
+//! Sending messages from a background task.
+
+use crate::{Message, MessageResponse, PushoverError};
+use reqwest::Client;
+use std::{
+  collections::VecDeque,
+  fmt,
+  sync::{Arc, Mutex},
+};
+use tokio::{sync::Notify, task::JoinHandle};
+
+/// What a [`BackgroundSender`](struct.BackgroundSender.html) does with a new
+/// message when its queue is full.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DropPolicy {
+  /// Drops the oldest queued message to make room for the new one.
+  DropOldest,
+  /// Drops the new message, keeping the queued ones.
+  DropNewest,
+  /// Returns the new message in a
+  /// [`Full`](enum.EnqueueError.html#variant.Full) error.
+  Reject,
+}
+
+/// What happened to a message queued on a
+/// [`BackgroundSender`](struct.BackgroundSender.html), passed to its callback.
+#[derive(Debug)]
+pub enum Delivery {
+  /// The message was sent.
+  Sent {
+    /// The message that was sent.
+    message: Message,
+    /// The response of the Pushover API.
+    response: MessageResponse,
+  },
+  /// Sending the message failed.
+  Failed {
+    /// The message that failed to send.
+    message: Message,
+    /// Why sending the message failed.
+    error: PushoverError,
+  },
+  /// The message was dropped because the queue was full.
+  Dropped {
+    /// The message that was dropped.
+    message: Message,
+  },
+}
+
+/// Why [`BackgroundSender::enqueue()`](struct.BackgroundSender.html#method.enqueue)
+/// didn't queue a message, which is returned in the error.
+#[derive(Debug)]
+pub enum EnqueueError {
+  /// The queue is full and its [`DropPolicy`](enum.DropPolicy.html) is
+  /// [`Reject`](enum.DropPolicy.html#variant.Reject).
+  Full(Box<Message>),
+  /// The sender has been shut down.
+  ShutDown(Box<Message>),
+}
+
+impl EnqueueError {
+  /// Returns the message that wasn't queued.
+  pub fn into_message(self) -> Message {
+    match self {
+      EnqueueError::Full(message) | EnqueueError::ShutDown(message) => *message,
+    }
+  }
+}
+
+impl fmt::Display for EnqueueError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      EnqueueError::Full(_) => write!(f, "The message queue is full"),
+      EnqueueError::ShutDown(_) => {
+        write!(f, "The background sender has been shut down")
+      }
+    }
+  }
+}
+
+impl std::error::Error for EnqueueError {}
+
+/// The callback of a background sender.
+type Callback = Arc<dyn Fn(Delivery) + Send + Sync>;
+
+/// The queue shared by the handles and the background task.
+struct Queue {
+  messages: VecDeque<Message>,
+  shut_down: bool,
+}
+
+/// The state shared by the handles and the background task.
+struct Shared {
+  queue: Mutex<Queue>,
+  notify: Notify,
+  capacity: usize,
+  drop_policy: DropPolicy,
+  callback: Callback,
+  task: Mutex<Option<JoinHandle<()>>>,
+}
+
+/// A handle to a background task that sends queued messages one by one, so
+/// sending doesn't hold up the caller.
+///
+/// [`enqueue()`](#method.enqueue) never waits, when the queue is full the
+/// [`DropPolicy`](enum.DropPolicy.html) decides which message is dropped.
+/// Clones of the handle share the same queue and task. The results of sending
+/// are passed to the callback given to
+/// [`spawn_with_callback()`](#method.spawn_with_callback).
+///
+/// It needs the `background` feature and must be spawned inside a Tokio
+/// runtime.
+///
+/// ```rust
+/// # #[cfg(feature = "background")]
+/// # #[tokio::main]
+/// # async fn main() {
+/// use pushover_api::{BackgroundSender, Delivery, DropPolicy, Message};
+/// use std::sync::{Arc, Mutex};
+///
+/// # pushover_api::test_support::MockServer::respond(vec![]).set_api_url_var();
+/// let sent = Arc::new(Mutex::new(0));
+/// let counter = sent.clone();
+/// let sender = BackgroundSender::spawn_with_callback(
+///   reqwest::Client::new(),
+///   100,
+///   DropPolicy::DropOldest,
+///   move |delivery| {
+///     if let Delivery::Sent { .. } = delivery {
+///       *counter.lock().unwrap() += 1;
+///     }
+///   },
+/// );
+///
+/// for number in 1..=3 {
+///   sender
+///     .enqueue(Message {
+///       token: "application token".into(),
+///       user: "user key".into(),
+///       message: format!("Message {}", number),
+///       ..Message::default()
+///     })
+///     .unwrap();
+/// }
+///
+/// sender.shutdown().await;
+/// assert_eq!(*sent.lock().unwrap(), 3);
+/// # }
+/// # #[cfg(not(feature = "background"))]
+/// # fn main() {}
+/// ```
+#[derive(Clone)]
+pub struct BackgroundSender {
+  shared: Arc<Shared>,
+}
+
+impl BackgroundSender {
+  /// Spawns the background task with a queue of at most `capacity` messages,
+  /// ignoring the results of sending.
+  ///
+  /// Panics when it's not called inside a Tokio runtime.
+  pub fn spawn(
+    client: Client,
+    capacity: usize,
+    drop_policy: DropPolicy,
+  ) -> Self {
+    Self::spawn_with_callback(client, capacity, drop_policy, |_| {})
+  }
+
+  /// Spawns the background task with a queue of at most `capacity` messages,
+  /// calling `callback` with every message that was sent, failed to send or
+  /// was dropped.
+  ///
+  /// The callback runs on the background task, so it shouldn't block. A
+  /// `capacity` of `0` is treated as `1`.
+  ///
+  /// Panics when it's not called inside a Tokio runtime.
+  ///
+  /// ```rust
+  /// # #[cfg(feature = "background")]
+  /// # #[tokio::main(basic_scheduler)]
+  /// # async fn main() {
+  /// use pushover_api::{
+  ///   BackgroundSender, Delivery, DropPolicy, EnqueueError, Message,
+  /// };
+  /// use std::sync::{Arc, Mutex};
+  ///
+  /// fn message(text: &str) -> Message {
+  ///   Message {
+  ///     token: "application token".into(),
+  ///     user: "user key".into(),
+  ///     message: text.to_string(),
+  ///     ..Message::default()
+  ///   }
+  /// }
+  ///
+  /// # std::env::set_var(pushover_api::PUSHOVER_API_URL_VAR, "http://127.0.0.1:1/1");
+  /// for policy in vec![
+  ///   DropPolicy::DropOldest,
+  ///   DropPolicy::DropNewest,
+  ///   DropPolicy::Reject,
+  /// ] {
+  ///   let dropped = Arc::new(Mutex::new(vec![]));
+  ///   let record = dropped.clone();
+  ///   let sender = BackgroundSender::spawn_with_callback(
+  ///     reqwest::Client::new(),
+  ///     2,
+  ///     policy,
+  ///     move |delivery| {
+  ///       if let Delivery::Dropped { message } = delivery {
+  ///         record.lock().unwrap().push(message.message);
+  ///       }
+  ///     },
+  ///   );
+  ///
+  ///   // The background task doesn't run until this task waits, so the queue
+  ///   // fills up.
+  ///   sender.enqueue(message("First")).unwrap();
+  ///   sender.enqueue(message("Second")).unwrap();
+  ///   let third = sender.enqueue(message("Third"));
+  ///   sender.shutdown().await;
+  ///
+  ///   let dropped = dropped.lock().unwrap().clone();
+  ///   match policy {
+  ///     DropPolicy::DropOldest => assert_eq!(dropped, vec!["First"]),
+  ///     DropPolicy::DropNewest => assert_eq!(dropped, vec!["Third"]),
+  ///     DropPolicy::Reject => {
+  ///       assert!(dropped.is_empty());
+  ///       assert!(matches!(third, Err(EnqueueError::Full(_))));
+  ///     }
+  ///   }
+  /// }
+  /// # }
+  /// # #[cfg(not(feature = "background"))]
+  /// # fn main() {}
+  /// ```
+  pub fn spawn_with_callback<F>(
+    client: Client,
+    capacity: usize,
+    drop_policy: DropPolicy,
+    callback: F,
+  ) -> Self
+  where
+    F: Fn(Delivery) + Send + Sync + 'static,
+  {
+    let shared = Arc::new(Shared {
+      queue: Mutex::new(Queue {
+        messages: VecDeque::new(),
+        shut_down: false,
+      }),
+      notify: Notify::new(),
+      capacity: capacity.max(1),
+      drop_policy,
+      callback: Arc::new(callback),
+      task: Mutex::new(None),
+    });
+
+    let task = tokio::spawn(run(shared.clone(), client));
+    *shared.task.lock().unwrap() = Some(task);
+    Self { shared }
+  }
+
+  /// Queues a message to be sent by the background task, without waiting.
+  ///
+  /// When the queue is full, the `DropOldest` and `DropNewest` policies pass
+  /// the dropped message to the callback and return `Ok`, while `Reject`
+  /// returns the new message in an error. Messages queued after
+  /// [`shutdown()`](#method.shutdown) are always returned in an error.
+  pub fn enqueue(&self, message: Message) -> Result<(), EnqueueError> {
+    let mut queue = self.shared.queue.lock().unwrap();
+    if queue.shut_down {
+      return Err(EnqueueError::ShutDown(Box::new(message)));
+    }
+
+    let dropped = if queue.messages.len() < self.shared.capacity {
+      None
+    } else {
+      match self.shared.drop_policy {
+        DropPolicy::DropOldest => queue.messages.pop_front(),
+        DropPolicy::DropNewest => {
+          drop(queue);
+          (self.shared.callback)(Delivery::Dropped { message });
+          return Ok(());
+        }
+        DropPolicy::Reject => {
+          return Err(EnqueueError::Full(Box::new(message)))
+        }
+      }
+    };
+
+    queue.messages.push_back(message);
+    drop(queue);
+    self.shared.notify.notify();
+
+    if let Some(message) = dropped {
+      (self.shared.callback)(Delivery::Dropped { message });
+    }
+
+    Ok(())
+  }
+
+  /// The number of messages waiting to be sent, not counting the one that's
+  /// being sent.
+  pub fn len(&self) -> usize {
+    self.shared.queue.lock().unwrap().messages.len()
+  }
+
+  /// Whether no messages are waiting to be sent.
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// Stops accepting new messages and waits until the queued messages have
+  /// been sent. Calling it again, also on a clone, returns right away.
+  ///
+  /// ```rust
+  /// # #[cfg(feature = "background")]
+  /// # #[tokio::main]
+  /// # async fn main() {
+  /// use pushover_api::{BackgroundSender, DropPolicy, EnqueueError, Message};
+  ///
+  /// let sender =
+  ///   BackgroundSender::spawn(reqwest::Client::new(), 10, DropPolicy::Reject);
+  /// sender.clone().shutdown().await;
+  ///
+  /// let error = sender.enqueue(Message::default()).unwrap_err();
+  /// assert!(matches!(error, EnqueueError::ShutDown(_)));
+  /// # }
+  /// # #[cfg(not(feature = "background"))]
+  /// # fn main() {}
+  /// ```
+  pub async fn shutdown(&self) {
+    self.shared.queue.lock().unwrap().shut_down = true;
+    self.shared.notify.notify();
+
+    let task = self.shared.task.lock().unwrap().take();
+    if let Some(task) = task {
+      // The task only fails when the callback panicked, which is the
+      // callback's problem to report.
+      let _ = task.await;
+    }
+  }
+}
+
+impl fmt::Debug for BackgroundSender {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.debug_struct("BackgroundSender")
+      .field("queued", &self.len())
+      .field("capacity", &self.shared.capacity)
+      .field("drop_policy", &self.shared.drop_policy)
+      .finish()
+  }
+}
+
+/// Sends the queued messages until the sender is shut down and the queue is
+/// empty.
+async fn run(shared: Arc<Shared>, client: Client) {
+  loop {
+    let next = {
+      let mut queue = shared.queue.lock().unwrap();
+      match queue.messages.pop_front() {
+        Some(message) => Some(message),
+        None if queue.shut_down => return,
+        None => None,
+      }
+    };
+
+    let message = match next {
+      Some(message) => message,
+      None => {
+        shared.notify.notified().await;
+        continue;
+      }
+    };
+
+    let delivery = match message.send_async_with(&client).await {
+      Ok(response) => Delivery::Sent { message, response },
+      Err(error) => Delivery::Failed { message, error },
+    };
+    (shared.callback)(delivery);
+  }
+}
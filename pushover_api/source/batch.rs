@@ -0,0 +1,215 @@
+//! Sending many messages at once.
+
+use crate::{
+  Message, MessageResponse, PushoverClient, PushoverError, Result, Transport,
+};
+use std::{thread, time::Duration};
+
+/// The results of [`send_batch`](fn.send_batch.html), in the same order as
+/// the messages were given.
+#[derive(Debug)]
+pub struct BatchReport {
+  /// The result of sending each message.
+  pub results: Vec<Result<MessageResponse>>,
+}
+
+impl BatchReport {
+  /// Returns the number of messages that were sent successfully.
+  pub fn successes(&self) -> usize {
+    self.results.iter().filter(|result| result.is_ok()).count()
+  }
+
+  /// Returns the number of messages that failed to send, not counting the
+  /// ones that were [suppressed](#method.suppressed).
+  pub fn failures(&self) -> usize {
+    self
+      .results
+      .iter()
+      .filter(|result| {
+        result.is_err() && !matches!(result, Err(PushoverError::Suppressed))
+      })
+      .count()
+  }
+
+  /// Returns the number of messages that weren't sent because they repeat a
+  /// message sent recently, see
+  /// [`PushoverClient::with_dedup()`](struct.PushoverClient.html#method.with_dedup).
+  ///
+  /// ```rust
+  /// use pushover_api::{test_support::FakeTransport, Message, PushoverClient};
+  /// use std::time::Duration;
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853c"}"#);
+  /// transport.push_json(400, r#"{"status":0,"errors":["user is invalid"]}"#);
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport)
+  ///   .default_user("user key")
+  ///   .with_dedup(Duration::from_secs(60));
+  /// let messages = vec!["The disk is full", "The disk is full", "Other"]
+  ///   .into_iter()
+  ///   .map(|text| Message {
+  ///     message: text.to_string(),
+  ///     ..Message::default()
+  ///   });
+  ///
+  /// let report = client.send_batch(messages);
+  /// assert_eq!(report.successes(), 1);
+  /// assert_eq!(report.suppressed(), 1);
+  /// assert_eq!(report.failures(), 1);
+  /// assert_eq!(transport.requests().len(), 2);
+  /// ```
+  pub fn suppressed(&self) -> usize {
+    self
+      .results
+      .iter()
+      .filter(|result| matches!(result, Err(PushoverError::Suppressed)))
+      .count()
+  }
+}
+
+/// Sends messages one after another, continuing after any failures, and
+/// returns the result for each of them.
+///
+/// ```rust,no_run
+/// use pushover_api::{send_batch, Message};
+///
+/// let messages = vec!["first user key", "second user key"]
+///   .into_iter()
+///   .map(|user| Message {
+///     token: "application token".into(),
+///     user: user.into(),
+///     message: "Message".to_string(),
+///     ..Message::default()
+///   });
+///
+/// let report = send_batch(messages);
+/// println!("{} sent, {} failed", report.successes(), report.failures());
+/// ```
+pub fn send_batch<I>(messages: I) -> BatchReport
+where
+  I: IntoIterator<Item = Message>,
+{
+  send_batch_with_delay(messages, Duration::from_secs(0))
+}
+
+/// Like [`send_batch`](fn.send_batch.html), but waits for `delay` between
+/// sending each message. Use
+/// [`PushoverClient::send_batch_with_delay()`](struct.PushoverClient.html#method.send_batch_with_delay)
+/// to send them with a client's transport, defaults and retry policy.
+pub fn send_batch_with_delay<I>(messages: I, delay: Duration) -> BatchReport
+where
+  I: IntoIterator<Item = Message>,
+{
+  send_batch_using(messages, delay, Message::send)
+}
+
+/// Sends messages one after another with `send`, waiting for `delay` between
+/// them.
+fn send_batch_using<I>(
+  messages: I,
+  delay: Duration,
+  mut send: impl FnMut(&Message) -> Result<MessageResponse>,
+) -> BatchReport
+where
+  I: IntoIterator<Item = Message>,
+{
+  let mut results = vec![];
+  for (index, message) in messages.into_iter().enumerate() {
+    if index > 0 && delay > Duration::from_secs(0) {
+      thread::sleep(delay);
+    }
+
+    results.push(send(&message));
+  }
+
+  BatchReport { results }
+}
+
+impl<T: Transport> PushoverClient<T> {
+  /// Sends messages one after another like [`send()`](#method.send),
+  /// continuing after any failures, see
+  /// [`send_batch`](fn.send_batch.html).
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, Message, PushoverClient, MAX_MESSAGE_LENGTH,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853c"}"#);
+  /// transport.push_json(400, r#"{"status":0,"errors":["user is invalid"]}"#);
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853e"}"#);
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport);
+  /// let messages = vec!["first", "second", "third", "fourth"]
+  ///   .into_iter()
+  ///   .map(|user| Message {
+  ///     user: user.into(),
+  ///     message: match user {
+  ///       "fourth" => "a".repeat(MAX_MESSAGE_LENGTH + 1),
+  ///       _ => "Message".to_string(),
+  ///     },
+  ///     ..Message::default()
+  ///   });
+  ///
+  /// let report = client.send_batch(messages);
+  /// assert_eq!(report.successes(), 2);
+  /// assert_eq!(report.failures(), 2);
+  ///
+  /// let results = &report.results;
+  /// assert_eq!(results[0].as_ref().unwrap().request.as_str(), "5042853c");
+  /// assert_eq!(
+  ///   results[1].as_ref().unwrap_err().api_errors()[0].message,
+  ///   "user is invalid"
+  /// );
+  /// assert_eq!(results[2].as_ref().unwrap().request.as_str(), "5042853e");
+  /// // The message that's too long fails validation without a request.
+  /// assert!(results[3].is_err());
+  ///
+  /// let requests = transport.requests();
+  /// assert_eq!(requests.len(), 3);
+  /// assert_eq!(requests[2].field("user").as_deref(), Some("third"));
+  /// ```
+  pub fn send_batch<I>(&self, messages: I) -> BatchReport
+  where
+    I: IntoIterator<Item = Message>,
+  {
+    self.send_batch_with_delay(messages, Duration::from_secs(0))
+  }
+
+  /// Like [`send_batch()`](#method.send_batch), but waits for `delay`
+  /// between sending each message.
+  ///
+  /// ```rust
+  /// use pushover_api::{test_support::FakeTransport, Message, PushoverClient};
+  /// use std::time::{Duration, Instant};
+  ///
+  /// let transport = FakeTransport::new();
+  /// let client = PushoverClient::with_transport("application token", &transport)
+  ///   .default_user("user key");
+  /// let messages = (1..=3).map(|number| Message {
+  ///   message: format!("Message {}", number),
+  ///   ..Message::default()
+  /// });
+  ///
+  /// let started = Instant::now();
+  /// let report = client.send_batch_with_delay(messages, Duration::from_millis(50));
+  /// assert!(started.elapsed() >= Duration::from_millis(100));
+  /// assert_eq!(report.successes(), 3);
+  ///
+  /// let requests = transport.requests();
+  /// assert_eq!(requests.len(), 3);
+  /// assert_eq!(requests[2].field("message").as_deref(), Some("Message 3"));
+  /// ```
+  pub fn send_batch_with_delay<I>(
+    &self,
+    messages: I,
+    delay: Duration,
+  ) -> BatchReport
+  where
+    I: IntoIterator<Item = Message>,
+  {
+    send_batch_using(messages, delay, |message| self.send(message))
+  }
+}
@@ -0,0 +1,179 @@
+//! Borrowed messages.
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+use crate::{
+  api, api_url, http_client, HttpBody, HttpMethod, HttpRequest,
+  MessageResponse, Result,
+};
+use crate::{comma_separated, int_bool, MessagePriority, Sound};
+use serde::Serialize;
+
+/// A borrowed counterpart of [`Message`](struct.Message.html) that doesn't
+/// need any of its values to be cloned into `String`s, for sending lots of
+/// messages from values you already have.
+///
+/// It serializes exactly the same as a `Message` with the same values, but it
+/// can't have a multipart attachment and isn't
+/// [validated](struct.Message.html#method.validate) before being sent.
+///
+/// ```rust,no_run
+/// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+/// # {
+/// use pushover_api::MessageRef;
+///
+/// let token = "application token";
+/// for user in &["first user key", "second user key"] {
+///   MessageRef {
+///     token,
+///     user,
+///     message: "Message",
+///     title: Some("Title"),
+///     ..MessageRef::default()
+///   }
+///   .send()
+///   .unwrap();
+/// }
+/// # }
+/// ```
+///
+/// ```rust
+/// use pushover_api::{Message, MessagePriority, MessageRef, Sound};
+///
+/// let owned = Message {
+///   token: "application token".into(),
+///   user: "user key".into(),
+///   message: "Message".to_string(),
+///   device: vec!["phone".to_string(), "desktop".to_string()],
+///   title: Some("Title".to_string()),
+///   url: Some("https://example.com".to_string()),
+///   url_title: Some("Example".to_string()),
+///   priority: Some(MessagePriority::Emergency),
+///   sound: Some(Sound::Siren),
+///   timestamp: Some(1593892362),
+///   retry: Some(60),
+///   expire: Some(3600),
+///   callback: Some("https://example.com/callback".to_string()),
+///   tags: vec!["backups".to_string(), "servers".to_string()],
+///   html: Some(true),
+///   monospace: Some(false),
+///   ttl: Some(7200),
+///   attachment_base64: Some("iVBORw0KGgo=".to_string()),
+///   attachment_type: Some("image/png".to_string()),
+///   ..Message::default()
+/// };
+///
+/// let borrowed = MessageRef {
+///   token: "application token",
+///   user: "user key",
+///   message: "Message",
+///   device: &["phone", "desktop"],
+///   title: Some("Title"),
+///   url: Some("https://example.com"),
+///   url_title: Some("Example"),
+///   priority: Some(MessagePriority::Emergency),
+///   sound: Some(&Sound::Siren),
+///   timestamp: Some(1593892362),
+///   retry: Some(60),
+///   expire: Some(3600),
+///   callback: Some("https://example.com/callback"),
+///   tags: &["backups", "servers"],
+///   html: Some(true),
+///   monospace: Some(false),
+///   ttl: Some(7200),
+///   attachment_base64: Some("iVBORw0KGgo="),
+///   attachment_type: Some("image/png"),
+/// };
+///
+/// assert_eq!(
+///   serde_json::to_string(&owned).unwrap(),
+///   serde_json::to_string(&borrowed).unwrap()
+/// );
+///
+/// let owned = Message {
+///   message: "Message".to_string(),
+///   ..Message::default()
+/// };
+/// let borrowed = MessageRef {
+///   message: "Message",
+///   ..MessageRef::default()
+/// };
+/// assert_eq!(
+///   serde_json::to_string(&owned).unwrap(),
+///   serde_json::to_string(&borrowed).unwrap()
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct MessageRef<'a> {
+  /// The application's API token.
+  pub token: &'a str,
+  /// The user or group identifier to send the message to.
+  pub user: &'a str,
+  /// The actual message to send.
+  pub message: &'a str,
+  /// The devices to send the message to.
+  #[serde(skip_serializing_if = "<[_]>::is_empty", with = "comma_separated")]
+  pub device: &'a [&'a str],
+  /// The title of the message.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub title: Option<&'a str>,
+  /// A supplementary URL to show with the message.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub url: Option<&'a str>,
+  /// A title to use for the supplementary URL.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub url_title: Option<&'a str>,
+  /// The priority of the message.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub priority: Option<MessagePriority>,
+  /// The sound to play with the notification.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub sound: Option<&'a Sound>,
+  /// A Unix timestamp to use as the date time for the message.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub timestamp: Option<i64>,
+  /// How often (in seconds) an emergency priority message is retried.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub retry: Option<u32>,
+  /// How long (in seconds) an emergency priority message is retried for.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub expire: Option<u32>,
+  /// A URL to call when an emergency priority message is acknowledged.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub callback: Option<&'a str>,
+  /// Tags to attach to the message.
+  #[serde(skip_serializing_if = "<[_]>::is_empty", with = "comma_separated")]
+  pub tags: &'a [&'a str],
+  /// Whether to render the message with HTML formatting.
+  #[serde(skip_serializing_if = "Option::is_none", with = "int_bool")]
+  pub html: Option<bool>,
+  /// Whether to display the message in a monospace font.
+  #[serde(skip_serializing_if = "Option::is_none", with = "int_bool")]
+  pub monospace: Option<bool>,
+  /// The number of seconds until the message is deleted from the user's
+  /// devices.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub ttl: Option<u32>,
+  /// A base64-encoded image to attach to the message.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub attachment_base64: Option<&'a str>,
+  /// The content type of the base64-encoded image.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub attachment_type: Option<&'a str>,
+}
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+impl<'a> MessageRef<'a> {
+  /// Send this message to the Pushover API.
+  pub fn send(&self) -> Result<MessageResponse> {
+    #[cfg(feature = "tracing")]
+    let _span = crate::trace::message_span(self.user, self.priority).entered();
+
+    let request = HttpRequest::new(HttpMethod::Post, api_url("messages.json"))
+      .with_body(HttpBody::Json(serde_json::to_string(self)?));
+
+    Ok(MessageResponse::from_reply(api::execute(
+      &http_client()?,
+      request,
+    )?))
+  }
+}
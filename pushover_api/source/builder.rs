@@ -0,0 +1,471 @@
+//! A fluent builder for messages.
+
+use crate::{
+  Attachment, Html, Message, MessagePriority, Secret, Sound, Template,
+  TemplateError, TemplateVars, ValidationErrors,
+};
+use std::marker::PhantomData;
+
+/// A builder to construct a [`Message`](struct.Message.html) without having
+/// to wrap every field in `Some(...to_string())`.
+///
+/// ```rust
+/// use pushover_api::{Message, MessagePriority};
+///
+/// let message = Message::builder("application token", "user key", "Message")
+///   .title("Title")
+///   .priority(MessagePriority::High)
+///   .device(vec!["phone", "desktop"])
+///   .build()
+///   .unwrap();
+///
+/// assert_eq!(message.title.as_deref(), Some("Title"));
+/// assert_eq!(message.device, vec!["phone", "desktop"]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct MessageBuilder {
+  message: Message,
+}
+
+impl MessageBuilder {
+  /// Creates a new builder with the required fields.
+  pub fn new<T, U, M>(token: T, user: U, message: M) -> Self
+  where
+    T: Into<String>,
+    U: Into<String>,
+    M: Into<String>,
+  {
+    Self {
+      message: Message {
+        token: Secret::new(token),
+        user: Secret::new(user),
+        message: message.into(),
+        ..Message::default()
+      },
+    }
+  }
+
+  /// Sets the devices to send the message to.
+  pub fn device<I, S>(mut self, devices: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    self.message.device = devices.into_iter().map(Into::into).collect();
+    self
+  }
+
+  /// Sets the title of the message.
+  pub fn title<S: Into<String>>(mut self, title: S) -> Self {
+    self.message.title = Some(title.into());
+    self
+  }
+
+  /// Sets the supplementary URL of the message.
+  pub fn url<S: Into<String>>(mut self, url: S) -> Self {
+    self.message.url = Some(url.into());
+    self
+  }
+
+  /// Sets the title of the supplementary URL.
+  pub fn url_title<S: Into<String>>(mut self, url_title: S) -> Self {
+    self.message.url_title = Some(url_title.into());
+    self
+  }
+
+  /// Sets the priority of the message.
+  pub fn priority(mut self, priority: MessagePriority) -> Self {
+    self.message.priority = Some(priority);
+    self
+  }
+
+  /// Sets the priority of the message to
+  /// [`MessagePriority::Emergency`](enum.MessagePriority.html#variant.Emergency),
+  /// turning this builder into an
+  /// [`EmergencyMessageBuilder`](struct.EmergencyMessageBuilder.html) that
+  /// requires `retry` and `expire` to be set before it can be built.
+  pub fn priority_emergency(mut self) -> EmergencyMessageBuilder {
+    self.message.priority = Some(MessagePriority::Emergency);
+    EmergencyMessageBuilder {
+      message: self.message,
+      state: PhantomData,
+    }
+  }
+
+  /// Sets the sound to play for the message, either a
+  /// [`Sound`](enum.Sound.html) or its identifier.
+  pub fn sound<S: Into<Sound>>(mut self, sound: S) -> Self {
+    self.message.sound = Some(sound.into());
+    self
+  }
+
+  /// Sets the Unix timestamp of the message.
+  pub fn timestamp(mut self, timestamp: i64) -> Self {
+    self.message.timestamp = Some(timestamp);
+    self
+  }
+
+  /// Sets how often (in seconds) an emergency priority message is retried.
+  pub fn retry(mut self, retry: u32) -> Self {
+    self.message.retry = Some(retry);
+    self
+  }
+
+  /// Sets how long (in seconds) an emergency priority message is retried for.
+  pub fn expire(mut self, expire: u32) -> Self {
+    self.message.expire = Some(expire);
+    self
+  }
+
+  /// Sets the URL to call when an emergency priority message is acknowledged.
+  pub fn callback<S: Into<String>>(mut self, callback: S) -> Self {
+    self.message.callback = Some(callback.into());
+    self
+  }
+
+  /// Sets the tags of the message.
+  pub fn tags<I, S>(mut self, tags: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    self.message.tags = tags.into_iter().map(Into::into).collect();
+    self
+  }
+
+  /// Sets whether to render the message with HTML formatting.
+  pub fn html(mut self, html: bool) -> Self {
+    self.message.html = Some(html);
+    self
+  }
+
+  /// Sets the message to the markup of an [`Html`](struct.Html.html) builder
+  /// and enables HTML formatting.
+  pub fn html_message(mut self, html: Html) -> Self {
+    self.message.set_html_message(html);
+    self
+  }
+
+  /// Sets the message to a [`Template`](struct.Template.html) rendered with
+  /// the given values.
+  ///
+  /// ```rust
+  /// use pushover_api::{Message, Template};
+  /// use std::collections::HashMap;
+  ///
+  /// let template = Template::parse("{service} is {state}").unwrap();
+  /// let mut vars = HashMap::new();
+  /// vars.insert("service", "nginx");
+  /// vars.insert("state", "down");
+  ///
+  /// let message = Message::builder("application token", "user key", "")
+  ///   .message_template(&template, &vars)
+  ///   .unwrap()
+  ///   .build()
+  ///   .unwrap();
+  /// assert_eq!(message.message, "nginx is down");
+  /// ```
+  pub fn message_template<V: TemplateVars + ?Sized>(
+    mut self,
+    template: &Template,
+    vars: &V,
+  ) -> Result<Self, TemplateError> {
+    self.message.message = template.render(vars)?;
+    Ok(self)
+  }
+
+  /// Sets whether to display the message in a monospace font.
+  pub fn monospace(mut self, monospace: bool) -> Self {
+    self.message.monospace = Some(monospace);
+    self
+  }
+
+  /// Sets the number of seconds until the message is deleted from the user's
+  /// devices.
+  pub fn ttl(mut self, ttl: u32) -> Self {
+    self.message.ttl = Some(ttl);
+    self
+  }
+
+  /// Sets the image to attach to the message.
+  pub fn attachment(mut self, attachment: Attachment) -> Self {
+    self.message.attachment = Some(attachment);
+    self
+  }
+
+  /// Base64-encodes an image and sets it as the message's base64 attachment.
+  pub fn attachment_base64(mut self, bytes: &[u8], mime: &str) -> Self {
+    self.message.set_attachment_base64(bytes, mime);
+    self
+  }
+
+  /// Sets whether to truncate the message, title and URL title to fit
+  /// Pushover's limits when sending the message.
+  pub fn truncate_to_fit(mut self, truncate_to_fit: bool) -> Self {
+    self.message.truncate_to_fit = truncate_to_fit;
+    self
+  }
+
+  /// [Validates](struct.Message.html#method.validate) and returns the message.
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   Attachment, Html, Message, MessagePriority, Sound, ValidationError,
+  ///   MAX_TITLE_LENGTH,
+  /// };
+  ///
+  /// let png = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+  /// let attachment = Attachment::from_bytes("image.png", png.clone(), None);
+  ///
+  /// let message = Message::builder("application token", "user key", "Message")
+  ///   .device(vec!["phone", "desktop"])
+  ///   .title("Title")
+  ///   .url("https://example.com")
+  ///   .url_title("Example")
+  ///   .priority(MessagePriority::High)
+  ///   .sound("magic")
+  ///   .timestamp(1_600_000_000)
+  ///   .retry(60)
+  ///   .expire(3600)
+  ///   .callback("https://example.com/acknowledged")
+  ///   .tags(vec!["outage", "server-1"])
+  ///   .html(false)
+  ///   .monospace(true)
+  ///   .ttl(600)
+  ///   .attachment(attachment.clone())
+  ///   .truncate_to_fit(true)
+  ///   .build()
+  ///   .unwrap();
+  ///
+  /// assert_eq!(
+  ///   message,
+  ///   Message {
+  ///     token: "application token".into(),
+  ///     user: "user key".into(),
+  ///     message: "Message".to_string(),
+  ///     device: vec!["phone".to_string(), "desktop".to_string()],
+  ///     title: Some("Title".to_string()),
+  ///     url: Some("https://example.com".to_string()),
+  ///     url_title: Some("Example".to_string()),
+  ///     priority: Some(MessagePriority::High),
+  ///     sound: Some(Sound::Magic),
+  ///     timestamp: Some(1_600_000_000),
+  ///     retry: Some(60),
+  ///     expire: Some(3600),
+  ///     callback: Some("https://example.com/acknowledged".to_string()),
+  ///     tags: vec!["outage".to_string(), "server-1".to_string()],
+  ///     html: Some(false),
+  ///     monospace: Some(true),
+  ///     ttl: Some(600),
+  ///     attachment: Some(attachment),
+  ///     truncate_to_fit: true,
+  ///     ..Message::default()
+  ///   }
+  /// );
+  ///
+  /// let message = Message::builder("application token", "user key", "")
+  ///   .html_message(Html::new().text("The build is ").bold("broken"))
+  ///   .attachment_base64(&png, "image/png")
+  ///   .build()
+  ///   .unwrap();
+  /// assert_eq!(message.message, "The build is <b>broken</b>");
+  /// assert_eq!(message.html, Some(true));
+  /// assert_eq!(message.attachment_base64.as_deref(), Some("iVBORw0KGgo="));
+  /// assert_eq!(message.attachment_type.as_deref(), Some("image/png"));
+  ///
+  /// // Building validates the message.
+  /// let errors = Message::builder("application token", "user key", "Message")
+  ///   .title("a".repeat(MAX_TITLE_LENGTH + 1))
+  ///   .html(true)
+  ///   .monospace(true)
+  ///   .build()
+  ///   .unwrap_err();
+  /// assert_eq!(errors.0.len(), 2);
+  /// assert_eq!(errors.0[1], ValidationError::HtmlAndMonospace);
+  /// ```
+  pub fn build(self) -> Result<Message, ValidationErrors> {
+    self.message.validate()?;
+    Ok(self.message)
+  }
+}
+
+/// Type-state marker for an
+/// [`EmergencyMessageBuilder`](struct.EmergencyMessageBuilder.html) field
+/// that hasn't been set yet.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Missing;
+
+/// Type-state marker for an
+/// [`EmergencyMessageBuilder`](struct.EmergencyMessageBuilder.html) field
+/// that has been set.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Provided;
+
+/// A builder for emergency priority messages, created with
+/// [`MessageBuilder::priority_emergency()`](struct.MessageBuilder.html#method.priority_emergency).
+///
+/// The `R` and `E` parameters track whether `retry` and `expire` have been set,
+/// and `build()` is only available once both are, so forgetting either of them
+/// is a compile error instead of a runtime one.
+///
+/// ```rust
+/// use pushover_api::Message;
+///
+/// let message = Message::builder("application token", "user key", "Message")
+///   .title("The server is on fire!")
+///   .priority_emergency()
+///   .retry(60)
+///   .expire(3600)
+///   .build()
+///   .unwrap();
+///
+/// assert_eq!(message.retry, Some(60));
+/// assert_eq!(message.expire, Some(3600));
+/// ```
+///
+/// `retry` and `expire` can be set in either order, and the callback and tags
+/// can be set at any point.
+///
+/// ```rust
+/// use pushover_api::{Message, MessagePriority, ValidationError};
+///
+/// let message = Message::builder("application token", "user key", "Message")
+///   .ttl(600)
+///   .priority_emergency()
+///   .callback("https://example.com/acknowledged")
+///   .expire(3600)
+///   .tags(vec!["outage"])
+///   .retry(60);
+///
+/// let errors = message.clone().build().unwrap_err();
+/// assert_eq!(errors.0, vec![ValidationError::EmergencyWithTtl]);
+///
+/// let message = Message::builder("application token", "user key", "Message")
+///   .priority_emergency()
+///   .callback("https://example.com/acknowledged")
+///   .expire(3600)
+///   .tags(vec!["outage"])
+///   .retry(60)
+///   .build()
+///   .unwrap();
+/// assert_eq!(
+///   message,
+///   Message {
+///     token: "application token".into(),
+///     user: "user key".into(),
+///     message: "Message".to_string(),
+///     priority: Some(MessagePriority::Emergency),
+///     retry: Some(60),
+///     expire: Some(3600),
+///     callback: Some("https://example.com/acknowledged".to_string()),
+///     tags: vec!["outage".to_string()],
+///     ..Message::default()
+///   }
+/// );
+///
+/// let errors = Message::builder("application token", "user key", "Message")
+///   .priority_emergency()
+///   .retry(60)
+///   .expire(3600)
+///   .tags(vec!["two words"])
+///   .build()
+///   .unwrap_err();
+/// assert_eq!(
+///   errors.0,
+///   vec![ValidationError::InvalidTag("two words".to_string())]
+/// );
+/// ```
+///
+/// Forgetting `expire`, `retry` or both doesn't compile.
+///
+/// ```rust,compile_fail
+/// use pushover_api::Message;
+///
+/// let message = Message::builder("application token", "user key", "Message")
+///   .priority_emergency()
+///   .retry(60)
+///   .build();
+/// ```
+///
+/// ```rust,compile_fail
+/// use pushover_api::Message;
+///
+/// let message = Message::builder("application token", "user key", "Message")
+///   .priority_emergency()
+///   .expire(3600)
+///   .build();
+/// ```
+///
+/// ```rust,compile_fail
+/// use pushover_api::Message;
+///
+/// let message = Message::builder("application token", "user key", "Message")
+///   .priority_emergency()
+///   .build();
+/// ```
+///
+/// Setting `retry` or `expire` twice doesn't compile either.
+///
+/// ```rust,compile_fail
+/// use pushover_api::Message;
+///
+/// let message = Message::builder("application token", "user key", "Message")
+///   .priority_emergency()
+///   .retry(60)
+///   .retry(120)
+///   .expire(3600)
+///   .build();
+/// ```
+#[derive(Clone, Debug)]
+pub struct EmergencyMessageBuilder<R = Missing, E = Missing> {
+  message: Message,
+  state: PhantomData<(R, E)>,
+}
+
+impl<E> EmergencyMessageBuilder<Missing, E> {
+  /// Sets how often (in seconds) the message is retried.
+  pub fn retry(mut self, retry: u32) -> EmergencyMessageBuilder<Provided, E> {
+    self.message.retry = Some(retry);
+    EmergencyMessageBuilder {
+      message: self.message,
+      state: PhantomData,
+    }
+  }
+}
+
+impl<R> EmergencyMessageBuilder<R, Missing> {
+  /// Sets how long (in seconds) the message is retried for.
+  pub fn expire(mut self, expire: u32) -> EmergencyMessageBuilder<R, Provided> {
+    self.message.expire = Some(expire);
+    EmergencyMessageBuilder {
+      message: self.message,
+      state: PhantomData,
+    }
+  }
+}
+
+impl<R, E> EmergencyMessageBuilder<R, E> {
+  /// Sets the URL to call when the message is acknowledged.
+  pub fn callback<S: Into<String>>(mut self, callback: S) -> Self {
+    self.message.callback = Some(callback.into());
+    self
+  }
+
+  /// Sets the tags of the message.
+  pub fn tags<I, S>(mut self, tags: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    self.message.tags = tags.into_iter().map(Into::into).collect();
+    self
+  }
+}
+
+impl EmergencyMessageBuilder<Provided, Provided> {
+  /// [Validates](struct.Message.html#method.validate) and returns the message.
+  pub fn build(self) -> Result<Message, ValidationErrors> {
+    self.message.validate()?;
+    Ok(self.message)
+  }
+}
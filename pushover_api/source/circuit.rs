@@ -0,0 +1,141 @@
+//! Failing fast while the Pushover API is down.
+
+use crate::{Message, MessageResponse, PushoverError, Result};
+use std::{
+  sync::Mutex,
+  time::{Duration, Instant},
+};
+
+/// The state of a [`CircuitBreaker`](struct.CircuitBreaker.html).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CircuitState {
+  /// Requests are made as usual, counting the consecutive failures.
+  Closed {
+    /// The number of consecutive failures so far.
+    failures: u32,
+  },
+  /// Requests fail right away until the cool-down is over.
+  Open {
+    /// When the cool-down is over.
+    retry_at: Instant,
+  },
+  /// A single request is being made to probe whether the Pushover API is
+  /// back, others fail right away.
+  HalfOpen,
+}
+
+/// Stops making requests for a while after too many consecutive failures, so
+/// a long-running process doesn't wait for a timeout on every message while
+/// the Pushover API is down.
+///
+/// After `failure_threshold` consecutive failures the circuit opens and every
+/// request fails right away with a
+/// [`CircuitOpen`](enum.PushoverError.html#variant.CircuitOpen) error. Once
+/// the cool-down is over, the next request is let through to probe the
+/// Pushover API. If it succeeds the circuit closes again, otherwise it opens
+/// for another cool-down.
+///
+/// Only transient failures count, like timeouts and 5xx responses. The
+/// circuit breaker can be shared between threads.
+///
+/// ```rust
+/// use pushover_api::{CircuitBreaker, CircuitState, PushoverError};
+/// use std::time::Duration;
+///
+/// let breaker = CircuitBreaker::new(2, Duration::from_millis(10));
+/// let outage = || -> Result<(), _> {
+///   Err(PushoverError::RateLimited {
+///     retry_after: None,
+///     reset_at: None,
+///     errors: vec![],
+///     request: None,
+///     extras: Default::default(),
+///     body: String::new(),
+///   })
+/// };
+///
+/// assert!(breaker.call(outage).is_err());
+/// assert!(breaker.call(outage).is_err());
+///
+/// // The circuit is open, so this fails without being called.
+/// let error = breaker.call(|| Ok(())).unwrap_err();
+/// assert!(matches!(error, PushoverError::CircuitOpen { .. }));
+///
+/// // After the cool-down, a successful probe closes the circuit.
+/// std::thread::sleep(Duration::from_millis(10));
+/// assert!(breaker.call(|| Ok(())).is_ok());
+/// assert_eq!(breaker.state(), CircuitState::Closed { failures: 0 });
+/// ```
+#[derive(Debug)]
+pub struct CircuitBreaker {
+  failure_threshold: u32,
+  cool_down: Duration,
+  state: Mutex<CircuitState>,
+}
+
+impl CircuitBreaker {
+  /// Creates a circuit breaker that opens for `cool_down` after
+  /// `failure_threshold` consecutive failures.
+  pub fn new(failure_threshold: u32, cool_down: Duration) -> Self {
+    Self {
+      failure_threshold: failure_threshold.max(1),
+      cool_down,
+      state: Mutex::new(CircuitState::Closed { failures: 0 }),
+    }
+  }
+
+  /// The current state of the circuit.
+  pub fn state(&self) -> CircuitState {
+    *self.state.lock().unwrap()
+  }
+
+  /// Calls `request` unless the circuit is open, recording whether it failed.
+  pub fn call<T>(&self, request: impl FnOnce() -> Result<T>) -> Result<T> {
+    {
+      let mut state = self.state.lock().unwrap();
+      match *state {
+        CircuitState::Closed { .. } => {}
+        CircuitState::Open { retry_at } if Instant::now() >= retry_at => {
+          *state = CircuitState::HalfOpen;
+        }
+        CircuitState::Open { retry_at } => {
+          return Err(PushoverError::CircuitOpen { retry_at });
+        }
+        CircuitState::HalfOpen => {
+          return Err(PushoverError::CircuitOpen {
+            retry_at: Instant::now() + self.cool_down,
+          });
+        }
+      }
+    }
+
+    let result = request();
+    let mut state = self.state.lock().unwrap();
+    *state = match (&result, *state) {
+      (Err(error), CircuitState::Closed { failures })
+        if error.is_retryable() =>
+      {
+        if failures + 1 >= self.failure_threshold {
+          CircuitState::Open {
+            retry_at: Instant::now() + self.cool_down,
+          }
+        } else {
+          CircuitState::Closed {
+            failures: failures + 1,
+          }
+        }
+      }
+      (Err(error), _) if error.is_retryable() => CircuitState::Open {
+        retry_at: Instant::now() + self.cool_down,
+      },
+      _ => CircuitState::Closed { failures: 0 },
+    };
+
+    result
+  }
+
+  /// Sends a message unless the circuit is open.
+  pub fn send(&self, message: &Message) -> Result<MessageResponse> {
+    self.call(|| message.send())
+  }
+}
@@ -0,0 +1,851 @@
+//! A client that remembers the application token and message defaults.
+
+use crate::{
+  api_base, build_client, check_base_url, check_proxy_env, client_builder,
+  sound, CircuitBreaker, Deduplicator, HttpClient, Message, MessagePriority,
+  MessageResponse, PushoverError, Result, RetryPolicy, Secret, Sound,
+  Transport, UserAgent, DEFAULT_CONNECT_TIMEOUT, DEFAULT_TIMEOUT,
+};
+use std::{borrow::Cow, sync::Arc, time::Duration};
+
+/// A client for one application, which fills in its token, default user and
+/// other defaults on every message it sends.
+///
+/// Every client has its own HTTP client, so applications with different
+/// tokens can be used side by side. Cloning a client is cheap and the clones
+/// share their HTTP client and circuit breaker.
+///
+/// Requests are sent with Reqwest, or ureq with the `ureq` feature, unless
+/// the client is created with another [`Transport`](trait.Transport.html)
+/// like the [`FakeTransport`](test_support/struct.FakeTransport.html) for
+/// tests.
+///
+/// The standalone [`Message::send()`](struct.Message.html#method.send) keeps
+/// working for messages that have their token and user set.
+///
+/// ```rust,no_run
+/// use pushover_api::{Message, PushoverClient, RetryPolicy, Sound};
+///
+/// let client = PushoverClient::try_new("application token")
+///   .unwrap()
+///   .default_user("user key")
+///   .default_sound(Sound::Magic)
+///   .default_title("Backups")
+///   .retry_policy(RetryPolicy::default());
+///
+/// client.notify("The backup finished").unwrap();
+///
+/// client
+///   .send(&Message {
+///     title: Some("Backup".to_string()),
+///     message: "The backup finished".to_string(),
+///     ..Message::default()
+///   })
+///   .unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct PushoverClient<T = HttpClient> {
+  token: Secret,
+  default_user: Option<Secret>,
+  default_device: Vec<String>,
+  default_title: Option<String>,
+  default_priority: Option<MessagePriority>,
+  default_sound: Option<Sound>,
+  pub(crate) transport: T,
+  pub(crate) base_url: String,
+  retry_policy: RetryPolicy,
+  circuit_breaker: Option<Arc<CircuitBreaker>>,
+  dedup: Option<Arc<Deduplicator>>,
+  pub(crate) sounds_cache: Option<Arc<sound::SoundsCache>>,
+}
+
+impl PushoverClient<HttpClient> {
+  /// Creates a client like [`try_new()`](#method.try_new), but panics when
+  /// the HTTP client can't be created.
+  #[deprecated(
+    note = "Panics when the HTTP client can't be created, use `try_new` instead"
+  )]
+  pub fn new<S: Into<String>>(token: S) -> Self {
+    match Self::try_new(token) {
+      Ok(client) => client,
+      Err(error) => panic!("Failed to create the HTTP client: {}", error),
+    }
+  }
+
+  /// Creates a client for the application with the given token. It uses the
+  /// [default timeouts](constant.DEFAULT_TIMEOUT.html) and doesn't retry.
+  ///
+  /// Returns an error when the HTTP client can't be created. This happens when
+  /// the TLS backend can't be initialized or a proxy environment variable like
+  /// `HTTPS_PROXY` isn't a valid URL.
+  ///
+  /// ```rust
+  /// use pushover_api::{Message, PushoverClient, PushoverError};
+  ///
+  /// std::env::set_var("HTTPS_PROXY", "ftp://proxy.example");
+  ///
+  /// let error = PushoverClient::try_new("application token").unwrap_err();
+  /// assert!(matches!(error, PushoverError::InvalidArgument(_)));
+  /// assert!(error.to_string().contains("HTTPS_PROXY"));
+  ///
+  /// let message = Message {
+  ///   token: "application token".into(),
+  ///   user: "user key".into(),
+  ///   message: "Message".to_string(),
+  ///   ..Message::default()
+  /// };
+  /// assert!(matches!(
+  ///   message.send(),
+  ///   Err(PushoverError::InvalidArgument(_))
+  /// ));
+  /// ```
+  pub fn try_new<S: Into<String>>(token: S) -> Result<Self> {
+    let http = build_client(DEFAULT_CONNECT_TIMEOUT, DEFAULT_TIMEOUT)?;
+    Ok(Self::from_client(token, http))
+  }
+
+  /// Creates a builder for a client with its own HTTP settings, like timeouts
+  /// and a proxy.
+  pub fn builder<S: Into<String>>(token: S) -> PushoverClientBuilder {
+    PushoverClientBuilder::new(Secret::new(token))
+  }
+
+  /// Creates a client for the application with the given token that uses
+  /// your own Reqwest client, or ureq agent with the `ureq` feature, for
+  /// example one with custom TLS settings. The timeouts of that client are
+  /// used.
+  pub fn from_client<S: Into<String>>(token: S, http: HttpClient) -> Self {
+    Self::with_transport(token, http)
+  }
+}
+
+/// How a [`PushoverClientBuilder`](struct.PushoverClientBuilder.html) uses
+/// proxies.
+#[derive(Clone, Debug)]
+enum ProxySetting {
+  /// Use the proxies from the environment, like `HTTPS_PROXY`.
+  Environment,
+  /// Use the proxy with this URL for all requests.
+  Url(String),
+  /// Don't use any proxies, even when they're set in the environment.
+  Disabled,
+}
+
+/// A builder for a [`PushoverClient`](struct.PushoverClient.html) with its
+/// own HTTP settings.
+///
+/// ```rust
+/// use pushover_api::{PushoverClient, UserAgent};
+/// use std::time::Duration;
+///
+/// # let server = pushover_api::test_support::MockServer::respond(vec![]);
+/// # let proxy = format!("http://{}", server.address());
+/// let client = PushoverClient::builder("application token")
+///   .timeout(Duration::from_secs(10))
+///   .user_agent(UserAgent::new("Backups/1.2").unwrap())
+///   .proxy(&proxy)
+///   .proxy_credentials("proxy user", "proxy password")
+///   .build()
+///   .unwrap()
+///   .default_user("user key")
+///   .base_url("http://api.pushover.example/1")
+///   .unwrap();
+///
+/// client.notify("Sent through a proxy").unwrap();
+/// # let request = &server.requests()[0];
+/// # assert_eq!(request.target, "http://api.pushover.example/1/messages.json");
+/// # assert!(request.header("proxy-authorization").unwrap().starts_with("Basic "));
+/// # assert_eq!(
+/// #   request.header("user-agent"),
+/// #   Some("Backups/1.2 Rust Pushover API Library")
+/// # );
+/// ```
+#[derive(Clone, Debug)]
+pub struct PushoverClientBuilder {
+  token: Secret,
+  connect_timeout: Duration,
+  timeout: Duration,
+  user_agent: Option<UserAgent>,
+  proxy: ProxySetting,
+  proxy_credentials: Option<(String, Secret)>,
+  defaults: MessageDefaults,
+}
+
+/// The message defaults of a
+/// [`PushoverClientBuilder`](struct.PushoverClientBuilder.html), which are
+/// passed on to the client it builds.
+#[derive(Clone, Debug, Default)]
+struct MessageDefaults {
+  user: Option<Secret>,
+  device: Vec<String>,
+  title: Option<String>,
+  priority: Option<MessagePriority>,
+  sound: Option<Sound>,
+}
+
+impl MessageDefaults {
+  /// Sets these defaults on a client.
+  fn apply<T: Transport>(self, client: PushoverClient<T>) -> PushoverClient<T> {
+    PushoverClient {
+      default_user: self.user,
+      default_device: self.device,
+      default_title: self.title,
+      default_priority: self.priority,
+      default_sound: self.sound,
+      ..client
+    }
+  }
+}
+
+impl PushoverClientBuilder {
+  /// Creates a builder with the default timeouts and the proxies from the
+  /// environment.
+  fn new(token: Secret) -> Self {
+    Self {
+      token,
+      connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+      timeout: DEFAULT_TIMEOUT,
+      user_agent: None,
+      proxy: ProxySetting::Environment,
+      proxy_credentials: None,
+      defaults: MessageDefaults::default(),
+    }
+  }
+
+  /// Sets how long to wait for a connection to the Pushover API, the default
+  /// is [`DEFAULT_CONNECT_TIMEOUT`](constant.DEFAULT_CONNECT_TIMEOUT.html).
+  pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+    self.connect_timeout = timeout;
+    self
+  }
+
+  /// Sets how long to wait for a whole request, the default is
+  /// [`DEFAULT_TIMEOUT`](constant.DEFAULT_TIMEOUT.html).
+  pub fn timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = timeout;
+    self
+  }
+
+  /// Sets the user agent of this client's requests, the default is the one
+  /// set with [`set_user_agent()`](fn.set_user_agent.html) or the library's
+  /// identifier.
+  pub fn user_agent(mut self, user_agent: UserAgent) -> Self {
+    self.user_agent = Some(user_agent);
+    self
+  }
+
+  /// Sends all requests through the proxy with the given URL.
+  pub fn proxy(mut self, url: &str) -> Self {
+    self.proxy = ProxySetting::Url(url.to_string());
+    self
+  }
+
+  /// Sets the username and password for the proxy set with
+  /// [`proxy()`](#method.proxy), sent with basic authentication. They can be
+  /// set before or after the proxy, but [`build()`](#method.build) returns an
+  /// error when there's no proxy URL to use them with.
+  ///
+  /// ```rust
+  /// use pushover_api::{PushoverClient, PushoverError};
+  ///
+  /// # let server = pushover_api::test_support::MockServer::respond(vec![]);
+  /// # let proxy = format!("http://{}", server.address());
+  /// let client = PushoverClient::builder("application token")
+  ///   .proxy_credentials("proxy user", "proxy password")
+  ///   .proxy(&proxy)
+  ///   .build()
+  ///   .unwrap()
+  ///   .default_user("user key")
+  ///   .base_url("http://api.pushover.example/1")
+  ///   .unwrap();
+  /// client.notify("Sent through a proxy").unwrap();
+  /// # let request = &server.requests()[0];
+  /// # assert!(request.header("proxy-authorization").unwrap().starts_with("Basic "));
+  ///
+  /// for builder in vec![
+  ///   PushoverClient::builder("application token"),
+  ///   PushoverClient::builder("application token").no_proxy(),
+  /// ] {
+  ///   let error = builder
+  ///     .proxy_credentials("proxy user", "proxy password")
+  ///     .build()
+  ///     .unwrap_err();
+  ///   assert!(matches!(error, PushoverError::InvalidArgument(_)));
+  /// }
+  /// ```
+  pub fn proxy_credentials(mut self, username: &str, password: &str) -> Self {
+    self.proxy_credentials = Some((username.to_string(), password.into()));
+    self
+  }
+
+  /// Doesn't use any proxies, even when they're set in the environment with
+  /// variables like `HTTPS_PROXY`.
+  ///
+  /// ```rust
+  /// use pushover_api::PushoverClient;
+  ///
+  /// # use pushover_api::test_support::MockServer;
+  /// # let (api, proxy) = (MockServer::respond(vec![]), MockServer::respond(vec![]));
+  /// # let base_url = api.url();
+  /// # for name in &["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy"] {
+  /// #   std::env::set_var(name, format!("http://{}", proxy.address()));
+  /// # }
+  /// let client = PushoverClient::builder("application token")
+  ///   .no_proxy()
+  ///   .build()
+  ///   .unwrap()
+  ///   .default_user("user key")
+  ///   .base_url(&base_url)
+  ///   .unwrap();
+  /// client.notify("Sent directly").unwrap();
+  /// # assert_eq!((api.requests().len(), proxy.requests().len()), (1, 0));
+  /// #
+  /// # // Without it, the proxy from the environment is used.
+  /// # PushoverClient::builder("application token")
+  /// #   .build()
+  /// #   .unwrap()
+  /// #   .default_user("user key")
+  /// #   .base_url(&base_url)
+  /// #   .unwrap()
+  /// #   .notify("Sent through a proxy")
+  /// #   .unwrap();
+  /// # assert_eq!((api.requests().len(), proxy.requests().len()), (1, 1));
+  /// ```
+  pub fn no_proxy(mut self) -> Self {
+    self.proxy = ProxySetting::Disabled;
+    self
+  }
+
+  /// Sets the user or group key to send messages to when they don't have a
+  /// user of their own, see
+  /// [`PushoverClient::default_user()`](struct.PushoverClient.html#method.default_user).
+  ///
+  /// The defaults can be set on the builder together with the HTTP settings,
+  /// or on the client after it's built.
+  ///
+  /// ```rust
+  /// use pushover_api::{MessagePriority, PushoverClient, Sound};
+  /// use std::time::Duration;
+  ///
+  /// # let server = pushover_api::test_support::MockServer::respond(vec![]);
+  /// let client = PushoverClient::builder("application token")
+  ///   .timeout(Duration::from_secs(10))
+  ///   .no_proxy()
+  ///   .default_user("user key")
+  ///   .default_device(vec!["phone"])
+  ///   .default_title("Backups")
+  ///   .default_priority(MessagePriority::Low)
+  ///   .default_sound(Sound::Bike)
+  ///   .build()
+  ///   .unwrap();
+  /// # let client = client.base_url(&server.url()).unwrap();
+  /// assert_eq!(client.user(), Some("user key"));
+  ///
+  /// client.notify("The backup finished").unwrap();
+  /// # let body = server.requests()[0].json();
+  /// # assert_eq!(body["user"], "user key");
+  /// # assert_eq!(body["device"], "phone");
+  /// # assert_eq!(body["title"], "Backups");
+  /// # assert_eq!(body["priority"], -1);
+  /// # assert_eq!(body["sound"], "bike");
+  /// ```
+  pub fn default_user<S: Into<String>>(mut self, user: S) -> Self {
+    self.defaults.user = Some(Secret::new(user));
+    self
+  }
+
+  /// Sets the devices to send messages to when they don't have any devices
+  /// of their own, see
+  /// [`PushoverClient::default_device()`](struct.PushoverClient.html#method.default_device).
+  pub fn default_device<I, S>(mut self, devices: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    self.defaults.device = devices.into_iter().map(Into::into).collect();
+    self
+  }
+
+  /// Sets the title of messages that don't have a title of their own.
+  pub fn default_title<S: Into<String>>(mut self, title: S) -> Self {
+    self.defaults.title = Some(title.into());
+    self
+  }
+
+  /// Sets the priority of messages that don't have a priority of their own,
+  /// see
+  /// [`PushoverClient::default_priority()`](struct.PushoverClient.html#method.default_priority).
+  pub fn default_priority(mut self, priority: MessagePriority) -> Self {
+    self.defaults.priority = Some(priority);
+    self
+  }
+
+  /// Sets the sound of messages that don't have a sound of their own.
+  pub fn default_sound<S: Into<Sound>>(mut self, sound: S) -> Self {
+    self.defaults.sound = Some(sound.into());
+    self
+  }
+
+  /// Returns an error when proxy credentials are set without a proxy URL,
+  /// since there'd be nothing to send them to.
+  fn check_proxy_credentials(&self) -> Result<()> {
+    match (&self.proxy, &self.proxy_credentials) {
+      (ProxySetting::Url(_), _) | (_, None) => Ok(()),
+      (_, Some(_)) => Err(PushoverError::InvalidArgument(
+        "Proxy credentials require a proxy URL set with proxy()".to_string(),
+      )),
+    }
+  }
+
+  /// Creates the client, returning an error when the proxy URL is invalid,
+  /// proxy credentials are set without a proxy URL, or the HTTP client can't
+  /// be created.
+  #[cfg(all(feature = "blocking", not(feature = "ureq")))]
+  pub fn build(self) -> Result<PushoverClient> {
+    self.check_proxy_credentials()?;
+    let user_agent = self.user_agent.unwrap_or_else(UserAgent::current_default);
+    let builder =
+      client_builder(self.connect_timeout, self.timeout, &user_agent);
+    let builder = match self.proxy {
+      ProxySetting::Environment => {
+        check_proxy_env()?;
+        builder
+      }
+      ProxySetting::Url(url) => {
+        let proxy = reqwest::Proxy::all(&url)?;
+        let proxy = match self.proxy_credentials {
+          Some((username, password)) => {
+            proxy.basic_auth(&username, password.expose_secret())
+          }
+          None => proxy,
+        };
+        builder.proxy(proxy)
+      }
+      ProxySetting::Disabled => builder.no_proxy(),
+    };
+
+    let client =
+      PushoverClient::from_client(self.token.expose_secret(), builder.build()?);
+    Ok(self.defaults.apply(client))
+  }
+
+  /// Creates the client, returning an error when the proxy URL is invalid or
+  /// proxy credentials are set without a proxy URL.
+  #[cfg(feature = "ureq")]
+  // The middleware has to return ureq's own error, which is large.
+  #[allow(clippy::result_large_err)]
+  pub fn build(self) -> Result<PushoverClient> {
+    self.check_proxy_credentials()?;
+    let user_agent = self.user_agent.unwrap_or_else(UserAgent::current_default);
+    let builder =
+      client_builder(self.connect_timeout, self.timeout, &user_agent);
+    let builder = match self.proxy {
+      ProxySetting::Environment => {
+        check_proxy_env()?;
+        builder
+      }
+      ProxySetting::Url(url) => {
+        // ureq takes the credentials as part of the proxy URL, but only sends
+        // them when tunneling HTTPS, so plain HTTP requests get the header
+        // from a middleware.
+        let (url, authorization) = match self.proxy_credentials {
+          Some((username, password)) => {
+            let credentials =
+              format!("{}:{}", username, password.expose_secret());
+            let authorization =
+              format!("Basic {}", base64::encode(&credentials));
+            let url = match url.split_once("://") {
+              Some((scheme, rest)) => {
+                format!("{}://{}@{}", scheme, credentials, rest)
+              }
+              None => format!("{}@{}", credentials, url),
+            };
+            (url, Some(Secret::new(authorization)))
+          }
+          None => (url, None),
+        };
+        let proxy = ureq::Proxy::new(&url).map_err(|error| {
+          PushoverError::InvalidArgument(format!(
+            "Invalid proxy URL: {}",
+            error.kind()
+          ))
+        })?;
+        let builder = builder.try_proxy_from_env(false).proxy(proxy);
+
+        match authorization {
+          Some(authorization) => builder.middleware(
+            move |request: ureq::Request, next: ureq::MiddlewareNext| {
+              if request.url().starts_with("http://") {
+                next.handle(
+                  request
+                    .set("proxy-authorization", authorization.expose_secret()),
+                )
+              } else {
+                next.handle(request)
+              }
+            },
+          ),
+          None => builder,
+        }
+      }
+      ProxySetting::Disabled => builder.try_proxy_from_env(false),
+    };
+
+    let client =
+      PushoverClient::from_client(self.token.expose_secret(), builder.build());
+    Ok(self.defaults.apply(client))
+  }
+}
+
+impl<T: Transport> PushoverClient<T> {
+  /// Creates a client for the application with the given token that sends
+  /// its requests with `transport`.
+  pub fn with_transport<S: Into<String>>(token: S, transport: T) -> Self {
+    Self {
+      token: Secret::new(token),
+      default_user: None,
+      default_device: vec![],
+      default_title: None,
+      default_priority: None,
+      default_sound: None,
+      transport,
+      base_url: api_base(),
+      retry_policy: RetryPolicy::never(),
+      circuit_breaker: None,
+      dedup: None,
+      sounds_cache: None,
+    }
+  }
+
+  /// Sets the user or group key to send messages to when they don't have a
+  /// user of their own.
+  pub fn default_user<S: Into<String>>(mut self, user: S) -> Self {
+    self.default_user = Some(Secret::new(user));
+    self
+  }
+
+  /// Sets the devices to send messages to when they don't have any devices
+  /// of their own.
+  ///
+  /// Like the other defaults, it's only used for messages that leave the
+  /// field empty, a message's own value always wins.
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, Message, MessagePriority, PushoverClient,
+  ///   Sound,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853c"}"#);
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853d"}"#);
+  ///
+  /// let client = PushoverClient::with_transport("token", &transport)
+  ///   .default_user("user key")
+  ///   .default_device(vec!["phone", "desktop"])
+  ///   .default_title("Backups")
+  ///   .default_priority(MessagePriority::High)
+  ///   .default_sound(Sound::Magic);
+  ///
+  /// client.notify("The backup finished").unwrap();
+  /// client
+  ///   .send(&Message {
+  ///     device: vec!["watch".to_string()],
+  ///     title: Some("Restore".to_string()),
+  ///     priority: Some(MessagePriority::Low),
+  ///     sound: Some(Sound::Bike),
+  ///     message: "The restore finished".to_string(),
+  ///     ..Message::default()
+  ///   })
+  ///   .unwrap();
+  ///
+  /// let requests = transport.requests();
+  /// let defaults = &requests[0];
+  /// assert_eq!(defaults.field("device").as_deref(), Some("phone,desktop"));
+  /// assert_eq!(defaults.field("title").as_deref(), Some("Backups"));
+  /// assert_eq!(defaults.field("priority").as_deref(), Some("1"));
+  /// assert_eq!(defaults.field("sound").as_deref(), Some("magic"));
+  ///
+  /// let overridden = &requests[1];
+  /// assert_eq!(overridden.field("device").as_deref(), Some("watch"));
+  /// assert_eq!(overridden.field("title").as_deref(), Some("Restore"));
+  /// assert_eq!(overridden.field("priority").as_deref(), Some("-1"));
+  /// assert_eq!(overridden.field("sound").as_deref(), Some("bike"));
+  /// ```
+  pub fn default_device<I, S>(mut self, devices: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    self.default_device = devices.into_iter().map(Into::into).collect();
+    self
+  }
+
+  /// Sets the title of messages that don't have a title of their own.
+  pub fn default_title<S: Into<String>>(mut self, title: S) -> Self {
+    self.default_title = Some(title.into());
+    self
+  }
+
+  /// Sets the priority of messages that don't have a priority of their own.
+  ///
+  /// With [`Emergency`](enum.MessagePriority.html#variant.Emergency) every
+  /// message needs its own `retry` and `expire`, otherwise it fails
+  /// validation.
+  pub fn default_priority(mut self, priority: MessagePriority) -> Self {
+    self.default_priority = Some(priority);
+    self
+  }
+
+  /// Sets the sound of messages that don't have a sound of their own.
+  pub fn default_sound<S: Into<Sound>>(mut self, sound: S) -> Self {
+    self.default_sound = Some(sound.into());
+    self
+  }
+
+  /// Sets how to retry messages that failed to send because of a transient
+  /// problem, see [`RetryPolicy`](struct.RetryPolicy.html).
+  pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+    self.retry_policy = policy;
+    self
+  }
+
+  /// Sets a circuit breaker to fail fast while the Pushover API is down, see
+  /// [`CircuitBreaker`](struct.CircuitBreaker.html).
+  pub fn circuit_breaker(mut self, breaker: CircuitBreaker) -> Self {
+    self.circuit_breaker = Some(Arc::new(breaker));
+    self
+  }
+
+  /// Drops messages that repeat a message sent within `window`, see
+  /// [`Deduplicator`](struct.Deduplicator.html). Repeats are returned as a
+  /// [`Suppressed`](enum.PushoverError.html#variant.Suppressed) error without
+  /// making a request, the same as
+  /// [`Deduplicator::send()`](struct.Deduplicator.html#method.send), and the
+  /// deduplicator is shared with the client's clones.
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, PushoverClient, PushoverError,
+  /// };
+  /// use std::time::Duration;
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(500, r#"{"status":0,"errors":["Server error"]}"#);
+  ///
+  /// let client = PushoverClient::with_transport("token", &transport)
+  ///   .default_user("user key")
+  ///   .with_dedup(Duration::from_secs(60));
+  ///
+  /// // Messages that fail to send aren't remembered.
+  /// assert!(client.notify("The disk is full").is_err());
+  /// client.notify("The disk is full").unwrap();
+  /// assert!(matches!(
+  ///   client.notify("The disk is full"),
+  ///   Err(PushoverError::Suppressed)
+  /// ));
+  /// assert!(matches!(
+  ///   client.clone().notify("The disk is full"),
+  ///   Err(PushoverError::Suppressed)
+  /// ));
+  /// client.notify("The disk is almost full").unwrap();
+  ///
+  /// assert_eq!(transport.requests().len(), 3);
+  /// ```
+  pub fn with_dedup(mut self, window: Duration) -> Self {
+    self.dedup = Some(Arc::new(Deduplicator::new(window)));
+    self
+  }
+
+  /// Caches the application's sounds for `ttl`, so
+  /// [`list_sounds()`](#method.list_sounds) and
+  /// [`is_sound_available()`](#method.is_sound_available) don't request them
+  /// every time. The cache is shared with the client's clones, and
+  /// [`DEFAULT_SOUNDS_CACHE_TTL`](constant.DEFAULT_SOUNDS_CACHE_TTL.html) is
+  /// a sensible TTL for sounds that rarely change. Sending messages doesn't
+  /// check their sounds either way.
+  pub fn cache_sounds(mut self, ttl: Duration) -> Self {
+    self.sounds_cache = Some(Arc::new(sound::SoundsCache::new(ttl)));
+    self
+  }
+
+  /// Sets the base URL for the Pushover API, for example to send messages to
+  /// a mock server or an internal relay. It defaults to
+  /// [`PUSHOVER_API`](constant.PUSHOVER_API.html), or the
+  /// [`PUSHOVER_API_URL_VAR`](constant.PUSHOVER_API_URL_VAR.html)
+  /// environment variable when it's set.
+  ///
+  /// Returns an error when the URL doesn't start with `http://` or
+  /// `https://`.
+  ///
+  /// ```rust
+  /// use pushover_api::PushoverClient;
+  ///
+  /// # use pushover_api::{test_support::MockServer, HttpResponse};
+  /// # let body = r#"{"status":1,"request":"5042853c"}"#;
+  /// # let server =
+  /// #   MockServer::respond(vec![HttpResponse::json(http::StatusCode::OK, body)]);
+  /// # let address = server.address();
+  /// let client = PushoverClient::try_new("application token")
+  ///   .unwrap()
+  ///   .default_user("user key")
+  ///   .base_url(&format!("http://{}/1", address))
+  ///   .unwrap();
+  ///
+  /// let response = client.notify("Hello from a mock server").unwrap();
+  /// assert_eq!(response.request.as_str(), "5042853c");
+  ///
+  /// let client = PushoverClient::try_new("token").unwrap();
+  /// assert!(client.base_url("localhost:8080").is_err());
+  /// ```
+  pub fn base_url(mut self, url: &str) -> Result<Self> {
+    self.base_url = check_base_url(url)?;
+    Ok(self)
+  }
+
+  /// Returns the application token.
+  pub fn token(&self) -> &str {
+    self.token.expose_secret()
+  }
+
+  /// Returns the default user or group key.
+  pub fn user(&self) -> Option<&str> {
+    self.default_user.as_ref().map(Secret::expose_secret)
+  }
+
+  /// Sends a message, filling in the token and the default user when the
+  /// message doesn't have them.
+  pub fn send(&self, message: &Message) -> Result<MessageResponse> {
+    let message = self.prepare(message);
+    if let Some(dedup) = &self.dedup {
+      if !dedup.should_send(&message) {
+        return Err(PushoverError::Suppressed);
+      }
+    }
+
+    let result = self.retry_policy.run(|| match &self.circuit_breaker {
+      Some(breaker) => {
+        breaker.call(|| message.send_using(&self.transport, &self.base_url))
+      }
+      None => message.send_using(&self.transport, &self.base_url),
+    });
+    if let (Some(dedup), Err(_)) = (&self.dedup, &result) {
+      dedup.forget(&message);
+    }
+
+    result
+  }
+
+  /// Sends a message to multiple users or groups in a single request, see
+  /// [`Message::send_to_users()`](struct.Message.html#method.send_to_users).
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, Message, PushoverClient, PushoverError,
+  ///   MAX_USERS_PER_MESSAGE,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853c"}"#);
+  ///
+  /// let client = PushoverClient::with_transport("token", &transport)
+  ///   .default_user("default user key");
+  /// let message = Message {
+  ///   message: "Message".to_string(),
+  ///   ..Message::default()
+  /// };
+  ///
+  /// client
+  ///   .send_to_users(&message, &["first key", " second key ", "group key"])
+  ///   .unwrap();
+  /// let requests = transport.requests();
+  /// assert_eq!(
+  ///   requests[0].field("user").as_deref(),
+  ///   Some("first key,second key,group key")
+  /// );
+  ///
+  /// let too_many = vec!["user key"; MAX_USERS_PER_MESSAGE + 1];
+  /// let all = vec!["user key"; MAX_USERS_PER_MESSAGE];
+  /// for users in &[&too_many[..], &["first key", " "], &["first,second"]] {
+  ///   let error = client.send_to_users(&message, users).unwrap_err();
+  ///   assert!(matches!(error, PushoverError::InvalidArgument(_)));
+  /// }
+  /// client.send_to_users(&message, &all).unwrap();
+  ///
+  /// let requests = transport.requests();
+  /// assert_eq!(requests.len(), 2);
+  /// let users = requests[1].field("user").unwrap();
+  /// assert_eq!(users.split(',').count(), MAX_USERS_PER_MESSAGE);
+  /// ```
+  pub fn send_to_users(
+    &self,
+    message: &Message,
+    users: &[&str],
+  ) -> Result<MessageResponse> {
+    self.send(&message.for_users(users)?)
+  }
+
+  /// Sends a message with only text to the default user.
+  ///
+  /// Returns an error without making a request when there is no default user.
+  pub fn notify(&self, text: &str) -> Result<MessageResponse> {
+    if self.default_user.is_none() {
+      return Err(PushoverError::InvalidArgument(
+        "Notifying requires a default user to be set".to_string(),
+      ));
+    }
+
+    self.send(&Message {
+      message: text.to_string(),
+      ..Message::default()
+    })
+  }
+
+  /// Fills in the token, default user and other defaults of a message when
+  /// its fields are empty.
+  pub(crate) fn prepare<'a>(&self, message: &'a Message) -> Cow<'a, Message> {
+    let needs_user = message.user.is_empty() && self.default_user.is_some();
+    let needs_device =
+      message.device.is_empty() && !self.default_device.is_empty();
+    let needs_title = message.title.is_none() && self.default_title.is_some();
+    let needs_priority =
+      message.priority.is_none() && self.default_priority.is_some();
+    let needs_sound = message.sound.is_none() && self.default_sound.is_some();
+    if !message.token.is_empty()
+      && !needs_user
+      && !needs_device
+      && !needs_title
+      && !needs_priority
+      && !needs_sound
+    {
+      return Cow::Borrowed(message);
+    }
+
+    let mut message = message.clone();
+    if message.token.is_empty() {
+      message.token = self.token.clone();
+    }
+
+    if let (true, Some(user)) = (message.user.is_empty(), &self.default_user) {
+      message.user = user.clone();
+    }
+
+    if needs_device {
+      message.device = self.default_device.clone();
+    }
+
+    if needs_title {
+      message.title = self.default_title.clone();
+    }
+
+    if needs_priority {
+      message.priority = self.default_priority;
+    }
+
+    if needs_sound {
+      message.sound = self.default_sound.clone();
+    }
+
+    Cow::Owned(message)
+  }
+}
@@ -0,0 +1,50 @@
+//! Serde helpers for fields that the Pushover API expects as a single
+//! comma-separated string but are more convenient as a list in Rust.
+
+use serde::{Deserialize, Deserializer, Serializer};
+
+/// Serializes a list of strings by joining them with commas.
+pub(crate) fn serialize<S, T>(
+  values: &[T],
+  serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+  S: Serializer,
+  T: AsRef<str>,
+{
+  let values = values.iter().map(AsRef::as_ref).collect::<Vec<&str>>();
+  serializer.serialize_str(&values.join(","))
+}
+
+/// Deserializes a comma-separated string or a list of strings into a list,
+/// ignoring any empty values. A `null` value results in an empty list.
+pub(crate) fn deserialize<'de, D>(
+  deserializer: D,
+) -> Result<Vec<String>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  #[derive(Deserialize)]
+  #[serde(untagged)]
+  enum StringOrList {
+    String(String),
+    List(Vec<String>),
+  }
+
+  let values = match Option::<StringOrList>::deserialize(deserializer)? {
+    Some(StringOrList::String(joined)) => {
+      joined.split(',').map(String::from).collect()
+    }
+    Some(StringOrList::List(values)) => values,
+    None => vec![],
+  };
+
+  Ok(
+    values
+      .iter()
+      .map(|value| value.trim())
+      .filter(|value| !value.is_empty())
+      .map(String::from)
+      .collect(),
+  )
+}
@@ -0,0 +1,74 @@
+//! Rendering messages as curl commands.
+
+use crate::{api_url, form_fields, Message, Result};
+
+/// The value used in place of the token when it's redacted.
+const REDACTED: &str = "REDACTED";
+
+/// Quotes a value for use as a single shell argument.
+fn shell_quote(value: &str) -> String {
+  format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+impl Message {
+  /// Renders the curl command that makes the same request as
+  /// [`send()`](#method.send) would, to debug or reproduce API errors.
+  ///
+  /// When `redact_token` is true, the token is replaced with `REDACTED` so
+  /// the command can be shared in bug reports. Messages with an attachment
+  /// are rendered as a `multipart/form-data` request, using `--form-string`
+  /// for the fields so curl doesn't treat values starting with `@` as files.
+  ///
+  /// ```rust
+  /// use pushover_api::Message;
+  ///
+  /// let message = Message {
+  ///   token: "application token".into(),
+  ///   user: "user key".into(),
+  ///   message: "It's working!".to_string(),
+  ///   ..Message::default()
+  /// };
+  ///
+  /// assert_eq!(
+  ///   message.to_curl(true).unwrap(),
+  ///   "curl -X POST 'https://api.pushover.net/1/messages.json' \
+  ///     -H 'Content-Type: application/json' \
+  ///     -d '{\"token\":\"REDACTED\",\"user\":\"user key\",\
+  ///     \"message\":\"It'\\''s working!\"}'"
+  /// );
+  /// ```
+  pub fn to_curl(&self, redact_token: bool) -> Result<String> {
+    let mut message = self.clone();
+    if redact_token {
+      message.token = REDACTED.into();
+    }
+
+    let mut arguments = vec![
+      "curl -X POST".to_string(),
+      shell_quote(&api_url("messages.json")),
+    ];
+
+    match &message.attachment {
+      Some(attachment) => {
+        for (key, value) in form_fields(&message)? {
+          arguments.push(format!(
+            "--form-string {}",
+            shell_quote(&format!("{}={}", key, value))
+          ));
+        }
+
+        arguments
+          .push(format!("-F {}", shell_quote(&attachment.to_curl_field())));
+      }
+      None => {
+        arguments.push(format!(
+          "-H {}",
+          shell_quote("Content-Type: application/json")
+        ));
+        arguments.push(format!("-d {}", shell_quote(&message.to_json()?)));
+      }
+    }
+
+    Ok(arguments.join(" "))
+  }
+}
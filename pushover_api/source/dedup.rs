@@ -0,0 +1,189 @@
+//! Suppressing repeated identical messages.
+
+use crate::Message;
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+use crate::{MessageResponse, PushoverError, Result};
+use std::{
+  collections::{hash_map::DefaultHasher, HashMap},
+  hash::{Hash, Hasher},
+  sync::Mutex,
+  time::{Duration, Instant},
+};
+
+/// The default maximum number of messages a
+/// [`Deduplicator`](struct.Deduplicator.html) remembers.
+pub const DEFAULT_DEDUP_CAPACITY: usize = 1024;
+
+/// Drops messages that repeat a message sent within a time window, for
+/// alerts that can fire many times in a row.
+///
+/// Messages are considered identical when their user, title and message are
+/// the same. The deduplicator can be shared between threads and remembers at
+/// most [`DEFAULT_DEDUP_CAPACITY`](constant.DEFAULT_DEDUP_CAPACITY.html)
+/// messages unless configured otherwise, forgetting the oldest ones first.
+///
+/// ```rust
+/// use pushover_api::{Deduplicator, Message};
+/// use std::time::Duration;
+///
+/// let dedup = Deduplicator::new(Duration::from_secs(60));
+/// let message = Message {
+///   user: "user key".into(),
+///   message: "The disk is full".to_string(),
+///   ..Message::default()
+/// };
+///
+/// assert!(dedup.should_send(&message));
+/// assert!(!dedup.should_send(&message));
+///
+/// let different = Message {
+///   message: "The disk is almost full".to_string(),
+///   ..message
+/// };
+/// assert!(dedup.should_send(&different));
+/// ```
+///
+/// Repeats are sent again once the window has passed, and when more messages
+/// than the capacity are remembered the oldest one is forgotten:
+///
+/// ```rust
+/// use pushover_api::{Deduplicator, Message};
+/// use std::{thread, time::Duration};
+///
+/// let message = |text: &str| Message {
+///   message: text.to_string(),
+///   ..Message::default()
+/// };
+///
+/// let dedup = Deduplicator::new(Duration::from_millis(50));
+/// assert!(dedup.should_send(&message("Expiring")));
+/// assert!(!dedup.should_send(&message("Expiring")));
+/// thread::sleep(Duration::from_millis(100));
+/// assert!(dedup.should_send(&message("Expiring")));
+///
+/// let dedup = Deduplicator::with_capacity(Duration::from_secs(60), 2);
+/// assert!(dedup.should_send(&message("First")));
+/// thread::sleep(Duration::from_millis(1));
+/// assert!(dedup.should_send(&message("Second")));
+/// thread::sleep(Duration::from_millis(1));
+/// assert!(dedup.should_send(&message("Third")));
+///
+/// // "First" was the oldest and got evicted for "Third".
+/// assert!(!dedup.should_send(&message("Third")));
+/// assert!(dedup.should_send(&message("First")));
+///
+/// // A capacity of 0 is treated as 1.
+/// let dedup = Deduplicator::with_capacity(Duration::from_secs(60), 0);
+/// assert!(dedup.should_send(&message("First")));
+/// assert!(!dedup.should_send(&message("First")));
+/// thread::sleep(Duration::from_millis(1));
+/// assert!(dedup.should_send(&message("Second")));
+/// assert!(dedup.should_send(&message("First")));
+/// ```
+#[derive(Debug)]
+pub struct Deduplicator {
+  window: Duration,
+  capacity: usize,
+  seen: Mutex<HashMap<u64, Instant>>,
+}
+
+impl Deduplicator {
+  /// Creates a deduplicator that suppresses repeats within `window`.
+  pub fn new(window: Duration) -> Self {
+    Self::with_capacity(window, DEFAULT_DEDUP_CAPACITY)
+  }
+
+  /// Creates a deduplicator that suppresses repeats within `window` and
+  /// remembers at most `capacity` messages. A capacity of 0 is treated as 1,
+  /// so the last message is always remembered.
+  pub fn with_capacity(window: Duration, capacity: usize) -> Self {
+    Self {
+      window,
+      capacity: capacity.max(1),
+      seen: Mutex::new(HashMap::new()),
+    }
+  }
+
+  /// Checks whether a message should be sent, remembering it if so.
+  pub fn should_send(&self, message: &Message) -> bool {
+    let key = Self::key(message);
+    let now = Instant::now();
+    let mut seen = self.seen.lock().unwrap();
+
+    let window = self.window;
+    seen.retain(|_, sent_at| now.duration_since(*sent_at) < window);
+    if seen.contains_key(&key) {
+      return false;
+    }
+
+    if seen.len() >= self.capacity {
+      let oldest = seen
+        .iter()
+        .min_by_key(|(_, sent_at)| **sent_at)
+        .map(|(key, _)| *key);
+      if let Some(oldest) = oldest {
+        seen.remove(&oldest);
+      }
+    }
+
+    seen.insert(key, now);
+    true
+  }
+
+  /// Sends a message unless it repeats one sent within the window, which is
+  /// returned as a [`Suppressed`](enum.PushoverError.html#variant.Suppressed)
+  /// error without making a request, like a client
+  /// [with deduplication](struct.PushoverClient.html#method.with_dedup) does.
+  ///
+  /// Messages that fail to send are forgotten, so sending them again isn't
+  /// suppressed.
+  ///
+  /// ```rust
+  /// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+  /// # {
+  /// use pushover_api::{Deduplicator, Message, PushoverError};
+  /// use std::time::Duration;
+  ///
+  /// # pushover_api::test_support::MockServer::respond(vec![]).set_api_url_var();
+  /// let dedup = Deduplicator::new(Duration::from_secs(60));
+  /// let message = Message {
+  ///   token: "application token".into(),
+  ///   user: "user key".into(),
+  ///   message: "The disk is full".to_string(),
+  ///   ..Message::default()
+  /// };
+  ///
+  /// dedup.send(&message).unwrap();
+  /// assert!(matches!(
+  ///   dedup.send(&message),
+  ///   Err(PushoverError::Suppressed)
+  /// ));
+  /// # }
+  /// ```
+  #[cfg(any(feature = "blocking", feature = "ureq"))]
+  pub fn send(&self, message: &Message) -> Result<MessageResponse> {
+    if !self.should_send(message) {
+      return Err(PushoverError::Suppressed);
+    }
+
+    let result = message.send();
+    if result.is_err() {
+      self.forget(message);
+    }
+
+    result
+  }
+
+  /// Forgets a message, so sending it again isn't suppressed.
+  #[cfg(any(feature = "blocking", feature = "ureq"))]
+  pub(crate) fn forget(&self, message: &Message) {
+    self.seen.lock().unwrap().remove(&Self::key(message));
+  }
+
+  /// Hashes the fields that make messages identical.
+  fn key(message: &Message) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (&message.user, &message.title, &message.message).hash(&mut hasher);
+    hasher.finish()
+  }
+}
@@ -0,0 +1,46 @@
+//! Serde helpers for Unix timestamps where `0` means there is no timestamp.
+
+use serde::{de::Error, Deserialize, Deserializer};
+
+/// Deserializes an optional Unix timestamp from an integer or a string of
+/// one, where `0` and `null` result in `None`.
+pub(crate) fn deserialize<'de, D>(
+  deserializer: D,
+) -> Result<Option<i64>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  #[derive(Deserialize)]
+  #[serde(untagged)]
+  enum IntOrString {
+    Int(i64),
+    String(String),
+  }
+
+  let timestamp = match Option::<IntOrString>::deserialize(deserializer)? {
+    Some(IntOrString::Int(timestamp)) => timestamp,
+    Some(IntOrString::String(timestamp)) => {
+      timestamp.parse().map_err(|_| {
+        D::Error::custom(format!("invalid timestamp {:?}", timestamp))
+      })?
+    }
+    None => 0,
+  };
+
+  Ok(Some(timestamp).filter(|timestamp| *timestamp != 0))
+}
+
+/// Converts a Unix timestamp into a chrono `DateTime`, where `0` and
+/// timestamps out of its range result in `None`.
+#[cfg(feature = "chrono")]
+pub(crate) fn to_datetime(
+  timestamp: i64,
+) -> Option<chrono::DateTime<chrono::Utc>> {
+  use chrono::TimeZone;
+
+  if timestamp == 0 {
+    return None;
+  }
+
+  chrono::Utc.timestamp_opt(timestamp, 0).single()
+}
@@ -0,0 +1,1155 @@
+//! The error type for everything that can go wrong in this library.
+
+use crate::{RequestId, ResponseExtras, ValidationErrors};
+use http::StatusCode;
+use serde::Serialize;
+use std::{
+  fmt,
+  time::{Duration, Instant},
+};
+use thiserror::Error;
+
+/// A `Result` with [`PushoverError`](enum.PushoverError.html) as its error.
+pub type Result<T, E = PushoverError> = std::result::Result<T, E>;
+
+/// Everything that can go wrong when using this library.
+///
+/// * Errors from making an HTTP request, like the network being down, are
+///   returned as [`Transport`](#variant.Transport), or as
+///   [`Timeout`](#variant.Timeout) when they took too long. With the `ureq`
+///   backend they're returned as [`Ureq`](#variant.Ureq), and from the Open
+///   Client WebSocket as [`WebSocket`](#variant.WebSocket).
+/// * Errors returned by the Pushover API itself, like an invalid token, are
+///   returned as [`Api`](#variant.Api).
+/// * Going over the message limit of an application is returned as
+///   [`RateLimited`](#variant.RateLimited).
+/// * Responses that aren't JSON, like a proxy's error page, are returned as
+///   [`UnexpectedResponse`](#variant.UnexpectedResponse).
+/// * Messages or files that can't be parsed are returned as
+///   [`Serialization`](#variant.Serialization).
+/// * Messages that would be rejected by the Pushover API are caught before
+///   being sent and returned as [`Validation`](#variant.Validation), other
+///   invalid arguments are returned as
+///   [`InvalidArgument`](#variant.InvalidArgument).
+/// * Repeated messages dropped by a client's deduplicator are returned as
+///   [`Suppressed`](#variant.Suppressed).
+///
+/// Since it implements `std::error::Error`, it can still be used with
+/// crates like `anyhow`.
+///
+/// ```rust
+/// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+/// # {
+/// use pushover_api::{Message, PushoverError, MAX_TITLE_LENGTH};
+///
+/// let message = Message {
+///   title: Some("a".repeat(MAX_TITLE_LENGTH + 1)),
+///   ..Message::default()
+/// };
+///
+/// let error = message.send().unwrap_err();
+/// assert!(matches!(error, PushoverError::Validation(_)));
+/// # }
+/// ```
+#[derive(Debug, Error)]
+pub enum PushoverError {
+  /// The HTTP request failed.
+  ///
+  /// Its message leaves out the query string of the URL, which can contain
+  /// the application token.
+  ///
+  /// ```rust
+  /// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+  /// # {
+  /// use pushover_api::PushoverClient;
+  /// use std::net::TcpListener;
+  ///
+  /// // A port that nothing listens on, so connecting fails.
+  /// let address = TcpListener::bind("127.0.0.1:0")
+  ///   .unwrap()
+  ///   .local_addr()
+  ///   .unwrap();
+  ///
+  /// let client = PushoverClient::builder("azGDORePK8gMaC0QOYAMyEEuzJnyUi")
+  ///   .build()
+  ///   .unwrap()
+  ///   .base_url(&format!("http://{}/1", address))
+  ///   .unwrap();
+  ///
+  /// let error = client.list_sounds().unwrap_err();
+  /// assert!(error.is_connect(), "{:?}", error);
+  /// assert!(!error.to_string().contains("azGDORePK8gMaC0QOYAMyEEuzJnyUi"));
+  /// # }
+  /// ```
+  #[cfg(feature = "reqwest")]
+  #[error("The request to the Pushover API failed: {}", redact_reqwest(.0))]
+  Transport(#[source] reqwest::Error),
+  /// The HTTP request took longer than its timeout, see
+  /// [`set_timeouts()`](fn.set_timeouts.html).
+  ///
+  /// ```rust
+  /// # #[cfg(all(feature = "blocking", not(feature = "ureq")))]
+  /// # {
+  /// use pushover_api::{PushoverClient, PushoverError};
+  /// use std::time::Duration;
+  ///
+  /// # use pushover_api::{test_support::MockServer, HttpResponse};
+  /// # // A server that takes longer to respond than the timeout.
+  /// # let server = MockServer::with_handler(|_| {
+  /// #   std::thread::sleep(Duration::from_secs(5));
+  /// #   HttpResponse::json(http::StatusCode::OK, "{}")
+  /// # });
+  /// # let address = server.address();
+  /// let client = PushoverClient::builder("token")
+  ///   .timeout(Duration::from_millis(200))
+  ///   .build()
+  ///   .unwrap()
+  ///   .default_user("user key")
+  ///   .base_url(&format!("http://{}/1", address))
+  ///   .unwrap();
+  ///
+  /// let error = client.notify("Hello").unwrap_err();
+  /// assert!(matches!(error, PushoverError::Timeout(_)));
+  /// assert!(error.is_timeout() && error.is_retryable());
+  /// # }
+  /// ```
+  #[cfg(feature = "reqwest")]
+  #[error("The request to the Pushover API timed out: {}", redact_reqwest(.0))]
+  Timeout(#[source] reqwest::Error),
+  /// The HTTP request made with ureq failed, including when it took longer
+  /// than its timeout.
+  #[cfg(feature = "ureq")]
+  #[error("The request to the Pushover API failed: {}", redact_ureq(.0))]
+  Ureq(#[source] Box<ureq::Transport>),
+  /// The connection to the Open Client WebSocket server failed, see
+  /// [`open_client::listen()`](open_client/fn.listen.html).
+  #[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+  #[error("The Open Client WebSocket connection failed: {0}")]
+  WebSocket(#[source] Box<tokio_tungstenite::tungstenite::Error>),
+  /// The Pushover API returned errors, or a response without errors that
+  /// doesn't have a 2xx HTTP status and a `status` of `1`.
+  ///
+  /// ```rust
+  /// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+  /// # {
+  /// use http::StatusCode;
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, PushoverClient, PushoverError,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(200, r#"{"status":0,"request":"5042853c"}"#);
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"status":0,"errors":[],"request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = PushoverClient::with_transport("token", &transport)
+  ///   .default_user("user key");
+  ///
+  /// for _ in 0..2 {
+  ///   let error = client.notify("Hello").unwrap_err();
+  ///   assert_eq!(
+  ///     error.to_string(),
+  ///     "The Pushover API returned status 0 with HTTP status 200 OK and no \
+  ///      errors"
+  ///   );
+  ///   assert!(error.raw_body().unwrap().contains(r#""status":0"#));
+  ///   match error {
+  ///     PushoverError::Api {
+  ///       errors,
+  ///       http_status,
+  ///       status,
+  ///       request,
+  ///       ..
+  ///     } => {
+  ///       assert!(errors.is_empty());
+  ///       assert_eq!(http_status, StatusCode::OK);
+  ///       assert_eq!(status, 0);
+  ///       assert_eq!(request.unwrap().as_str(), "5042853c");
+  ///     }
+  ///     error => panic!("Unexpected error: {}", error),
+  ///   }
+  /// }
+  /// # }
+  /// ```
+  ///
+  /// Responses without a request identifier, like some responses for an
+  /// invalid token, still return their errors:
+  ///
+  /// ```rust
+  /// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+  /// # {
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, ApiErrorKind, PushoverClient, PushoverError,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"token":"invalid","errors":["application token is invalid"],
+  ///     "status":0}"#,
+  /// );
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"token":"invalid","errors":["application token is invalid"],
+  ///     "status":0,"request":null}"#,
+  /// );
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"token":"invalid","errors":["application token is invalid"],
+  ///     "status":0,"request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(200, r#"{"status":1}"#);
+  ///
+  /// let client = PushoverClient::with_transport("token", &transport)
+  ///   .default_user("user key");
+  ///
+  /// for expected in &[None, None, Some("5042853c")] {
+  ///   match client.notify("Hello").unwrap_err() {
+  ///     PushoverError::Api { errors, request, .. } => {
+  ///       assert_eq!(errors[0].kind(), ApiErrorKind::InvalidToken);
+  ///       let request = request.as_ref().map(|request| request.as_str());
+  ///       assert_eq!(request, *expected);
+  ///     }
+  ///     error => panic!("Unexpected error: {}", error),
+  ///   }
+  /// }
+  ///
+  /// let response = client.notify("Hello").unwrap();
+  /// assert_eq!(response.request.as_str(), "");
+  /// # }
+  /// ```
+  #[error("{}", api_message(.errors, .http_status, *.status))]
+  Api {
+    /// The errors returned by the Pushover API.
+    errors: Vec<ApiError>,
+    /// The HTTP status code of the response.
+    http_status: StatusCode,
+    /// The `status` of the response.
+    status: i32,
+    /// The request identifier of the response, if there was one.
+    request: Option<RequestId>,
+    /// Any other keys of the response, like the parameters that are invalid.
+    extras: Box<ResponseExtras>,
+    /// The original JSON body of the response.
+    body: String,
+  },
+  /// The application has gone over its message limit, the Pushover API
+  /// responded with the 429 status code.
+  ///
+  /// ```rust
+  /// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+  /// # {
+  /// use http::StatusCode;
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, HttpResponse, PushoverClient, PushoverError,
+  /// };
+  /// use std::time::Duration;
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_response(Ok(HttpResponse::json(
+  ///   StatusCode::TOO_MANY_REQUESTS,
+  ///   r#"{"status":0,"errors":["application is over its monthly limit"],
+  ///     "request":"5042853c"}"#,
+  /// )
+  /// .with_header("Retry-After", "60")
+  /// .with_header("X-Limit-App-Reset", "1393653600")));
+  /// transport.push_response(Ok(HttpResponse {
+  ///   status: StatusCode::TOO_MANY_REQUESTS,
+  ///   headers: vec![],
+  ///   body: "Too Many Requests".to_string(),
+  /// }));
+  ///
+  /// let client = PushoverClient::with_transport("token", &transport)
+  ///   .default_user("user key");
+  ///
+  /// let error = client.notify("Hello").unwrap_err();
+  /// assert_eq!(
+  ///   error.api_errors()[0].message,
+  ///   "application is over its monthly limit"
+  /// );
+  /// assert!(error.raw_body().unwrap().contains("monthly limit"));
+  /// match error {
+  ///   PushoverError::RateLimited {
+  ///     retry_after,
+  ///     reset_at,
+  ///     request,
+  ///     ..
+  ///   } => {
+  ///     assert_eq!(retry_after, Some(Duration::from_secs(60)));
+  ///     assert_eq!(reset_at, Some(1393653600));
+  ///     assert_eq!(request.unwrap().as_str(), "5042853c");
+  ///   }
+  ///   error => panic!("{}", error),
+  /// }
+  ///
+  /// let error = client.notify("Hello").unwrap_err();
+  /// assert!(error.api_errors().is_empty());
+  /// assert_eq!(error.raw_body(), Some("Too Many Requests"));
+  /// match error {
+  ///   PushoverError::RateLimited {
+  ///     retry_after,
+  ///     reset_at,
+  ///     request,
+  ///     ..
+  ///   } => {
+  ///     assert_eq!(retry_after, None);
+  ///     assert_eq!(reset_at, None);
+  ///     assert_eq!(request, None);
+  ///   }
+  ///   error => panic!("{}", error),
+  /// }
+  /// # }
+  /// ```
+  #[error("The Pushover API rate limit has been reached")]
+  RateLimited {
+    /// How long to wait before trying again, from the `Retry-After` header.
+    retry_after: Option<Duration>,
+    /// When the limit resets as a Unix timestamp, from the
+    /// `X-Limit-App-Reset` header.
+    reset_at: Option<i64>,
+    /// The errors returned by the Pushover API, which is empty when the body
+    /// isn't JSON.
+    errors: Vec<ApiError>,
+    /// The request identifier of the response, if there was one.
+    request: Option<RequestId>,
+    /// Any other keys of the response.
+    extras: Box<ResponseExtras>,
+    /// The body of the response, cut off after a few hundred bytes when it
+    /// isn't JSON.
+    body: String,
+  },
+  /// The message repeats one that was sent within the deduplication window
+  /// of a client or [`Deduplicator`](struct.Deduplicator.html), see
+  /// [`PushoverClient::with_dedup()`](struct.PushoverClient.html#method.with_dedup).
+  /// It's dropped on purpose, so a
+  /// [`BatchReport`](struct.BatchReport.html) doesn't count it as a failure.
+  #[error("The message repeats one that was sent recently")]
+  Suppressed,
+  /// Sending failed after retrying, see
+  /// [`RetryPolicy`](struct.RetryPolicy.html).
+  #[error("Giving up after {attempts} attempts: {last_error}")]
+  RetriesExhausted {
+    /// The number of attempts that were made.
+    attempts: u32,
+    /// The error of the last attempt.
+    #[source]
+    last_error: Box<PushoverError>,
+  },
+  /// Sending didn't finish before its deadline, including any retries, see
+  /// [`Message::send_async_with_deadline()`](struct.Message.html#method.send_async_with_deadline).
+  #[error("{}", deadline_message(*.deadline, *.attempts, .last_error.as_deref()))]
+  DeadlineExceeded {
+    /// How long sending was allowed to take.
+    deadline: Duration,
+    /// The number of attempts that were started.
+    attempts: u32,
+    /// The error of the last attempt that failed before the deadline, if
+    /// any.
+    last_error: Option<Box<PushoverError>>,
+  },
+  /// The request wasn't made because the Pushover API failed too many times
+  /// in a row, see [`CircuitBreaker`](struct.CircuitBreaker.html).
+  #[error("The circuit breaker is open after too many failures")]
+  CircuitOpen {
+    /// When a request will be let through again.
+    retry_at: Instant,
+  },
+  /// The Pushover API returned a response that isn't JSON, like the HTML error
+  /// page of a proxy.
+  ///
+  /// ```rust
+  /// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+  /// # {
+  /// use http::StatusCode;
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, HttpResponse, PushoverClient, PushoverError,
+  /// };
+  ///
+  /// let html = HttpResponse {
+  ///   status: StatusCode::BAD_GATEWAY,
+  ///   headers: vec![("content-type".to_string(), "text/html".to_string())],
+  ///   body: "<html><body>502 Bad Gateway</body></html>".to_string(),
+  /// };
+  /// // 299 bytes followed by a 2 byte character that doesn't fit.
+  /// let long = HttpResponse {
+  ///   body: format!("{}é and more", "a".repeat(299)),
+  ///   ..html.clone()
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_response(Ok(html));
+  /// transport.push_response(Ok(HttpResponse {
+  ///   status: StatusCode::INTERNAL_SERVER_ERROR,
+  ///   headers: vec![],
+  ///   body: String::new(),
+  /// }));
+  /// transport.push_json(200, r#"{"status":1,"request":"#);
+  /// transport.push_response(Ok(long));
+  ///
+  /// let client = PushoverClient::with_transport("token", &transport)
+  ///   .default_user("user key");
+  /// let error = || match client.notify("Hello").unwrap_err() {
+  ///   PushoverError::UnexpectedResponse {
+  ///     http_status,
+  ///     content_type,
+  ///     body,
+  ///   } => (http_status, content_type, body),
+  ///   error => panic!("Unexpected error: {}", error),
+  /// };
+  ///
+  /// let (status, content_type, body) = error();
+  /// assert_eq!(status, StatusCode::BAD_GATEWAY);
+  /// assert_eq!(content_type.as_deref(), Some("text/html"));
+  /// assert_eq!(body, "<html><body>502 Bad Gateway</body></html>");
+  ///
+  /// let (status, content_type, body) = error();
+  /// assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+  /// assert_eq!((content_type, body.as_str()), (None, ""));
+  ///
+  /// let (status, content_type, body) = error();
+  /// assert_eq!(status, StatusCode::OK);
+  /// assert_eq!(content_type.as_deref(), Some("application/json"));
+  /// assert_eq!(body, r#"{"status":1,"request":"#);
+  ///
+  /// // Long bodies are cut off at 300 bytes, or before that when the cut
+  /// // would split a character.
+  /// let (_, _, body) = error();
+  /// assert_eq!(body, "a".repeat(299));
+  /// # }
+  /// ```
+  #[error("Unexpected {http_status} response from the Pushover API: {body:?}")]
+  UnexpectedResponse {
+    /// The HTTP status code of the response.
+    http_status: StatusCode,
+    /// The content type of the response, if there was one.
+    content_type: Option<String>,
+    /// The first few hundred bytes of the response body.
+    body: String,
+  },
+  /// Something couldn't be serialized or deserialized.
+  ///
+  /// ```rust
+  /// use pushover_api::{Message, PushoverError, ReceiptCallback};
+  ///
+  /// let error = Message::from_json_str(r#"{"message": 5}"#).unwrap_err();
+  /// assert!(matches!(error, PushoverError::Serialization(_)));
+  /// assert!(error.to_string().starts_with("message: invalid type"));
+  ///
+  /// let error = Message::from_toml_str("message = ").unwrap_err();
+  /// assert!(matches!(error, PushoverError::Serialization(_)));
+  ///
+  /// let body = b"receipt=abc&acknowledged=maybe";
+  /// let error = ReceiptCallback::from_form_bytes(body).unwrap_err();
+  /// assert!(matches!(error, PushoverError::Serialization(_)));
+  /// assert!(!error.is_retryable() && error.raw_body().is_none());
+  /// ```
+  #[error("{0}")]
+  Serialization(#[source] Box<dyn std::error::Error + Send + Sync>),
+  /// A message would be rejected by the Pushover API.
+  #[error("{0}")]
+  Validation(#[from] ValidationErrors),
+  /// An argument isn't valid.
+  #[error("{0}")]
+  InvalidArgument(String),
+  /// An attachment is larger than
+  /// [`MAX_ATTACHMENT_SIZE`](constant.MAX_ATTACHMENT_SIZE.html).
+  #[error(
+    "The attachment {name} is {size} bytes, the maximum is {limit} bytes"
+  )]
+  AttachmentTooLarge {
+    /// The name or path of the attachment.
+    name: String,
+    /// The size of the attachment in bytes.
+    size: u64,
+    /// The maximum size of an attachment in bytes.
+    limit: u64,
+  },
+  /// A response has no receipt, because it's not for an emergency priority
+  /// message.
+  #[error("There is no receipt on this response")]
+  MissingReceipt,
+  /// Reading a file failed.
+  #[error("{0}")]
+  Io(#[from] std::io::Error),
+}
+
+/// Classifying errors, for example to decide whether to try again.
+///
+/// ```rust
+/// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+/// # {
+/// use pushover_api::{
+///   test_support::FakeTransport, Message, PushoverClient, PushoverError,
+///   MAX_TITLE_LENGTH,
+/// };
+///
+/// let rate_limited = PushoverError::RateLimited {
+///   retry_after: None,
+///   reset_at: None,
+///   errors: vec![],
+///   request: None,
+///   extras: Default::default(),
+///   body: String::new(),
+/// };
+/// assert!(rate_limited.is_retryable());
+/// assert!(rate_limited.is_client_error());
+///
+/// let transport = FakeTransport::new();
+/// transport.push_json(429, r#"{"status":0,"errors":["over the limit"]}"#);
+/// transport.push_json(503, r#"{"status":0,"errors":["unavailable"]}"#);
+/// transport.push_json(400, r#"{"status":0,"errors":["user is invalid"]}"#);
+/// let client = PushoverClient::with_transport("token", &transport)
+///   .default_user("user key");
+///
+/// let rate_limited = client.notify("Hello").unwrap_err();
+/// assert!(matches!(rate_limited, PushoverError::RateLimited { .. }));
+/// assert!(rate_limited.is_retryable() && rate_limited.is_client_error());
+/// assert!(!rate_limited.is_timeout() && !rate_limited.is_connect());
+///
+/// let unavailable = client.notify("Hello").unwrap_err();
+/// assert!(unavailable.is_retryable() && !unavailable.is_client_error());
+///
+/// let rejected = client.notify("Hello").unwrap_err();
+/// assert!(!rejected.is_retryable() && rejected.is_client_error());
+///
+/// let message = Message {
+///   title: Some("a".repeat(MAX_TITLE_LENGTH + 1)),
+///   ..Message::default()
+/// };
+/// let invalid = message.send().unwrap_err();
+/// assert!(!invalid.is_retryable());
+/// assert!(invalid.is_client_error());
+/// assert!(!invalid.is_timeout());
+/// # }
+/// ```
+impl PushoverError {
+  /// Whether the error is likely to go away by trying again. This is the case
+  /// for connection errors, timeouts, 5xx responses and rate limiting, but
+  /// not for 4xx responses and validation errors.
+  pub fn is_retryable(&self) -> bool {
+    match self {
+      #[cfg(feature = "reqwest")]
+      PushoverError::Transport(error) => is_connect_error(error),
+      #[cfg(feature = "reqwest")]
+      PushoverError::Timeout(_) => true,
+      #[cfg(feature = "ureq")]
+      PushoverError::Ureq(error) => {
+        is_ureq_connect_error(error) || is_ureq_timeout(error)
+      }
+      PushoverError::RateLimited { .. } => true,
+      PushoverError::Api { http_status, .. }
+      | PushoverError::UnexpectedResponse { http_status, .. } => {
+        http_status.is_server_error()
+      }
+      PushoverError::RetriesExhausted { last_error, .. } => {
+        last_error.is_retryable()
+      }
+      _ => false,
+    }
+  }
+
+  /// Whether the request took longer than its timeout.
+  pub fn is_timeout(&self) -> bool {
+    match self {
+      #[cfg(feature = "reqwest")]
+      PushoverError::Timeout(_) => true,
+      #[cfg(feature = "reqwest")]
+      PushoverError::Transport(error) => error.is_timeout(),
+      #[cfg(feature = "ureq")]
+      PushoverError::Ureq(error) => is_ureq_timeout(error),
+      PushoverError::RetriesExhausted { last_error, .. } => {
+        last_error.is_timeout()
+      }
+      PushoverError::DeadlineExceeded { .. } => true,
+      _ => false,
+    }
+  }
+
+  /// Whether connecting to the Pushover API failed.
+  pub fn is_connect(&self) -> bool {
+    match self {
+      #[cfg(feature = "reqwest")]
+      PushoverError::Transport(error) | PushoverError::Timeout(error) => {
+        is_connect_error(error)
+      }
+      #[cfg(feature = "ureq")]
+      PushoverError::Ureq(error) => is_ureq_connect_error(error),
+      PushoverError::RetriesExhausted { last_error, .. } => {
+        last_error.is_connect()
+      }
+      _ => false,
+    }
+  }
+
+  /// Whether the request was at fault, because the Pushover API responded
+  /// with a 4xx status code or because the message or an argument is
+  /// invalid.
+  pub fn is_client_error(&self) -> bool {
+    match self {
+      PushoverError::Api { http_status, .. }
+      | PushoverError::UnexpectedResponse { http_status, .. } => {
+        http_status.is_client_error()
+      }
+      #[cfg(feature = "reqwest")]
+      PushoverError::Transport(error) => error
+        .status()
+        .is_some_and(|status| status.is_client_error()),
+      PushoverError::RateLimited { .. }
+      | PushoverError::Validation(_)
+      | PushoverError::InvalidArgument(_)
+      | PushoverError::AttachmentTooLarge { .. } => true,
+      PushoverError::RetriesExhausted { last_error, .. } => {
+        last_error.is_client_error()
+      }
+      _ => false,
+    }
+  }
+
+  /// The body of the response, for errors caused by a response of the
+  /// Pushover API. Bodies that aren't JSON are cut off after a few hundred
+  /// bytes.
+  ///
+  /// ```rust
+  /// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+  /// # {
+  /// use http::StatusCode;
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, HttpResponse, PushoverClient, PushoverError,
+  ///   RetryPolicy,
+  /// };
+  /// use std::time::Duration;
+  ///
+  /// let json = r#"{"user":"invalid","errors":["user identifier is invalid"],
+  ///   "status":0,"request":"5042853c"}"#;
+  /// let html = "<html><body>503 Service Unavailable</body></html>";
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(400, json);
+  /// transport.push_response(Ok(HttpResponse {
+  ///   status: StatusCode::SERVICE_UNAVAILABLE,
+  ///   headers: vec![],
+  ///   body: html.to_string(),
+  /// }));
+  /// transport.push_response(Ok(HttpResponse {
+  ///   status: StatusCode::SERVICE_UNAVAILABLE,
+  ///   headers: vec![],
+  ///   body: html.to_string(),
+  /// }));
+  ///
+  /// let client = PushoverClient::with_transport("token", &transport)
+  ///   .default_user("user key");
+  ///
+  /// let error = client.notify("Hello").unwrap_err();
+  /// assert!(matches!(error, PushoverError::Api { .. }));
+  /// assert_eq!(error.raw_body(), Some(json));
+  ///
+  /// // The body of the last attempt is kept after retrying.
+  /// let client = client.retry_policy(RetryPolicy {
+  ///   max_attempts: 2,
+  ///   base_delay: Duration::from_millis(1),
+  ///   ..RetryPolicy::default()
+  /// });
+  /// let error = client.notify("Hello").unwrap_err();
+  /// assert!(matches!(error, PushoverError::RetriesExhausted { .. }));
+  /// assert_eq!(error.raw_body(), Some(html));
+  ///
+  /// let error = PushoverError::InvalidArgument("Invalid".to_string());
+  /// assert_eq!(error.raw_body(), None);
+  /// # }
+  /// ```
+  pub fn raw_body(&self) -> Option<&str> {
+    match self {
+      PushoverError::Api { body, .. }
+      | PushoverError::RateLimited { body, .. }
+      | PushoverError::UnexpectedResponse { body, .. } => Some(body),
+      PushoverError::RetriesExhausted { last_error, .. } => {
+        last_error.raw_body()
+      }
+      _ => None,
+    }
+  }
+
+  /// The errors returned by the Pushover API, which is empty for any other
+  /// kind of error.
+  pub fn api_errors(&self) -> &[ApiError] {
+    match self {
+      PushoverError::Api { errors, .. }
+      | PushoverError::RateLimited { errors, .. } => errors,
+      PushoverError::RetriesExhausted { last_error, .. } => {
+        last_error.api_errors()
+      }
+      _ => &[],
+    }
+  }
+}
+
+/// The well-known errors of the Pushover API, see
+/// [`ApiError::kind()`](struct.ApiError.html#method.kind).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ApiErrorKind {
+  /// The application token is invalid.
+  InvalidToken,
+  /// The user key isn't a valid user, group or subscribed user key.
+  InvalidUser,
+  /// A device isn't valid for the user, with the name of the device when the
+  /// error mentions it and the whole error message otherwise.
+  InvalidDevice(String),
+  /// The message is empty.
+  MessageBlank,
+  /// The receipt doesn't exist, or has expired.
+  ReceiptNotFound,
+  /// The emergency priority message has already been acknowledged.
+  AlreadyAcknowledged,
+  /// The group key isn't a valid group of the application.
+  InvalidGroup,
+  /// The user is already a member of the group.
+  AlreadyGroupMember,
+  /// The user isn't a member of the group, or not with the given device.
+  NotGroupMember,
+  /// The user is already on the Pushover for Teams account.
+  AlreadyOnTeam,
+  /// The email address isn't on the Pushover for Teams account.
+  NotOnTeam,
+  /// The Pushover for Teams account doesn't have any licenses left.
+  NoLicensesAvailable,
+  /// The email address or password of an Open Client login is wrong.
+  InvalidCredentials,
+  /// An Open Client login needs a two-factor authentication code, or the
+  /// code is wrong.
+  TwoFactorRequired,
+  /// The user already has a device with the name that's being registered.
+  DeviceNameTaken,
+  /// The user can't register any more devices.
+  TooManyDevices,
+  /// Any other error.
+  Other,
+}
+
+/// An error returned by the Pushover API.
+///
+/// ```rust
+/// use pushover_api::{ApiError, ApiErrorKind};
+///
+/// let error = ApiError {
+///   field: None,
+///   message: "application token is invalid".to_string(),
+/// };
+/// assert_eq!(error.kind(), ApiErrorKind::InvalidToken);
+///
+/// let error = ApiError {
+///   field: Some("devices".to_string()),
+///   message: "device 'toaster' is not valid for this user".to_string(),
+/// };
+/// assert_eq!(error.kind(), ApiErrorKind::InvalidDevice("toaster".to_string()));
+///
+/// let error = ApiError {
+///   field: None,
+///   message: "user identifier is not a valid user, group, or subscribed user \
+///     key"
+///     .to_string(),
+/// };
+/// assert_eq!(error.kind(), ApiErrorKind::InvalidUser);
+///
+/// let error = ApiError {
+///   field: Some("message".to_string()),
+///   message: "cannot be blank".to_string(),
+/// };
+/// assert_eq!(error.kind(), ApiErrorKind::MessageBlank);
+///
+/// let error = ApiError {
+///   field: Some("receipt".to_string()),
+///   message: "receipt not found; may be invalid or expired".to_string(),
+/// };
+/// assert_eq!(error.kind(), ApiErrorKind::ReceiptNotFound);
+///
+/// let error = ApiError {
+///   field: Some("receipt".to_string()),
+///   message: "receipt has already been acknowledged".to_string(),
+/// };
+/// assert_eq!(error.kind(), ApiErrorKind::AlreadyAcknowledged);
+///
+/// let error = ApiError {
+///   field: Some("group".to_string()),
+///   message: "not found".to_string(),
+/// };
+/// assert_eq!(error.kind(), ApiErrorKind::InvalidGroup);
+///
+/// let error = ApiError {
+///   field: Some("user".to_string()),
+///   message: "user is already a member of this group".to_string(),
+/// };
+/// assert_eq!(error.kind(), ApiErrorKind::AlreadyGroupMember);
+///
+/// let error = ApiError {
+///   field: Some("user".to_string()),
+///   message: "user is not a member of this group".to_string(),
+/// };
+/// assert_eq!(error.kind(), ApiErrorKind::NotGroupMember);
+///
+/// let error = ApiError {
+///   field: Some("email".to_string()),
+///   message: "is already on this team".to_string(),
+/// };
+/// assert_eq!(error.kind(), ApiErrorKind::AlreadyOnTeam);
+///
+/// let error = ApiError {
+///   field: Some("email".to_string()),
+///   message: "is not on this team".to_string(),
+/// };
+/// assert_eq!(error.kind(), ApiErrorKind::NotOnTeam);
+///
+/// let error = ApiError {
+///   field: None,
+///   message: "team has no licenses available".to_string(),
+/// };
+/// assert_eq!(error.kind(), ApiErrorKind::NoLicensesAvailable);
+///
+/// let error = ApiError {
+///   field: None,
+///   message: "invalid email and/or password".to_string(),
+/// };
+/// assert_eq!(error.kind(), ApiErrorKind::InvalidCredentials);
+///
+/// let error = ApiError {
+///   field: Some("twofa".to_string()),
+///   message: "is invalid".to_string(),
+/// };
+/// assert_eq!(error.kind(), ApiErrorKind::TwoFactorRequired);
+///
+/// let error = ApiError {
+///   field: Some("name".to_string()),
+///   message: "has already been taken".to_string(),
+/// };
+/// assert_eq!(error.kind(), ApiErrorKind::DeviceNameTaken);
+///
+/// let error = ApiError {
+///   field: None,
+///   message: "you have too many active devices".to_string(),
+/// };
+/// assert_eq!(error.kind(), ApiErrorKind::TooManyDevices);
+/// ```
+///
+/// The Pushover API usually returns its errors as a list of messages, but
+/// sometimes as an object keyed by the parameter they're about. Both become
+/// a list of errors, and any other shape is kept as a single error with the
+/// raw JSON as its message.
+///
+/// ```rust
+/// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+/// # {
+/// use pushover_api::{test_support::FakeTransport, ApiError, PushoverClient};
+///
+/// let transport = FakeTransport::new();
+/// transport.push_json(
+///   400,
+///   r#"{"errors":["user identifier is invalid","message cannot be blank"],
+///     "status":0,"request":"5042853c"}"#,
+/// );
+/// transport.push_json(
+///   400,
+///   r#"{"errors":{"user":"is not a valid user","sound":["is invalid"]},
+///     "status":0,"request":"5042853c"}"#,
+/// );
+/// transport.push_json(
+///   400,
+///   r#"{"errors":{"user":42},"status":0,"request":"5042853c"}"#,
+/// );
+/// transport.push_json(
+///   400,
+///   r#"{"errors":"user is invalid","status":0,"request":"5042853c"}"#,
+/// );
+///
+/// let client = PushoverClient::with_transport("token", &transport)
+///   .default_user("user key");
+///
+/// let error = client.notify("Message").unwrap_err();
+/// assert_eq!(
+///   error.api_errors(),
+///   &[
+///     ApiError {
+///       field: None,
+///       message: "user identifier is invalid".to_string(),
+///     },
+///     ApiError {
+///       field: None,
+///       message: "message cannot be blank".to_string(),
+///     },
+///   ]
+/// );
+///
+/// let error = client.notify("Message").unwrap_err();
+/// assert_eq!(
+///   error.api_errors(),
+///   &[
+///     ApiError {
+///       field: Some("sound".to_string()),
+///       message: "is invalid".to_string(),
+///     },
+///     ApiError {
+///       field: Some("user".to_string()),
+///       message: "is not a valid user".to_string(),
+///     },
+///   ]
+/// );
+///
+/// let error = client.notify("Message").unwrap_err();
+/// assert_eq!(
+///   error.api_errors(),
+///   &[ApiError {
+///     field: None,
+///     message: r#"{"user":42}"#.to_string(),
+///   }]
+/// );
+///
+/// let error = client.notify("Message").unwrap_err();
+/// assert_eq!(
+///   error.api_errors(),
+///   &[ApiError {
+///     field: None,
+///     message: r#""user is invalid""#.to_string(),
+///   }]
+/// );
+/// # }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ApiError {
+  /// The parameter the error is about, when the Pushover API said so.
+  pub field: Option<String>,
+  /// The error message.
+  pub message: String,
+}
+
+impl ApiError {
+  /// Creates an error with an optional parameter it's about.
+  pub(crate) fn new(field: Option<String>, message: String) -> Self {
+    Self { field, message }
+  }
+
+  /// Recognizes the well-known errors from their message and parameter. The
+  /// original message stays available in
+  /// [`message`](#structfield.message).
+  pub fn kind(&self) -> ApiErrorKind {
+    let message = self.message.to_lowercase();
+    let field = self.field.as_deref().unwrap_or_default();
+
+    if field == "token" || message.starts_with("application token") {
+      ApiErrorKind::InvalidToken
+    } else if message.contains("team") && message.contains("already") {
+      ApiErrorKind::AlreadyOnTeam
+    } else if message.contains("team")
+      && (message.contains("not a member") || message.contains("not on"))
+    {
+      ApiErrorKind::NotOnTeam
+    } else if message.contains("no licenses")
+      || message.contains("licenses available")
+      || message.contains("not enough license")
+    {
+      ApiErrorKind::NoLicensesAvailable
+    } else if message.contains("already acknowledged")
+      || message.contains("already been acknowledged")
+    {
+      ApiErrorKind::AlreadyAcknowledged
+    } else if message.contains("already a member") {
+      ApiErrorKind::AlreadyGroupMember
+    } else if message.contains("not a member") {
+      ApiErrorKind::NotGroupMember
+    } else if field == "twofa" || message.contains("two-factor") {
+      ApiErrorKind::TwoFactorRequired
+    } else if field == "email"
+      || field == "password"
+      || message.contains("email and/or password")
+      || message.contains("email or password")
+    {
+      ApiErrorKind::InvalidCredentials
+    } else if field == "user"
+      || message.starts_with("user identifier")
+      || message.starts_with("user key")
+    {
+      ApiErrorKind::InvalidUser
+    } else if field == "name" && message.contains("taken") {
+      ApiErrorKind::DeviceNameTaken
+    } else if message.contains("too many") && message.contains("device") {
+      ApiErrorKind::TooManyDevices
+    } else if field.starts_with("device") || message.contains("device") {
+      let name = self.message.split(&['\'', '"'][..]).nth(1);
+      ApiErrorKind::InvalidDevice(name.unwrap_or(&self.message).to_string())
+    } else if message.contains("message cannot be blank")
+      || (field == "message" && message.contains("blank"))
+    {
+      ApiErrorKind::MessageBlank
+    } else if field == "receipt" || message.starts_with("receipt not found") {
+      ApiErrorKind::ReceiptNotFound
+    } else if field == "group" || message.starts_with("group not found") {
+      ApiErrorKind::InvalidGroup
+    } else {
+      ApiErrorKind::Other
+    }
+  }
+}
+
+impl fmt::Display for ApiError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match &self.field {
+      Some(field) => write!(f, "{}: {}", field, self.message),
+      None => write!(f, "{}", self.message),
+    }
+  }
+}
+
+/// The message of an [`Api`](enum.PushoverError.html#variant.Api) error,
+/// joining its errors with commas.
+fn api_message(
+  errors: &[ApiError],
+  http_status: &StatusCode,
+  status: i32,
+) -> String {
+  if errors.is_empty() {
+    return format!(
+      "The Pushover API returned status {} with HTTP status {} and no errors",
+      status, http_status
+    );
+  }
+
+  let errors = errors
+    .iter()
+    .map(ToString::to_string)
+    .collect::<Vec<_>>()
+    .join(", ");
+  format!("The Pushover API returned an error: {}", errors)
+}
+
+/// The message of a
+/// [`DeadlineExceeded`](enum.PushoverError.html#variant.DeadlineExceeded)
+/// error, with the last error when there was one.
+fn deadline_message(
+  deadline: Duration,
+  attempts: u32,
+  last_error: Option<&PushoverError>,
+) -> String {
+  let message = format!(
+    "Sending didn't finish within {:?} after {} attempts",
+    deadline, attempts
+  );
+  match last_error {
+    Some(error) => format!("{}, the last one failed with: {}", message, error),
+    None => message,
+  }
+}
+
+/// Whether a Reqwest error happened while connecting.
+#[cfg(all(feature = "reqwest", not(target_arch = "wasm32")))]
+fn is_connect_error(error: &reqwest::Error) -> bool {
+  error.is_connect()
+}
+
+/// Whether a Reqwest error happened while connecting, on WebAssembly `fetch`
+/// only tells that the request failed.
+#[cfg(all(feature = "reqwest", target_arch = "wasm32"))]
+fn is_connect_error(error: &reqwest::Error) -> bool {
+  error.is_request()
+}
+
+/// Formats a Reqwest error without the query string of its URL.
+#[cfg(feature = "reqwest")]
+fn redact_reqwest(error: &reqwest::Error) -> String {
+  redact_query(error.to_string(), error.url().map(|url| url.as_str()))
+}
+
+/// Formats a ureq error without the query string of its URL.
+#[cfg(feature = "ureq")]
+fn redact_ureq(error: &ureq::Transport) -> String {
+  redact_query(error.to_string(), error.url().map(|url| url.as_str()))
+}
+
+/// Removes the query string of `url` from an error message, because the
+/// Pushover API takes the application token as a query parameter of `GET`
+/// requests.
+#[cfg(any(feature = "reqwest", feature = "ureq"))]
+fn redact_query(message: String, url: Option<&str>) -> String {
+  match url.and_then(|url| Some((url, url.split_once('?')?.0))) {
+    Some((url, without_query)) => message.replace(url, without_query),
+    None => message,
+  }
+}
+
+#[cfg(feature = "reqwest")]
+impl From<reqwest::Error> for PushoverError {
+  fn from(error: reqwest::Error) -> Self {
+    if error.is_timeout() {
+      PushoverError::Timeout(error)
+    } else {
+      PushoverError::Transport(error)
+    }
+  }
+}
+
+/// Whether a ureq error happened while connecting.
+#[cfg(feature = "ureq")]
+fn is_ureq_connect_error(error: &ureq::Transport) -> bool {
+  matches!(
+    error.kind(),
+    ureq::ErrorKind::Dns
+      | ureq::ErrorKind::ConnectionFailed
+      | ureq::ErrorKind::ProxyConnect
+  )
+}
+
+/// Whether a ureq error happened because the request took longer than its
+/// timeout, which ureq reports as an I/O error.
+#[cfg(feature = "ureq")]
+fn is_ureq_timeout(error: &ureq::Transport) -> bool {
+  use std::error::Error;
+
+  error.kind() == ureq::ErrorKind::Io
+    && error
+      .source()
+      .and_then(|source| source.downcast_ref::<std::io::Error>())
+      .is_some_and(|source| {
+        matches!(
+          source.kind(),
+          std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock
+        )
+      })
+}
+
+#[cfg(feature = "ureq")]
+impl From<ureq::Transport> for PushoverError {
+  fn from(error: ureq::Transport) -> Self {
+    PushoverError::Ureq(Box::new(error))
+  }
+}
+
+impl From<serde_json::Error> for PushoverError {
+  fn from(error: serde_json::Error) -> Self {
+    PushoverError::Serialization(Box::new(error))
+  }
+}
+
+impl From<toml::de::Error> for PushoverError {
+  fn from(error: toml::de::Error) -> Self {
+    PushoverError::Serialization(Box::new(error))
+  }
+}
+
+impl From<serde_urlencoded::ser::Error> for PushoverError {
+  fn from(error: serde_urlencoded::ser::Error) -> Self {
+    PushoverError::Serialization(Box::new(error))
+  }
+}
+
+impl From<serde_urlencoded::de::Error> for PushoverError {
+  fn from(error: serde_urlencoded::de::Error) -> Self {
+    PushoverError::Serialization(Box::new(error))
+  }
+}
+
+impl<E> From<serde_path_to_error::Error<E>> for PushoverError
+where
+  E: std::error::Error + Send + Sync + 'static,
+{
+  fn from(error: serde_path_to_error::Error<E>) -> Self {
+    PushoverError::Serialization(Box::new(error))
+  }
+}
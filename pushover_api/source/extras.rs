@@ -0,0 +1,99 @@
+//! Extra keys of Pushover API responses.
+
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+
+/// The keys of a Pushover API response besides its status, request, errors
+/// and receipt.
+///
+/// When a parameter is invalid, the Pushover API usually adds a key for it
+/// with `"invalid"` as its value, next to the error messages.
+///
+/// ```rust
+/// use pushover_api::ResponseExtras;
+///
+/// let extras: ResponseExtras =
+///   serde_json::from_str(r#"{"user": "invalid", "device": "invalid"}"#)
+///     .unwrap();
+/// assert_eq!(extras.user.as_deref(), Some("invalid"));
+/// assert_eq!(extras.invalid_parameters(), vec!["device", "user"]);
+///
+/// let extras: ResponseExtras =
+///   serde_json::from_str(r#"{"token": "invalid", "sound": "invalid"}"#)
+///     .unwrap();
+/// assert_eq!(extras.invalid_parameters(), vec!["sound", "token"]);
+/// assert_eq!(extras.other["sound"], "invalid");
+///
+/// // Some endpoints return objects under these keys, which aren't markers.
+/// let extras: ResponseExtras =
+///   serde_json::from_str(r#"{"user": {"quiet_hours": false}}"#).unwrap();
+/// assert_eq!(extras.user, None);
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ResponseExtras {
+  /// The marker for the application token, `"invalid"` when it's invalid.
+  #[serde(
+    default,
+    deserialize_with = "deserialize_marker",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub token: Option<String>,
+  /// The marker for the user key, `"invalid"` when it's invalid.
+  #[serde(
+    default,
+    deserialize_with = "deserialize_marker",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub user: Option<String>,
+  /// The marker for the device, `"invalid"` when it's invalid.
+  #[serde(
+    default,
+    deserialize_with = "deserialize_marker",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub device: Option<String>,
+  /// Any other keys.
+  #[serde(flatten)]
+  pub other: HashMap<String, serde_json::Value>,
+}
+
+impl ResponseExtras {
+  /// The names of the parameters the Pushover API marked as invalid, sorted
+  /// alphabetically.
+  pub fn invalid_parameters(&self) -> Vec<&str> {
+    let known = [
+      ("token", &self.token),
+      ("user", &self.user),
+      ("device", &self.device),
+    ];
+
+    let mut invalid = known
+      .iter()
+      .filter(|(_, value)| value.as_deref() == Some("invalid"))
+      .map(|(name, _)| *name)
+      .chain(
+        self
+          .other
+          .iter()
+          .filter(|(_, value)| value.as_str() == Some("invalid"))
+          .map(|(name, _)| name.as_str()),
+      )
+      .collect::<Vec<_>>();
+
+    invalid.sort_unstable();
+    invalid
+  }
+}
+
+/// Deserializes a parameter marker, ignoring values that aren't strings.
+fn deserialize_marker<'de, D>(
+  deserializer: D,
+) -> Result<Option<String>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  match serde_json::Value::deserialize(deserializer)? {
+    serde_json::Value::String(marker) => Ok(Some(marker)),
+    _ => Ok(None),
+  }
+}
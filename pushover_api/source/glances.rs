@@ -0,0 +1,442 @@
+//! Updating Glances widgets.
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+use crate::{
+  api, api_base, form_fields, http_client, join_url, HttpBody, HttpMethod,
+  HttpRequest, PushoverClient, RequestId, ResponseExtras, Transport,
+};
+use crate::{PushoverError, Result, Secret};
+use serde::{
+  de::{DeserializeOwned, Error as _},
+  Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// The maximum number of characters in a glance's title, text and subtext.
+pub const MAX_GLANCE_TEXT_LENGTH: usize = 100;
+
+/// How a field of a [`Glance`](struct.Glance.html) changes the widget.
+///
+/// Fields that are [`Unchanged`](#variant.Unchanged) aren't sent at all, so
+/// the widget keeps showing their previous value, and fields that are
+/// [`Clear`](#variant.Clear) are sent empty to remove their value.
+///
+/// ```rust
+/// use pushover_api::GlanceField;
+///
+/// assert_eq!(GlanceField::<i32>::default(), GlanceField::Unchanged);
+/// assert_eq!(GlanceField::from("Queue"), GlanceField::Set("Queue".to_string()));
+/// assert_eq!(GlanceField::from(42), GlanceField::Set(42));
+/// assert_eq!(serde_json::to_string(&GlanceField::<i32>::Clear).unwrap(), r#""""#);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum GlanceField<T> {
+  /// Keep the value the widget has.
+  #[default]
+  Unchanged,
+  /// Show a new value.
+  Set(T),
+  /// Remove the value from the widget.
+  Clear,
+}
+
+impl<T> GlanceField<T> {
+  /// Returns whether the field leaves the widget as it is.
+  pub fn is_unchanged(&self) -> bool {
+    matches!(self, GlanceField::Unchanged)
+  }
+
+  /// Returns the new value of the field, if it sets one.
+  pub fn as_set(&self) -> Option<&T> {
+    match self {
+      GlanceField::Set(value) => Some(value),
+      _ => None,
+    }
+  }
+}
+
+impl<T> From<T> for GlanceField<T> {
+  fn from(value: T) -> Self {
+    GlanceField::Set(value)
+  }
+}
+
+impl From<&str> for GlanceField<String> {
+  fn from(value: &str) -> Self {
+    GlanceField::Set(value.to_string())
+  }
+}
+
+impl<T: Serialize> Serialize for GlanceField<T> {
+  fn serialize<S: Serializer>(
+    &self,
+    serializer: S,
+  ) -> std::result::Result<S::Ok, S::Error> {
+    match self {
+      GlanceField::Set(value) => value.serialize(serializer),
+      _ => serializer.serialize_str(""),
+    }
+  }
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for GlanceField<T> {
+  /// Deserializes empty strings and `null` as [`Clear`](#variant.Clear), a
+  /// missing field is [`Unchanged`](#variant.Unchanged) through the field's
+  /// default.
+  fn deserialize<D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> std::result::Result<Self, D::Error> {
+    match serde_json::Value::deserialize(deserializer)? {
+      serde_json::Value::Null => Ok(GlanceField::Clear),
+      serde_json::Value::String(value) if value.is_empty() => {
+        Ok(GlanceField::Clear)
+      }
+      value => serde_json::from_value(value)
+        .map(GlanceField::Set)
+        .map_err(D::Error::custom),
+    }
+  }
+}
+
+/// An update for the [Glances](https://pushover.net/api/glances) widgets of a
+/// user's watch faces and home screens, which show small pieces of data
+/// without sending a notification.
+///
+/// At least one of `title`, `text`, `subtext`, `count` and `percent` has to be
+/// set or cleared, see [`GlanceField`](enum.GlanceField.html). The
+/// [`GlanceBuilder`](struct.GlanceBuilder.html) avoids having to wrap every
+/// field.
+///
+/// ```rust,no_run
+/// use pushover_api::{Glance, GlanceField};
+///
+/// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+/// Glance {
+///   token: "application token".into(),
+///   user: "user key".into(),
+///   title: "Queue".into(),
+///   count: 42.into(),
+///   subtext: GlanceField::Clear,
+///   ..Glance::default()
+/// }
+/// .send()
+/// .unwrap();
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Glance {
+  /// The application's API token.
+  pub token: Secret,
+  /// The key of the user to update the widgets of.
+  pub user: Secret,
+  /// A description of the data, like `Backups`.
+  #[serde(skip_serializing_if = "GlanceField::is_unchanged")]
+  pub title: GlanceField<String>,
+  /// The main line of data.
+  #[serde(skip_serializing_if = "GlanceField::is_unchanged")]
+  pub text: GlanceField<String>,
+  /// A second line of data.
+  #[serde(skip_serializing_if = "GlanceField::is_unchanged")]
+  pub subtext: GlanceField<String>,
+  /// A number shown on the widget, which can be negative.
+  #[serde(skip_serializing_if = "GlanceField::is_unchanged")]
+  pub count: GlanceField<i32>,
+  /// A percentage from 0 to 100, shown as a progress bar or circle.
+  #[serde(skip_serializing_if = "GlanceField::is_unchanged")]
+  pub percent: GlanceField<u8>,
+  /// The name of the device to update, all of the user's devices when `None`.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub device: Option<String>,
+}
+
+impl Glance {
+  /// Creates a builder for a glance, see
+  /// [`GlanceBuilder`](struct.GlanceBuilder.html).
+  pub fn builder<T, U>(token: T, user: U) -> GlanceBuilder
+  where
+    T: Into<String>,
+    U: Into<String>,
+  {
+    GlanceBuilder::new(token, user)
+  }
+
+  /// Checks that the glance has at least one field to update, and that its
+  /// fields fit Pushover's limits.
+  ///
+  /// ```rust
+  /// use pushover_api::{Glance, GlanceField};
+  ///
+  /// assert!(Glance::default().validate().is_err());
+  ///
+  /// let glance = Glance {
+  ///   percent: 101.into(),
+  ///   ..Glance::default()
+  /// };
+  /// assert!(glance.validate().is_err());
+  ///
+  /// let glance = Glance {
+  ///   count: 0.into(),
+  ///   ..Glance::default()
+  /// };
+  /// assert!(glance.validate().is_ok());
+  ///
+  /// let glance = Glance {
+  ///   text: GlanceField::Clear,
+  ///   ..Glance::default()
+  /// };
+  /// assert!(glance.validate().is_ok());
+  /// ```
+  pub fn validate(&self) -> Result<()> {
+    if self.title.is_unchanged()
+      && self.text.is_unchanged()
+      && self.subtext.is_unchanged()
+      && self.count.is_unchanged()
+      && self.percent.is_unchanged()
+    {
+      return Err(PushoverError::InvalidArgument(
+        "A glance needs to set or clear at least one of title, text, \
+         subtext, count or percent"
+          .to_string(),
+      ));
+    }
+
+    let texts = [
+      ("title", &self.title),
+      ("text", &self.text),
+      ("subtext", &self.subtext),
+    ];
+    for (field, text) in texts.iter() {
+      let length = text.as_set().map_or(0, |text| text.chars().count());
+      if length > MAX_GLANCE_TEXT_LENGTH {
+        return Err(PushoverError::InvalidArgument(format!(
+          "The glance's {} has {} characters, at most {} are allowed",
+          field, length, MAX_GLANCE_TEXT_LENGTH
+        )));
+      }
+    }
+
+    match self.percent {
+      GlanceField::Set(percent) if percent > 100 => {
+        Err(PushoverError::InvalidArgument(format!(
+          "The glance's percent is {}, it must be from 0 to 100",
+          percent
+        )))
+      }
+      _ => Ok(()),
+    }
+  }
+
+  /// Validates and sends this glance to the Pushover API, returning the
+  /// identifier of the request.
+  #[cfg(any(feature = "blocking", feature = "ureq"))]
+  pub fn send(&self) -> Result<RequestId> {
+    self.send_using(&http_client()?, &api_base())
+  }
+
+  /// Validates and sends this glance with the given transport to the
+  /// Pushover API at `base`.
+  #[cfg(any(feature = "blocking", feature = "ureq"))]
+  pub(crate) fn send_using(
+    &self,
+    transport: &impl Transport,
+    base: &str,
+  ) -> Result<RequestId> {
+    self.validate()?;
+
+    let request =
+      HttpRequest::new(HttpMethod::Post, join_url(base, "glances.json"))
+        .with_body(HttpBody::Form(form_fields(self)?));
+    let reply = api::execute::<ResponseExtras>(transport, request)?;
+    Ok(reply.response.request.unwrap_or_default())
+  }
+}
+
+/// A builder to construct a [`Glance`](struct.Glance.html), where every
+/// field is left unchanged unless it's set or cleared.
+///
+/// ```rust
+/// use pushover_api::{Glance, GlanceField};
+///
+/// let glance = Glance::builder("application token", "user key")
+///   .title("Backups")
+///   .percent(75)
+///   .clear_subtext()
+///   .device("watch")
+///   .build()
+///   .unwrap();
+///
+/// assert_eq!(glance.title, GlanceField::Set("Backups".to_string()));
+/// assert_eq!(glance.text, GlanceField::Unchanged);
+/// assert_eq!(glance.subtext, GlanceField::Clear);
+/// assert_eq!(glance.device.as_deref(), Some("watch"));
+///
+/// // Only the fields that change are serialized, cleared fields are empty.
+/// let json = serde_json::to_value(&glance).unwrap();
+/// assert_eq!(json["percent"], 75);
+/// assert_eq!(json["subtext"], "");
+/// assert!(json.get("text").is_none());
+/// assert!(json.get("count").is_none());
+///
+/// assert!(Glance::builder("application token", "user key")
+///   .build()
+///   .is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct GlanceBuilder {
+  glance: Glance,
+}
+
+impl GlanceBuilder {
+  /// Creates a new builder with the required fields.
+  pub fn new<T, U>(token: T, user: U) -> Self
+  where
+    T: Into<String>,
+    U: Into<String>,
+  {
+    Self {
+      glance: Glance {
+        token: Secret::new(token),
+        user: Secret::new(user),
+        ..Glance::default()
+      },
+    }
+  }
+
+  /// Sets the title of the glance.
+  pub fn title<S: Into<String>>(mut self, title: S) -> Self {
+    self.glance.title = GlanceField::Set(title.into());
+    self
+  }
+
+  /// Clears the title from the widget.
+  pub fn clear_title(mut self) -> Self {
+    self.glance.title = GlanceField::Clear;
+    self
+  }
+
+  /// Sets the main line of the glance.
+  pub fn text<S: Into<String>>(mut self, text: S) -> Self {
+    self.glance.text = GlanceField::Set(text.into());
+    self
+  }
+
+  /// Clears the main line from the widget.
+  pub fn clear_text(mut self) -> Self {
+    self.glance.text = GlanceField::Clear;
+    self
+  }
+
+  /// Sets the second line of the glance.
+  pub fn subtext<S: Into<String>>(mut self, subtext: S) -> Self {
+    self.glance.subtext = GlanceField::Set(subtext.into());
+    self
+  }
+
+  /// Clears the second line from the widget.
+  pub fn clear_subtext(mut self) -> Self {
+    self.glance.subtext = GlanceField::Clear;
+    self
+  }
+
+  /// Sets the number of the glance.
+  pub fn count(mut self, count: i32) -> Self {
+    self.glance.count = GlanceField::Set(count);
+    self
+  }
+
+  /// Clears the number from the widget.
+  pub fn clear_count(mut self) -> Self {
+    self.glance.count = GlanceField::Clear;
+    self
+  }
+
+  /// Sets the percentage of the glance.
+  pub fn percent(mut self, percent: u8) -> Self {
+    self.glance.percent = GlanceField::Set(percent);
+    self
+  }
+
+  /// Clears the percentage from the widget.
+  pub fn clear_percent(mut self) -> Self {
+    self.glance.percent = GlanceField::Clear;
+    self
+  }
+
+  /// Sets the name of the only device to update.
+  pub fn device<S: Into<String>>(mut self, device: S) -> Self {
+    self.glance.device = Some(device.into());
+    self
+  }
+
+  /// Validates and returns the glance.
+  pub fn build(self) -> Result<Glance> {
+    self.glance.validate()?;
+    Ok(self.glance)
+  }
+}
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+impl<T: Transport> PushoverClient<T> {
+  /// Sends a glance, filling in the token and the default user when the
+  /// glance doesn't have them.
+  ///
+  /// ```rust
+  /// use pushover_api::{test_support::FakeTransport, Glance, PushoverClient};
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853c"}"#);
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853d"}"#);
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport)
+  ///   .default_user("user key");
+  ///
+  /// let glance = Glance {
+  ///   title: "Queue".into(),
+  ///   count: 42.into(),
+  ///   ..Glance::default()
+  /// };
+  /// let request = client.send_glance(&glance).unwrap();
+  /// assert_eq!(request.as_str(), "5042853c");
+  ///
+  /// let glance = Glance::builder("", "")
+  ///   .clear_text()
+  ///   .clear_count()
+  ///   .device("watch")
+  ///   .build()
+  ///   .unwrap();
+  /// client.send_glance(&glance).unwrap();
+  ///
+  /// let requests = transport.requests();
+  /// let sent = &requests[0];
+  /// assert!(sent.url.ends_with("/1/glances.json"));
+  /// assert_eq!(sent.field("token").as_deref(), Some("application token"));
+  /// assert_eq!(sent.field("user").as_deref(), Some("user key"));
+  /// assert_eq!(sent.field("count").as_deref(), Some("42"));
+  /// assert_eq!(sent.field("text"), None);
+  /// assert_eq!(sent.field("device"), None);
+  ///
+  /// // Cleared fields are sent empty, unchanged ones are left out.
+  /// let cleared = &requests[1];
+  /// assert_eq!(cleared.field("text").as_deref(), Some(""));
+  /// assert_eq!(cleared.field("count").as_deref(), Some(""));
+  /// assert_eq!(cleared.field("title"), None);
+  /// assert_eq!(cleared.field("device").as_deref(), Some("watch"));
+  ///
+  /// // Glances without anything to update fail without making a request.
+  /// assert!(client.send_glance(&Glance::default()).is_err());
+  /// assert_eq!(transport.requests().len(), 2);
+  /// ```
+  pub fn send_glance(&self, glance: &Glance) -> Result<RequestId> {
+    let mut glance = glance.clone();
+    if glance.token.is_empty() {
+      glance.token = self.token().into();
+    }
+
+    if glance.user.is_empty() {
+      if let Some(user) = self.user() {
+        glance.user = user.into();
+      }
+    }
+
+    glance.send_using(&self.transport, &self.base_url)
+  }
+}
@@ -0,0 +1,860 @@
+//! Managing delivery groups.
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+use crate::{
+  api, api_base, encode_path_segment, http_client, join_url, HttpBody,
+  HttpMethod, HttpRequest, PushoverClient, ResponseExtras, Result, Transport,
+};
+use crate::{int_bool, non_empty, GroupKey, PushoverError};
+use serde::Deserialize;
+
+/// The maximum number of characters in a delivery group's name.
+pub const MAX_GROUP_NAME_LENGTH: usize = 100;
+
+/// A delivery group, as returned by [`get_group()`](fn.get_group.html).
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct Group {
+  /// The name of the group.
+  #[serde(default)]
+  pub name: String,
+  /// The members of the group.
+  #[serde(default)]
+  pub users: Vec<GroupMember>,
+}
+
+/// A member of a delivery group.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct GroupMember {
+  /// The user's key.
+  pub user: String,
+  /// The name of the only device of the user that receives the group's
+  /// messages, all of the user's devices when `None`.
+  #[serde(default, deserialize_with = "non_empty::deserialize")]
+  pub device: Option<String>,
+  /// A note about the user, only visible to the group's owner.
+  #[serde(default, deserialize_with = "non_empty::deserialize")]
+  pub memo: Option<String>,
+  /// Whether the user is temporarily not receiving the group's messages.
+  #[serde(default, deserialize_with = "int_bool::deserialize_flag")]
+  pub disabled: bool,
+}
+
+/// A delivery group of an application, as returned by
+/// [`list_groups()`](fn.list_groups.html).
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct GroupSummary {
+  /// The key of the group.
+  pub group: GroupKey,
+  /// The name of the group.
+  #[serde(default)]
+  pub name: String,
+}
+
+/// The optional settings of a user that's added to a delivery group.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GroupUserOptions {
+  /// The name of the only device of the user that should receive the group's
+  /// messages, all of the user's devices when `None`.
+  pub device: Option<String>,
+  /// A note about the user, only visible to the group's owner.
+  pub memo: Option<String>,
+}
+
+/// The keys of the Pushover API's response to creating a group.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+#[derive(Debug, Deserialize)]
+struct CreatedGroupPayload {
+  group: GroupKey,
+}
+
+/// Creates a delivery group without any members, returning its key.
+///
+/// The key is what you put in a message's
+/// [`user`](struct.Message.html#structfield.user) to send it to the group,
+/// and what the other group functions take to change it. Returns an error
+/// without making a request when the name isn't valid, see
+/// [`check_group_name()`](fn.check_group_name.html).
+///
+/// ```rust,no_run
+/// use pushover_api::GroupUserOptions;
+///
+/// let token = "application token";
+/// let group = pushover_api::create_group(token, "On-call").unwrap();
+/// pushover_api::group_add_user(
+///   token,
+///   &group,
+///   "user key",
+///   &GroupUserOptions::default(),
+/// )
+/// .unwrap();
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn create_group(token: &str, name: &str) -> Result<GroupKey> {
+  create_group_using(&http_client()?, &api_base(), token, name)
+}
+
+/// Creates a delivery group with the given transport and base URL.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+fn create_group_using(
+  transport: &impl Transport,
+  base: &str,
+  token: &str,
+  name: &str,
+) -> Result<GroupKey> {
+  let name = check_group_name(name)?;
+  let request =
+    HttpRequest::new(HttpMethod::Post, join_url(base, "groups.json"))
+      .with_body(HttpBody::Form(vec![
+        ("token".to_string(), token.to_string()),
+        ("name".to_string(), name.to_string()),
+      ]));
+
+  let payload: CreatedGroupPayload =
+    api::execute(transport, request)?.response.payload;
+  Ok(payload.group)
+}
+
+/// The keys of the Pushover API's response to listing groups.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+#[derive(Debug, Deserialize)]
+struct GroupsPayload {
+  #[serde(default)]
+  groups: Vec<GroupSummary>,
+}
+
+/// Lists the delivery groups of an application.
+///
+/// ```rust,no_run
+/// let token = "application token";
+/// let groups = pushover_api::list_groups(token).unwrap();
+///
+/// let on_call = match groups.iter().find(|group| group.name == "On-call") {
+///   Some(group) => group.group.clone(),
+///   None => pushover_api::create_group(token, "On-call").unwrap(),
+/// };
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn list_groups(token: &str) -> Result<Vec<GroupSummary>> {
+  list_groups_using(&http_client()?, &api_base(), token)
+}
+
+/// Lists the delivery groups of an application with the given transport and
+/// base URL.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+fn list_groups_using(
+  transport: &impl Transport,
+  base: &str,
+  token: &str,
+) -> Result<Vec<GroupSummary>> {
+  let query = serde_urlencoded::to_string([("token", token)])?;
+  let request = HttpRequest::new(
+    HttpMethod::Get,
+    format!("{}?{}", join_url(base, "groups.json"), query),
+  );
+
+  let payload: GroupsPayload =
+    api::execute(transport, request)?.response.payload;
+  Ok(payload.groups)
+}
+
+/// Gets the name and members of a delivery group.
+///
+/// An invalid group key is returned as an
+/// [`Api`](enum.PushoverError.html#variant.Api) error, whose
+/// [`ApiError::kind()`](struct.ApiError.html#method.kind) is
+/// [`InvalidGroup`](enum.ApiErrorKind.html#variant.InvalidGroup).
+///
+/// ```rust,no_run
+/// let group = pushover_api::get_group("application token", "group key").unwrap();
+///
+/// for member in &group.users {
+///   println!("{} ({:?})", member.user, member.memo);
+/// }
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn get_group(token: &str, group: &str) -> Result<Group> {
+  get_group_using(&http_client()?, &api_base(), token, group)
+}
+
+/// The path of a delivery group's endpoint, relative to the base URL.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+fn group_path(group: &str, action: Option<&str>) -> String {
+  match action {
+    Some(action) => {
+      format!("groups/{}/{}.json", encode_path_segment(group), action)
+    }
+    None => format!("groups/{}.json", encode_path_segment(group)),
+  }
+}
+
+/// Adds a user to a delivery group, so they receive the messages sent to the
+/// group's key.
+///
+/// A user that's already a member is returned as an
+/// [`Api`](enum.PushoverError.html#variant.Api) error, whose
+/// [`ApiError::kind()`](struct.ApiError.html#method.kind) is
+/// [`AlreadyGroupMember`](enum.ApiErrorKind.html#variant.AlreadyGroupMember),
+/// and an invalid user key is
+/// [`InvalidUser`](enum.ApiErrorKind.html#variant.InvalidUser).
+///
+/// ```rust,no_run
+/// use pushover_api::GroupUserOptions;
+///
+/// pushover_api::group_add_user(
+///   "application token",
+///   "group key",
+///   "user key",
+///   &GroupUserOptions {
+///     memo: Some("Secondary on-call".to_string()),
+///     ..GroupUserOptions::default()
+///   },
+/// )
+/// .unwrap();
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn group_add_user(
+  token: &str,
+  group: &str,
+  user: &str,
+  options: &GroupUserOptions,
+) -> Result<()> {
+  group_add_user_using(
+    &http_client()?,
+    &api_base(),
+    token,
+    group,
+    user,
+    options,
+  )
+}
+
+/// Adds a user to a delivery group with the given transport and base URL.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+fn group_add_user_using(
+  transport: &impl Transport,
+  base: &str,
+  token: &str,
+  group: &str,
+  user: &str,
+  options: &GroupUserOptions,
+) -> Result<()> {
+  let mut fields = vec![("user", user)];
+  if let Some(device) = &options.device {
+    fields.push(("device", device));
+  }
+  if let Some(memo) = &options.memo {
+    fields.push(("memo", memo));
+  }
+
+  post_group_action(transport, base, token, group, "add_user", &fields)
+}
+
+/// Removes a user from a delivery group, or only one of their devices when
+/// `device` is given.
+///
+/// A user that isn't a member is returned as an
+/// [`Api`](enum.PushoverError.html#variant.Api) error, whose
+/// [`ApiError::kind()`](struct.ApiError.html#method.kind) is
+/// [`NotGroupMember`](enum.ApiErrorKind.html#variant.NotGroupMember).
+///
+/// ```rust,no_run
+/// use pushover_api::ApiErrorKind;
+///
+/// let result = pushover_api::group_remove_user(
+///   "application token",
+///   "group key",
+///   "user key",
+///   None,
+/// );
+///
+/// // The user not being a member is fine, they're gone either way.
+/// if let Err(error) = result {
+///   let mut kinds = error.api_errors().iter().map(|error| error.kind());
+///   if !kinds.any(|kind| kind == ApiErrorKind::NotGroupMember) {
+///     panic!("{}", error);
+///   }
+/// }
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn group_remove_user(
+  token: &str,
+  group: &str,
+  user: &str,
+  device: Option<&str>,
+) -> Result<()> {
+  post_member_action(
+    &http_client()?,
+    &api_base(),
+    token,
+    group,
+    "remove_user",
+    user,
+    device,
+  )
+}
+
+/// Temporarily stops a member of a delivery group, or only one of their
+/// devices when `device` is given, from receiving the group's messages. Their
+/// memo and device stay as they are.
+///
+/// A user that isn't a member is returned as an
+/// [`Api`](enum.PushoverError.html#variant.Api) error, whose
+/// [`ApiError::kind()`](struct.ApiError.html#method.kind) is
+/// [`NotGroupMember`](enum.ApiErrorKind.html#variant.NotGroupMember).
+///
+/// ```rust,no_run
+/// pushover_api::group_disable_user(
+///   "application token",
+///   "group key",
+///   "user key",
+///   None,
+/// )
+/// .unwrap();
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn group_disable_user(
+  token: &str,
+  group: &str,
+  user: &str,
+  device: Option<&str>,
+) -> Result<()> {
+  post_member_action(
+    &http_client()?,
+    &api_base(),
+    token,
+    group,
+    "disable_user",
+    user,
+    device,
+  )
+}
+
+/// Lets a member of a delivery group that was
+/// [disabled](fn.group_disable_user.html) receive the group's messages again.
+///
+/// ```rust,no_run
+/// pushover_api::group_enable_user(
+///   "application token",
+///   "group key",
+///   "user key",
+///   None,
+/// )
+/// .unwrap();
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn group_enable_user(
+  token: &str,
+  group: &str,
+  user: &str,
+  device: Option<&str>,
+) -> Result<()> {
+  post_member_action(
+    &http_client()?,
+    &api_base(),
+    token,
+    group,
+    "enable_user",
+    user,
+    device,
+  )
+}
+
+/// Renames a delivery group.
+///
+/// Returns an error without making a request when the name is empty or
+/// longer than [`MAX_GROUP_NAME_LENGTH`](constant.MAX_GROUP_NAME_LENGTH.html)
+/// characters.
+///
+/// ```rust,no_run
+/// pushover_api::group_rename("application token", "group key", "On-call")
+///   .unwrap();
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn group_rename(token: &str, group: &str, name: &str) -> Result<()> {
+  group_rename_using(&http_client()?, &api_base(), token, group, name)
+}
+
+/// Renames a delivery group with the given transport and base URL.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+fn group_rename_using(
+  transport: &impl Transport,
+  base: &str,
+  token: &str,
+  group: &str,
+  name: &str,
+) -> Result<()> {
+  let name = check_group_name(name)?;
+  post_group_action(transport, base, token, group, "rename", &[("name", name)])
+}
+
+/// Checks that a delivery group's name isn't empty and fits Pushover's limit,
+/// returning it trimmed.
+///
+/// ```rust
+/// use pushover_api::{PushoverError, MAX_GROUP_NAME_LENGTH};
+///
+/// assert_eq!(pushover_api::check_group_name(" On-call ").unwrap(), "On-call");
+/// assert!(matches!(
+///   pushover_api::check_group_name("  "),
+///   Err(PushoverError::InvalidArgument(_))
+/// ));
+/// assert!(
+///   pushover_api::check_group_name(&"a".repeat(MAX_GROUP_NAME_LENGTH + 1))
+///     .is_err()
+/// );
+/// ```
+pub fn check_group_name(name: &str) -> crate::Result<&str> {
+  let name = name.trim();
+  if name.is_empty() {
+    return Err(PushoverError::InvalidArgument(
+      "The group name can't be empty".to_string(),
+    ));
+  }
+
+  let length = name.chars().count();
+  if length > MAX_GROUP_NAME_LENGTH {
+    return Err(PushoverError::InvalidArgument(format!(
+      "The group name has {} characters, at most {} are allowed",
+      length, MAX_GROUP_NAME_LENGTH
+    )));
+  }
+
+  Ok(name)
+}
+
+/// Posts a user and an optional device to one of a delivery group's
+/// endpoints that changes a member, like `remove_user`.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+fn post_member_action(
+  transport: &impl Transport,
+  base: &str,
+  token: &str,
+  group: &str,
+  action: &str,
+  user: &str,
+  device: Option<&str>,
+) -> Result<()> {
+  let mut fields = vec![("user", user)];
+  if let Some(device) = device {
+    fields.push(("device", device));
+  }
+
+  post_group_action(transport, base, token, group, action, &fields)
+}
+
+/// Posts the token and the given fields to one of a delivery group's
+/// endpoints, like `add_user`.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+fn post_group_action(
+  transport: &impl Transport,
+  base: &str,
+  token: &str,
+  group: &str,
+  action: &str,
+  fields: &[(&str, &str)],
+) -> Result<()> {
+  let mut form = vec![("token".to_string(), token.to_string())];
+  form.extend(
+    fields
+      .iter()
+      .map(|(key, value)| (key.to_string(), value.to_string())),
+  );
+
+  let request = HttpRequest::new(
+    HttpMethod::Post,
+    join_url(base, &group_path(group, Some(action))),
+  )
+  .with_body(HttpBody::Form(form));
+
+  api::execute::<ResponseExtras>(transport, request)?;
+  Ok(())
+}
+
+/// Gets a delivery group with the given transport and base URL.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+fn get_group_using(
+  transport: &impl Transport,
+  base: &str,
+  token: &str,
+  group: &str,
+) -> Result<Group> {
+  let query = serde_urlencoded::to_string([("token", token)])?;
+  let request = HttpRequest::new(
+    HttpMethod::Get,
+    format!("{}?{}", join_url(base, &group_path(group, None)), query),
+  );
+
+  Ok(api::execute(transport, request)?.response.payload)
+}
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+impl<T: Transport> PushoverClient<T> {
+  /// Gets the name and members of a delivery group, see
+  /// [`get_group()`](fn.get_group.html).
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, ApiErrorKind, PushoverClient,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"name":"On-call","users":[
+  ///     {"user":"uQiRzpo4DXghDmr9QzzfQu27cmVRsG","device":null,
+  ///       "memo":"Primary","disabled":false},
+  ///     {"user":"u4fKzVjGPUbnEoQ8ri9WJ2PmFBDhXh","device":"pixel",
+  ///       "memo":"","disabled":true}
+  ///   ],"status":1,"request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"name":"Empty","users":[],"status":1,"request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"group":"not found",
+  ///     "errors":["group not found or you are not authorized to edit it"],
+  ///     "status":0,"request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport);
+  ///
+  /// let group = client.get_group("gznej3rKEVAvPUxu9vvNnqpmZpokzF").unwrap();
+  /// assert_eq!(group.name, "On-call");
+  /// assert_eq!(group.users.len(), 2);
+  /// assert_eq!(group.users[0].device, None);
+  /// assert_eq!(group.users[0].memo.as_deref(), Some("Primary"));
+  /// assert!(!group.users[0].disabled);
+  /// assert_eq!(group.users[1].device.as_deref(), Some("pixel"));
+  /// assert_eq!(group.users[1].memo, None);
+  /// assert!(group.users[1].disabled);
+  ///
+  /// let group = client.get_group("gznej3rKEVAvPUxu9vvNnqpmZpokzF").unwrap();
+  /// assert!(group.users.is_empty());
+  ///
+  /// let error = client.get_group("invalid").unwrap_err();
+  /// assert_eq!(error.api_errors()[0].kind(), ApiErrorKind::InvalidGroup);
+  ///
+  /// let request = &transport.requests()[0];
+  /// assert!(request.url.ends_with(
+  ///   "/1/groups/gznej3rKEVAvPUxu9vvNnqpmZpokzF.json?token=application+token"
+  /// ));
+  /// ```
+  pub fn get_group(&self, group: &str) -> Result<Group> {
+    get_group_using(&self.transport, &self.base_url, self.token(), group)
+  }
+
+  /// Adds a user to a delivery group, see
+  /// [`group_add_user()`](fn.group_add_user.html).
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, ApiErrorKind, GroupUserOptions,
+  ///   PushoverClient,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853c"}"#);
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"user":"is already a member of this group",
+  ///     "errors":["user is already a member of this group"],"status":0,
+  ///     "request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"user":"invalid","errors":["user key is invalid"],"status":0,
+  ///     "request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport);
+  /// let options = GroupUserOptions {
+  ///   device: Some("pixel".to_string()),
+  ///   memo: Some("Secondary on-call".to_string()),
+  /// };
+  ///
+  /// client.group_add_user("group key", "user key", &options).unwrap();
+  ///
+  /// let error = client
+  ///   .group_add_user("group key", "user key", &options)
+  ///   .unwrap_err();
+  /// assert_eq!(error.api_errors()[0].kind(), ApiErrorKind::AlreadyGroupMember);
+  ///
+  /// let error = client
+  ///   .group_add_user("group key", "invalid", &GroupUserOptions::default())
+  ///   .unwrap_err();
+  /// assert_eq!(error.api_errors()[0].kind(), ApiErrorKind::InvalidUser);
+  ///
+  /// let requests = transport.requests();
+  /// assert!(requests[0].url.ends_with("/1/groups/group%20key/add_user.json"));
+  /// assert_eq!(requests[0].field("user").as_deref(), Some("user key"));
+  /// assert_eq!(requests[0].field("device").as_deref(), Some("pixel"));
+  /// assert_eq!(requests[0].field("memo").as_deref(), Some("Secondary on-call"));
+  /// assert_eq!(requests[2].field("memo"), None);
+  /// ```
+  pub fn group_add_user(
+    &self,
+    group: &str,
+    user: &str,
+    options: &GroupUserOptions,
+  ) -> Result<()> {
+    group_add_user_using(
+      &self.transport,
+      &self.base_url,
+      self.token(),
+      group,
+      user,
+      options,
+    )
+  }
+
+  /// Removes a user or one of their devices from a delivery group, see
+  /// [`group_remove_user()`](fn.group_remove_user.html).
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, ApiErrorKind, PushoverClient,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853c"}"#);
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853c"}"#);
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"user":"is not a member of this group",
+  ///     "errors":["user is not a member of this group"],"status":0,
+  ///     "request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport);
+  ///
+  /// client.group_remove_user("group key", "user key", None).unwrap();
+  /// client
+  ///   .group_remove_user("group key", "user key", Some("pixel"))
+  ///   .unwrap();
+  ///
+  /// let error = client
+  ///   .group_remove_user("group key", "stranger", None)
+  ///   .unwrap_err();
+  /// assert_eq!(error.api_errors()[0].kind(), ApiErrorKind::NotGroupMember);
+  ///
+  /// let requests = transport.requests();
+  /// assert!(requests[0].url.ends_with("/1/groups/group%20key/remove_user.json"));
+  /// assert_eq!(requests[0].field("user").as_deref(), Some("user key"));
+  /// assert_eq!(requests[0].field("device"), None);
+  /// assert_eq!(requests[1].field("device").as_deref(), Some("pixel"));
+  /// ```
+  pub fn group_remove_user(
+    &self,
+    group: &str,
+    user: &str,
+    device: Option<&str>,
+  ) -> Result<()> {
+    post_member_action(
+      &self.transport,
+      &self.base_url,
+      self.token(),
+      group,
+      "remove_user",
+      user,
+      device,
+    )
+  }
+
+  /// Temporarily stops a member of a delivery group from receiving its
+  /// messages, see [`group_disable_user()`](fn.group_disable_user.html).
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, ApiErrorKind, PushoverClient,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853c"}"#);
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853c"}"#);
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"user":"is not a member of this group",
+  ///     "errors":["user is not a member of this group"],"status":0,
+  ///     "request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport);
+  ///
+  /// client
+  ///   .group_disable_user("group key", "user key", Some("pixel"))
+  ///   .unwrap();
+  /// client.group_enable_user("group key", "user key", None).unwrap();
+  ///
+  /// let error = client
+  ///   .group_enable_user("group key", "stranger", None)
+  ///   .unwrap_err();
+  /// assert_eq!(error.api_errors()[0].kind(), ApiErrorKind::NotGroupMember);
+  ///
+  /// let requests = transport.requests();
+  /// assert!(requests[0]
+  ///   .url
+  ///   .ends_with("/1/groups/group%20key/disable_user.json"));
+  /// assert_eq!(requests[0].field("token").as_deref(), Some("application token"));
+  /// assert_eq!(requests[0].field("user").as_deref(), Some("user key"));
+  /// assert_eq!(requests[0].field("device").as_deref(), Some("pixel"));
+  /// assert!(requests[1].url.ends_with("/1/groups/group%20key/enable_user.json"));
+  /// assert_eq!(requests[1].field("device"), None);
+  /// ```
+  pub fn group_disable_user(
+    &self,
+    group: &str,
+    user: &str,
+    device: Option<&str>,
+  ) -> Result<()> {
+    post_member_action(
+      &self.transport,
+      &self.base_url,
+      self.token(),
+      group,
+      "disable_user",
+      user,
+      device,
+    )
+  }
+
+  /// Lists the delivery groups of the application, see
+  /// [`list_groups()`](fn.list_groups.html).
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, ApiErrorKind, PushoverClient,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(200, r#"{"groups":[],"status":1,"request":"5042853c"}"#);
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"groups":[
+  ///     {"group":"gznej3rKEVAvPUxu9vvNnqpmZpokzF","name":"On-call"},
+  ///     {"group":"g7Q9bUhFv5ysdQ3zNsi8Yefja2Ezxm","name":"Releases"}
+  ///   ],"status":1,"request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"token":"invalid","errors":["application token is invalid"],
+  ///     "status":0,"request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport);
+  ///
+  /// assert!(client.list_groups().unwrap().is_empty());
+  ///
+  /// let groups = client.list_groups().unwrap();
+  /// assert_eq!(groups.len(), 2);
+  /// assert_eq!(groups[0].group.as_str(), "gznej3rKEVAvPUxu9vvNnqpmZpokzF");
+  /// assert_eq!(groups[1].name, "Releases");
+  ///
+  /// let error = client.list_groups().unwrap_err();
+  /// assert_eq!(error.api_errors()[0].kind(), ApiErrorKind::InvalidToken);
+  ///
+  /// let request = &transport.requests()[0];
+  /// assert!(request.url.ends_with("/1/groups.json?token=application+token"));
+  /// ```
+  pub fn list_groups(&self) -> Result<Vec<GroupSummary>> {
+    list_groups_using(&self.transport, &self.base_url, self.token())
+  }
+
+  /// Creates a delivery group without any members, see
+  /// [`create_group()`](fn.create_group.html).
+  ///
+  /// ```rust
+  /// use pushover_api::{test_support::FakeTransport, PushoverClient};
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"group":"gznej3rKEVAvPUxu9vvNnqpmZpokzF","status":1,
+  ///     "request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"errors":["application has reached its maximum number of groups"],
+  ///     "status":0,"request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport);
+  ///
+  /// let group = client.create_group("On-call").unwrap();
+  /// assert_eq!(group.as_str(), "gznej3rKEVAvPUxu9vvNnqpmZpokzF");
+  ///
+  /// let error = client.create_group("Another").unwrap_err();
+  /// assert!(error.api_errors()[0].message.contains("maximum number of groups"));
+  ///
+  /// assert!(client.create_group(" ").is_err());
+  ///
+  /// let requests = transport.requests();
+  /// assert_eq!(requests.len(), 2);
+  /// assert!(requests[0].url.ends_with("/1/groups.json"));
+  /// assert_eq!(requests[0].field("name").as_deref(), Some("On-call"));
+  /// ```
+  pub fn create_group(&self, name: &str) -> Result<GroupKey> {
+    create_group_using(&self.transport, &self.base_url, self.token(), name)
+  }
+
+  /// Renames a delivery group, see [`group_rename()`](fn.group_rename.html).
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, ApiErrorKind, PushoverClient,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853c"}"#);
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"group":"not found",
+  ///     "errors":["group not found or you are not authorized to edit it"],
+  ///     "status":0,"request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport);
+  ///
+  /// client.group_rename("group key", " On-call ").unwrap();
+  ///
+  /// let error = client.group_rename("invalid", "On-call").unwrap_err();
+  /// assert_eq!(error.api_errors()[0].kind(), ApiErrorKind::InvalidGroup);
+  ///
+  /// assert!(client.group_rename("group key", "").is_err());
+  ///
+  /// let requests = transport.requests();
+  /// assert_eq!(requests.len(), 2);
+  /// assert!(requests[0].url.ends_with("/1/groups/group%20key/rename.json"));
+  /// assert_eq!(requests[0].field("token").as_deref(), Some("application token"));
+  /// assert_eq!(requests[0].field("name").as_deref(), Some("On-call"));
+  /// ```
+  pub fn group_rename(&self, group: &str, name: &str) -> Result<()> {
+    group_rename_using(
+      &self.transport,
+      &self.base_url,
+      self.token(),
+      group,
+      name,
+    )
+  }
+
+  /// Lets a disabled member of a delivery group receive its messages again,
+  /// see [`group_enable_user()`](fn.group_enable_user.html).
+  pub fn group_enable_user(
+    &self,
+    group: &str,
+    user: &str,
+    device: Option<&str>,
+  ) -> Result<()> {
+    post_member_action(
+      &self.transport,
+      &self.base_url,
+      self.token(),
+      group,
+      "enable_user",
+      user,
+      device,
+    )
+  }
+}
@@ -0,0 +1,146 @@
+//! Composing HTML-formatted messages.
+
+use crate::Message;
+use std::fmt;
+
+/// A builder for the limited set of [HTML](https://pushover.net/api#html)
+/// that Pushover supports, escaping any text that's put into it.
+///
+/// Anything that takes content accepts either plain text, which gets escaped,
+/// or another `Html` to nest tags.
+///
+/// ```rust
+/// use pushover_api::Html;
+///
+/// let html = Html::new()
+///   .bold("<host> down")
+///   .text(": ")
+///   .link("https://example.com/?a=1&b=2", Html::new().italic("dashboard"))
+///   .build();
+///
+/// assert_eq!(
+///   html,
+///   "<b>&lt;host&gt; down</b>: \
+///     <a href=\"https://example.com/?a=1&amp;b=2\"><i>dashboard</i></a>"
+/// );
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Html {
+  markup: String,
+}
+
+impl Html {
+  /// Creates an empty builder.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds escaped text.
+  pub fn text(mut self, text: &str) -> Self {
+    self.markup.push_str(&escape_html(text));
+    self
+  }
+
+  /// Adds content in bold.
+  pub fn bold<T: Into<Html>>(self, content: T) -> Self {
+    self.tag("b", None, content.into())
+  }
+
+  /// Adds content in italics.
+  pub fn italic<T: Into<Html>>(self, content: T) -> Self {
+    self.tag("i", None, content.into())
+  }
+
+  /// Adds underlined content.
+  pub fn underline<T: Into<Html>>(self, content: T) -> Self {
+    self.tag("u", None, content.into())
+  }
+
+  /// Adds content in a color, like `#ff0000` or `red`.
+  pub fn color<T: Into<Html>>(self, color: &str, content: T) -> Self {
+    self.tag("font", Some(("color", color)), content.into())
+  }
+
+  /// Adds a link to a URL.
+  pub fn link<T: Into<Html>>(self, url: &str, content: T) -> Self {
+    self.tag("a", Some(("href", url)), content.into())
+  }
+
+  /// Returns the markup.
+  pub fn build(self) -> String {
+    self.markup
+  }
+
+  /// Adds content wrapped in a tag with an optional attribute.
+  fn tag(
+    mut self,
+    name: &str,
+    attribute: Option<(&str, &str)>,
+    content: Html,
+  ) -> Self {
+    self.markup.push('<');
+    self.markup.push_str(name);
+    if let Some((key, value)) = attribute {
+      self
+        .markup
+        .push_str(&format!(" {}=\"{}\"", key, escape_html(value)));
+    }
+
+    self.markup.push('>');
+    self.markup.push_str(&content.markup);
+    self.markup.push_str(&format!("</{}>", name));
+    self
+  }
+}
+
+impl From<&str> for Html {
+  fn from(text: &str) -> Self {
+    Html::new().text(text)
+  }
+}
+
+impl From<String> for Html {
+  fn from(text: String) -> Self {
+    Html::new().text(&text)
+  }
+}
+
+impl fmt::Display for Html {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.markup)
+  }
+}
+
+/// Escapes the characters that have a special meaning in HTML text and
+/// attribute values.
+///
+/// ```rust
+/// use pushover_api::escape_html;
+///
+/// assert_eq!(escape_html("<b>\"Tom\" & 'Jerry'</b>"),
+///   "&lt;b&gt;&quot;Tom&quot; &amp; &#39;Jerry&#39;&lt;/b&gt;");
+/// ```
+pub fn escape_html(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for character in text.chars() {
+    match character {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      '\'' => escaped.push_str("&#39;"),
+      character => escaped.push(character),
+    }
+  }
+
+  escaped
+}
+
+impl Message {
+  /// Sets the message to the markup of an [`Html`](struct.Html.html) builder
+  /// and enables HTML formatting.
+  pub fn set_html_message(&mut self, html: Html) {
+    self.message = html.build();
+    self.html = Some(true);
+  }
+}
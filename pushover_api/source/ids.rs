@@ -0,0 +1,187 @@
+//! Request identifiers, receipts and keys the Pushover API returns.
+
+use crate::{api_url, Secret};
+use serde::{Deserialize, Serialize};
+use std::{fmt, ops::Deref};
+
+/// Generates a newtype around an identifier the Pushover API returns.
+macro_rules! id_type {
+  ($(#[$meta:meta])* $name:ident) => {
+    $(#[$meta])*
+    #[derive(
+      Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize,
+    )]
+    #[serde(transparent)]
+    pub struct $name(String);
+
+    impl $name {
+      /// Returns the identifier as a string slice.
+      pub fn as_str(&self) -> &str {
+        &self.0
+      }
+    }
+
+    impl Deref for $name {
+      type Target = str;
+
+      fn deref(&self) -> &str {
+        &self.0
+      }
+    }
+
+    impl fmt::Display for $name {
+      fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+      }
+    }
+
+    impl From<String> for $name {
+      fn from(id: String) -> Self {
+        Self(id)
+      }
+    }
+
+    impl From<&str> for $name {
+      fn from(id: &str) -> Self {
+        Self(id.to_string())
+      }
+    }
+
+    impl From<$name> for String {
+      fn from(id: $name) -> Self {
+        id.0
+      }
+    }
+  };
+}
+
+id_type!(
+  /// The identifier Pushover gives every request, which is a UUID. It's kept
+  /// as an opaque string, so an identifier that isn't a UUID doesn't cause
+  /// any errors.
+  ///
+  /// ```rust
+  /// use pushover_api::RequestId;
+  ///
+  /// let id = RequestId::from("5042853c-402d-4a18-abcb-168734a801de");
+  /// assert_eq!(id.to_string(), "5042853c-402d-4a18-abcb-168734a801de");
+  ///
+  /// let malformed = RequestId::from("not a uuid");
+  /// assert_eq!(malformed.as_str(), "not a uuid");
+  /// # #[cfg(feature = "uuid")]
+  /// # {
+  /// assert!(id.to_uuid().is_some());
+  /// assert!(malformed.to_uuid().is_none());
+  /// # }
+  /// ```
+  RequestId
+);
+
+id_type!(
+  /// The receipt of an emergency priority message, which is used to check
+  /// whether it has been acknowledged and to cancel its retries.
+  ///
+  /// ```rust
+  /// use pushover_api::Receipt;
+  ///
+  /// let receipt = Receipt::from("rLqVuqTRh62UzxtmqiaLzQmVcPgiCy");
+  /// assert_eq!(
+  ///   receipt.status_url(),
+  ///   "https://api.pushover.net/1/receipts/rLqVuqTRh62UzxtmqiaLzQmVcPgiCy.json"
+  /// );
+  /// assert_eq!(
+  ///   receipt.cancel_url(),
+  ///   "https://api.pushover.net/1/receipts/rLqVuqTRh62UzxtmqiaLzQmVcPgiCy/\
+  ///    cancel.json"
+  /// );
+  /// ```
+  Receipt
+);
+
+id_type!(
+  /// The key of a delivery group, as returned by
+  /// [`create_group()`](fn.create_group.html).
+  ///
+  /// Messages are sent to a group by using its key as their
+  /// [`user`](struct.Message.html#structfield.user).
+  ///
+  /// ```rust
+  /// use pushover_api::{GroupKey, Message};
+  ///
+  /// let group = GroupKey::from("gznej3rKEVAvPUxu9vvNnqpmZpokzF");
+  /// let message = Message {
+  ///   user: group.as_str().into(),
+  ///   ..Message::default()
+  /// };
+  /// assert_eq!(message.user.expose_secret(), "gznej3rKEVAvPUxu9vvNnqpmZpokzF");
+  /// ```
+  GroupKey
+);
+
+id_type!(
+  /// The key of a user's subscription to an application, as returned by
+  /// [`migrate_subscription()`](fn.migrate_subscription.html), which is used
+  /// instead of their user key to send them messages.
+  ///
+  /// ```rust
+  /// use pushover_api::{Message, SubscribedUserKey};
+  ///
+  /// let key = SubscribedUserKey::from("uQiRzpo4DXghDmr9QzzfQu27cmVRsG");
+  /// let message = Message {
+  ///   user: key.into(),
+  ///   ..Message::default()
+  /// };
+  /// assert_eq!(message.user.expose_secret(), "uQiRzpo4DXghDmr9QzzfQu27cmVRsG");
+  /// ```
+  SubscribedUserKey
+);
+
+id_type!(
+  /// The identifier of a device registered with the Open Client API, as
+  /// returned by [`register_device()`](open_client/fn.register_device.html).
+  DeviceId
+);
+
+impl From<SubscribedUserKey> for Secret {
+  fn from(key: SubscribedUserKey) -> Self {
+    Secret::new(key.0)
+  }
+}
+
+impl RequestId {
+  /// Parses the identifier as a UUID, returning `None` when it isn't one.
+  #[cfg(feature = "uuid")]
+  pub fn to_uuid(&self) -> Option<uuid::Uuid> {
+    uuid::Uuid::parse_str(&self.0).ok()
+  }
+}
+
+impl Receipt {
+  /// The URL to get the status of the receipt from.
+  pub fn status_url(&self) -> String {
+    api_url(&self.status_path())
+  }
+
+  /// The URL to cancel the retries of the message with.
+  pub fn cancel_url(&self) -> String {
+    api_url(&self.cancel_path())
+  }
+
+  /// The path to get the status of the receipt from, relative to the base
+  /// URL.
+  pub(crate) fn status_path(&self) -> String {
+    format!("receipts/{}.json", self.0)
+  }
+
+  /// The path to cancel the retries of the message with, relative to the base
+  /// URL.
+  pub(crate) fn cancel_path(&self) -> String {
+    format!("receipts/{}/cancel.json", self.0)
+  }
+
+  /// The path to acknowledge the message with, relative to the base URL.
+  #[cfg(any(feature = "blocking", feature = "ureq"))]
+  pub(crate) fn acknowledge_path(&self) -> String {
+    format!("receipts/{}/acknowledge.json", self.0)
+  }
+}
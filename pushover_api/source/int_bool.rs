@@ -0,0 +1,60 @@
+//! Serde helpers for booleans that the Pushover API represents as `1` and
+//! `0` integers.
+
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+/// Serializes an optional boolean as `1` or `0`.
+pub(crate) fn serialize<S>(
+  value: &Option<bool>,
+  serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+  S: Serializer,
+{
+  match value {
+    Some(value) => serializer.serialize_u8(*value as u8),
+    None => serializer.serialize_none(),
+  }
+}
+
+/// Deserializes an optional boolean from an integer, a boolean or a string of
+/// either, where any non-zero integer is considered `true`.
+pub(crate) fn deserialize<'de, D>(
+  deserializer: D,
+) -> Result<Option<bool>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  #[derive(Deserialize)]
+  #[serde(untagged)]
+  enum IntOrBool {
+    Int(i64),
+    Bool(bool),
+    String(String),
+  }
+
+  Option::<IntOrBool>::deserialize(deserializer)?
+    .map(|value| match value {
+      IntOrBool::Int(value) => Ok(value != 0),
+      IntOrBool::Bool(value) => Ok(value),
+      IntOrBool::String(value) => match value.as_str() {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        value => value.parse::<i64>().map(|value| value != 0).map_err(|_| {
+          D::Error::custom(format!("invalid boolean {:?}", value))
+        }),
+      },
+    })
+    .transpose()
+}
+
+/// Deserializes a boolean like [`deserialize`](fn.deserialize.html), where a
+/// `null` value is considered `false`.
+pub(crate) fn deserialize_flag<'de, D>(
+  deserializer: D,
+) -> Result<bool, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  deserialize(deserializer).map(Option::unwrap_or_default)
+}
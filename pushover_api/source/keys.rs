@@ -0,0 +1,142 @@
+//! Validated application tokens and user keys.
+
+use serde::{Deserialize, Serialize};
+use std::{convert::TryFrom, fmt, str::FromStr};
+
+/// The number of characters in application tokens and user keys.
+pub const KEY_LENGTH: usize = 30;
+
+/// The reason an application token or user key is invalid.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum KeyError {
+  /// The key doesn't have exactly [`KEY_LENGTH`](constant.KEY_LENGTH.html)
+  /// characters, contains the number of characters it does have.
+  WrongLength(usize),
+  /// The key contains a character that isn't an ASCII letter or number.
+  IllegalCharacter(char),
+}
+
+impl fmt::Display for KeyError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      KeyError::WrongLength(length) => write!(
+        f,
+        "Keys must be {} characters long, got {}",
+        KEY_LENGTH, length
+      ),
+      KeyError::IllegalCharacter(character) => write!(
+        f,
+        "Keys can only contain letters and numbers, got {:?}",
+        character
+      ),
+    }
+  }
+}
+
+impl std::error::Error for KeyError {}
+
+/// Checks that a key is [`KEY_LENGTH`](constant.KEY_LENGTH.html) ASCII
+/// letters and numbers.
+fn validate_key(key: &str) -> Result<(), KeyError> {
+  if let Some(character) = key.chars().find(|c| !c.is_ascii_alphanumeric()) {
+    return Err(KeyError::IllegalCharacter(character));
+  }
+
+  if key.len() != KEY_LENGTH {
+    return Err(KeyError::WrongLength(key.chars().count()));
+  }
+
+  Ok(())
+}
+
+/// Defines a validated key newtype with all its conversions.
+macro_rules! key_type {
+  ($(#[$attribute:meta])* $name:ident) => {
+    $(#[$attribute])*
+    #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+    #[serde(try_from = "String", into = "String")]
+    pub struct $name(String);
+
+    impl $name {
+      /// Validates and creates a new key.
+      pub fn new<S: Into<String>>(key: S) -> Result<Self, KeyError> {
+        let key = key.into();
+        validate_key(&key)?;
+        Ok(Self(key))
+      }
+
+      /// Returns the key as a string slice.
+      pub fn as_str(&self) -> &str {
+        &self.0
+      }
+    }
+
+    impl AsRef<str> for $name {
+      fn as_ref(&self) -> &str {
+        &self.0
+      }
+    }
+
+    impl fmt::Display for $name {
+      fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+      }
+    }
+
+    impl FromStr for $name {
+      type Err = KeyError;
+
+      fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::new(input)
+      }
+    }
+
+    impl TryFrom<String> for $name {
+      type Error = KeyError;
+
+      fn try_from(input: String) -> Result<Self, Self::Error> {
+        Self::new(input)
+      }
+    }
+
+    impl From<$name> for String {
+      fn from(key: $name) -> Self {
+        key.0
+      }
+    }
+  };
+}
+
+key_type!(
+  /// An application's API token, which is 30 ASCII letters and numbers.
+  ///
+  /// ```rust
+  /// use pushover_api::{AppToken, KeyError};
+  ///
+  /// let token: AppToken = "azGDORePK8gMaC0QOYAMyEEuzJnyUi".parse().unwrap();
+  /// assert_eq!(token.as_str(), "azGDORePK8gMaC0QOYAMyEEuzJnyUi");
+  ///
+  /// assert_eq!(AppToken::new("short"), Err(KeyError::WrongLength(5)));
+  /// ```
+  AppToken
+);
+
+key_type!(
+  /// A user or group key, which is 30 ASCII letters and numbers.
+  ///
+  /// Since it converts into a `String`, it can be used anywhere a
+  /// [`MessageBuilder`](struct.MessageBuilder.html) accepts a user.
+  ///
+  /// ```rust
+  /// use pushover_api::{KeyError, Message, UserKey};
+  ///
+  /// let user = UserKey::new("uQiRzpo4DXghDmr9QzzfQu27cmVRsG").unwrap();
+  /// let message = Message::builder("application token", user, "Message");
+  ///
+  /// assert_eq!(
+  ///   UserKey::new("uQiRzpo4DXghDmr9QzzfQu27cmVRs!"),
+  ///   Err(KeyError::IllegalCharacter('!'))
+  /// );
+  /// ```
+  UserKey
+);
@@ -14,20 +14,25 @@
 //! [`send_simple_message`](fn.send_simple_message.html) function.
 //!
 //! ```rust,no_run
+//! # #[cfg(any(feature = "blocking", feature = "ureq"))]
+//! # {
 //! use pushover_api::send_simple_message;
 //!
 //! send_simple_message("application token", "user key", "Message").unwrap();
+//! # }
 //! ```
 //!
 //! To send a more complex message, create a [`Message`](struct.Message.html)
 //! and [`send()` it](struct.Message.html#method.send).
 //!
 //! ```rust,no_run
+//! # #[cfg(any(feature = "blocking", feature = "ureq"))]
+//! # {
 //! use pushover_api::Message;
 //!
 //! let message = Message {
-//!   token: "application token".to_string(),
-//!   user: "user key".to_string(),
+//!   token: "application token".into(),
+//!   user: "user key".into(),
 //!   message: "Message".to_string(),
 //!   title: Some("Title".to_string()),
 //!   url: Some("https://example.com".to_string()),
@@ -36,85 +41,1633 @@
 //!
 //! let response = message.send().unwrap();
 //! dbg!(response);
+//! # }
 //! ```
+//!
+//! Or use the [`MessageBuilder`](struct.MessageBuilder.html) to do the same.
+//!
+//! ```rust,no_run
+//! # #[cfg(any(feature = "blocking", feature = "ureq"))]
+//! # {
+//! use pushover_api::Message;
+//!
+//! Message::builder("application token", "user key", "Message")
+//!   .title("Title")
+//!   .url("https://example.com")
+//!   .build()
+//!   .unwrap()
+//!   .send()
+//!   .unwrap();
+//! # }
+//! ```
+//!
+//! ## Features
+//!
+//! The `blocking` and `async` features enable the blocking client and
+//! [`send_async()`](struct.Message.html#method.send_async), both are enabled
+//! by default. Services that only send asynchronously can disable `blocking`
+//! to leave out the background thread of the blocking client, keeping one of
+//! the `native-tls` (default) and `rustls` features to make HTTPS requests.
+//!
+//! ```toml
+//! [dependencies.pushover_api]
+//! version = "0.1"
+//! default-features = false
+//! features = ["async", "rustls"]
+//! ```
+//!
+//! The `background` feature adds a
+//! [`BackgroundSender`](struct.BackgroundSender.html) that queues messages
+//! and sends them from a Tokio task.
+//!
+//! The `sink` feature adds a [`MessageSink`](struct.MessageSink.html) to
+//! forward a stream of messages into.
+//!
+//! The `websocket` feature adds
+//! [`open_client::listen()`](open_client/fn.listen.html), to be told about
+//! new messages for an Open Client device as they arrive.
+//!
+//! The `ureq` feature makes the blocking client use [ureq](https://docs.rs/ureq)
+//! instead of Reqwest, which needs far fewer dependencies. It takes priority
+//! when both `ureq` and `blocking` are enabled, so disable the default
+//! features to leave out Reqwest entirely.
+//!
+//! ```toml
+//! [dependencies.pushover_api]
+//! version = "0.1"
+//! default-features = false
+//! features = ["ureq", "rustls"]
+//! ```
+//!
+//! ## WebAssembly
+//!
+//! On `wasm32-unknown-unknown`, only enable the `async` feature, the requests
+//! are made with the browser's `fetch` which handles TLS itself.
+//!
+//! ```toml
+//! [dependencies.pushover_api]
+//! version = "0.1"
+//! default-features = false
+//! features = ["async"]
+//! ```
+//!
+//! ## Tracing
+//!
+//! With the `tracing` feature enabled, sending messages and other requests
+//! create [`tracing`](https://docs.rs/tracing) spans with the endpoint, the
+//! priority and the start of the user key, and record events for the response
+//! status, the rate limit headers and retries. The application token is never
+//! recorded and response bodies are only recorded at the trace level.
+//!
+//! ```rust
+//! # #[cfg(any(feature = "blocking", feature = "ureq"))]
+//! # {
+//! # use std::{io::{self, Write}, sync::{Arc, Mutex}};
+//! # use pushover_api::{test_support::MockServer, HttpResponse};
+//! use pushover_api::Message;
+//!
+//! # #[derive(Clone, Default)]
+//! # struct Logs(Arc<Mutex<Vec<u8>>>);
+//! # impl Write for Logs {
+//! #   fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+//! #     self.0.lock().unwrap().write(buf)
+//! #   }
+//! #   fn flush(&mut self) -> io::Result<()> {
+//! #     Ok(())
+//! #   }
+//! # }
+//! # let body = r#"{"status":1,"request":"5042853c"}"#;
+//! # MockServer::respond(vec![HttpResponse::json(http::StatusCode::OK, body)
+//! #   .with_header("X-Limit-App-Remaining", "7496")])
+//! # .set_api_url_var();
+//! # let logs = Logs::default();
+//! # let writer = logs.clone();
+//! let subscriber = tracing_subscriber::fmt()
+//!   .with_max_level(tracing::Level::TRACE)
+//! #   .with_ansi(false)
+//! #   .with_writer(move || writer.clone())
+//!   .finish();
+//!
+//! tracing::subscriber::with_default(subscriber, || {
+//!   Message {
+//!     token: "azGDORePK8gMaC0QOYAMyEEuzJnyUi".into(),
+//!     user: "uQiRzpo4DXghDmr9QzzfQu27cmVRsG".into(),
+//!     message: "Message".to_string(),
+//!     ..Message::default()
+//!   }
+//!   .send()
+//!   .unwrap();
+//! });
+//! # let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+//! # #[cfg(feature = "tracing")]
+//! # {
+//! #   assert!(logs.contains(r#"send{endpoint="messages.json" user=uQiRz…"#));
+//! #   assert!(logs.contains("remaining=Some(7496)"));
+//! # }
+//! # assert!(!logs.contains("uQiRzpo4DXghDmr9QzzfQu27cmVRsG"));
+//! # assert!(!logs.contains("azGDORePK8gMaC0QOYAMyEEuzJnyUi"));
+//! # }
+//! ```
+
+// Without a client only the types are left, which leaves the code to make
+// requests unused.
+#![cfg_attr(
+  not(any(feature = "async", feature = "blocking", feature = "ureq")),
+  allow(dead_code)
+)]
 
-use anyhow::{anyhow, Result};
-use lazy_static::lazy_static;
-use reqwest::{blocking::Client, StatusCode};
-use serde::{Deserialize, Serialize};
+#[cfg(all(feature = "native-tls", feature = "rustls"))]
+compile_error!("Only one of the native-tls and rustls features can be enabled");
+
+#[cfg(all(
+  target_arch = "wasm32",
+  any(feature = "blocking", feature = "ureq")
+))]
+compile_error!("The blocking features aren't available on WebAssembly");
+
+use api::ApiReply;
+use http::StatusCode;
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize};
 use serde_repr::*;
+use std::{
+  borrow::Cow,
+  convert::TryFrom,
+  fmt,
+  str::FromStr,
+  time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+use std::sync::RwLock;
+
+/// The envelope shared by Pushover API responses.
+mod api;
+/// Serde helpers for the errors of Pushover API responses.
+mod api_errors;
+/// Checking the message limits of applications.
+mod apps;
+/// Sending messages asynchronously, which also works on WebAssembly.
+#[cfg(feature = "async")]
+mod asynchronous;
+/// Image attachments for messages.
+mod attachment;
+/// Sending messages from a background task.
+#[cfg(all(feature = "background", not(target_arch = "wasm32")))]
+mod background;
+/// Sending many messages at once.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+mod batch;
+/// Borrowed messages.
+mod borrowed;
+/// A fluent builder for messages.
+mod builder;
+/// Failing fast while the Pushover API is down.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+mod circuit;
+/// A client that remembers the application token and default user.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+mod client;
+/// Serde helpers for comma-separated fields.
+mod comma_separated;
+/// Rendering messages as curl commands.
+mod curl;
+/// Suppressing repeated identical messages.
+mod dedup;
+/// Serde helpers for Unix timestamps.
+mod epoch;
+/// The error type.
+mod error;
+/// Extra keys of Pushover API responses.
+mod extras;
+/// Updating Glances widgets.
+mod glances;
+/// Managing delivery groups.
+mod groups;
+/// Composing HTML-formatted messages.
+mod html;
+/// Request identifiers, receipts and keys the Pushover API returns.
+mod ids;
+/// Serde helpers for booleans represented as integers.
+mod int_bool;
+/// Validated application tokens and user keys.
+mod keys;
+/// Assigning Pushover licenses to users.
+mod licenses;
+/// Loading messages from files.
+mod load;
+/// Serde helpers for strings where an empty string means there is no value.
+mod non_empty;
+/// The Open Client API, for receiving messages.
+#[cfg(any(
+  feature = "blocking",
+  feature = "ureq",
+  all(feature = "websocket", not(target_arch = "wasm32"))
+))]
+pub mod open_client;
+/// Emergency priority message receipts.
+mod receipts;
+/// Retrying messages that failed to send.
+#[cfg(any(
+  feature = "blocking",
+  feature = "ureq",
+  all(feature = "async", not(target_arch = "wasm32"))
+))]
+mod retry;
+/// Strings like tokens and keys that shouldn't end up in logs.
+mod secret;
+/// Sending a stream of messages through a `Sink`.
+#[cfg(all(feature = "sink", not(target_arch = "wasm32")))]
+mod sink;
+/// The notification sounds.
+mod sound;
+/// Splitting messages that are too long.
+mod split;
+/// Serde helpers for HTTP status codes.
+mod status_code;
+/// Migrating users to subscriptions.
+mod subscriptions;
+/// Managing the users of a Pushover for Teams account.
+mod teams;
+/// Interpolating values into messages.
+mod template;
+/// Fakes for testing code that uses this library without a network.
+pub mod test_support;
+/// Tracing spans and events for requests to the Pushover API.
+#[cfg(feature = "tracing")]
+mod trace;
+/// Sending HTTP requests, replaceable to test without a network.
+mod transport;
+/// Truncating text to fit Pushover's limits.
+mod truncate;
+/// The user agent sent to the Pushover API.
+#[cfg(not(target_arch = "wasm32"))]
+mod user_agent;
+/// Validating user and group keys.
+mod users;
+/// Local validation of messages.
+mod validation;
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub use apps::get_limits;
+pub use apps::AppLimits;
+#[cfg(feature = "async")]
+pub use asynchronous::{
+  send_message_with_priority_async, send_simple_message_async,
+  send_simple_message_with_title_async, send_simple_message_with_url_async,
+};
+pub use attachment::{Attachment, MAX_ATTACHMENT_SIZE};
+#[cfg(all(feature = "background", not(target_arch = "wasm32")))]
+pub use background::{BackgroundSender, Delivery, DropPolicy, EnqueueError};
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub use batch::{send_batch, send_batch_with_delay, BatchReport};
+pub use borrowed::MessageRef;
+pub use builder::{EmergencyMessageBuilder, MessageBuilder, Missing, Provided};
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub use circuit::{CircuitBreaker, CircuitState};
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub use client::{PushoverClient, PushoverClientBuilder};
+pub use dedup::{Deduplicator, DEFAULT_DEDUP_CAPACITY};
+pub use error::{ApiError, ApiErrorKind, PushoverError, Result};
+pub use extras::ResponseExtras;
+pub use glances::{Glance, GlanceBuilder, GlanceField, MAX_GLANCE_TEXT_LENGTH};
+pub use groups::{
+  check_group_name, Group, GroupMember, GroupSummary, GroupUserOptions,
+  MAX_GROUP_NAME_LENGTH,
+};
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub use groups::{
+  create_group, get_group, group_add_user, group_disable_user,
+  group_enable_user, group_remove_user, group_rename, list_groups,
+};
+pub use html::{escape_html, Html};
+pub use ids::{DeviceId, GroupKey, Receipt, RequestId, SubscribedUserKey};
+pub use keys::{AppToken, KeyError, UserKey, KEY_LENGTH};
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub use licenses::{assign_license, check_license_credits};
+pub use licenses::{LicenseCredits, LicenseOs};
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub use receipts::{
+  cancel_by_tag, cancel_receipt, get_receipt, poll_receipt_until_done,
+};
+pub use receipts::{
+  AckOutcome, CancelByTagResponse, ReceiptCallback, ReceiptStatus,
+  MIN_RECEIPT_POLL_INTERVAL,
+};
+#[cfg(any(
+  feature = "blocking",
+  feature = "ureq",
+  all(feature = "async", not(target_arch = "wasm32"))
+))]
+pub use retry::RetryPolicy;
+pub use secret::Secret;
+#[cfg(all(feature = "sink", not(target_arch = "wasm32")))]
+pub use sink::MessageSink;
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub use sound::list_sounds;
+pub use sound::{Sound, DEFAULT_SOUNDS_CACHE_TTL};
+pub use split::{split_message, SplitSendError};
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub use subscriptions::migrate_subscription;
+pub use subscriptions::SubscriptionOptions;
+pub use teams::NewTeamUser;
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub use teams::{team_add_user, team_remove_user, TeamAddOutcome};
+pub use template::{Template, TemplateError, TemplateVars};
+pub use transport::{
+  HttpBody, HttpMethod, HttpRequest, HttpResponse, Transport,
+};
+pub use truncate::truncate_message;
+#[cfg(not(target_arch = "wasm32"))]
+pub use user_agent::UserAgent;
+pub use users::UserValidation;
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub use users::{is_user_valid, list_user_devices, validate_user};
+pub use validation::{
+  ValidationError, ValidationErrors, MAX_DEVICE_NAME_LENGTH,
+  MAX_MESSAGE_LENGTH, MAX_TITLE_LENGTH, MAX_URL_LENGTH, MAX_URL_TITLE_LENGTH,
+};
+
+/// The library's identifier in the user agent sent to the Pushover API.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) const USER_AGENT: &str = "Rust Pushover API Library";
+
+/// The default time to wait for a connection to the Pushover API.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The default time to wait for a whole request to the Pushover API.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The HTTP client of the blocking functions, Reqwest's blocking client.
+#[cfg(all(feature = "blocking", not(feature = "ureq")))]
+pub(crate) type HttpClient = reqwest::blocking::Client;
+
+/// The HTTP client of the blocking functions, a ureq agent.
+#[cfg(feature = "ureq")]
+pub(crate) type HttpClient = ureq::Agent;
+
+/// Reusable HTTP client to make HTTP requests with, created when it's first
+/// needed.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+static HTTP_CLIENT: RwLock<Option<HttpClient>> = RwLock::new(None);
+
+/// The timeouts of the reusable HTTP client, kept to create it and to rebuild
+/// it when the user agent changes.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+static TIMEOUTS: RwLock<(Duration, Duration)> =
+  RwLock::new((DEFAULT_CONNECT_TIMEOUT, DEFAULT_TIMEOUT));
+
+/// Builds a Reqwest client with the given timeouts and the default user
+/// agent.
+#[cfg(all(feature = "blocking", not(feature = "ureq")))]
+pub(crate) fn build_client(
+  connect_timeout: Duration,
+  timeout: Duration,
+) -> Result<HttpClient> {
+  check_proxy_env()?;
+  let user_agent = UserAgent::current_default();
+  Ok(client_builder(connect_timeout, timeout, &user_agent).build()?)
+}
+
+/// Builds a ureq agent with the given timeouts and the default user agent.
+#[cfg(feature = "ureq")]
+pub(crate) fn build_client(
+  connect_timeout: Duration,
+  timeout: Duration,
+) -> Result<HttpClient> {
+  check_proxy_env()?;
+  let user_agent = UserAgent::current_default();
+  Ok(client_builder(connect_timeout, timeout, &user_agent).build())
+}
+
+/// Creates a Reqwest client builder with the given timeouts and user agent.
+#[cfg(all(feature = "blocking", not(feature = "ureq")))]
+pub(crate) fn client_builder(
+  connect_timeout: Duration,
+  timeout: Duration,
+  user_agent: &UserAgent,
+) -> reqwest::blocking::ClientBuilder {
+  HttpClient::builder()
+    .user_agent(user_agent.as_str())
+    .connect_timeout(connect_timeout)
+    .timeout(timeout)
+}
+
+/// Creates a ureq agent builder with the given timeouts and user agent, using
+/// the proxies from the environment like Reqwest does.
+#[cfg(feature = "ureq")]
+pub(crate) fn client_builder(
+  connect_timeout: Duration,
+  timeout: Duration,
+  user_agent: &UserAgent,
+) -> ureq::AgentBuilder {
+  ureq::AgentBuilder::new()
+    .user_agent(user_agent.as_str())
+    .timeout_connect(connect_timeout)
+    .timeout(timeout)
+    .try_proxy_from_env(true)
+}
+
+/// The environment variables with proxies that Reqwest and ureq use.
+#[cfg(not(target_arch = "wasm32"))]
+const PROXY_ENV_VARS: [&str; 6] = [
+  "HTTPS_PROXY",
+  "https_proxy",
+  "HTTP_PROXY",
+  "http_proxy",
+  "ALL_PROXY",
+  "all_proxy",
+];
 
-lazy_static! {
-  /// Reusable Reqwest client to make HTTP requests with.
-  pub(crate) static ref REQWEST: Client = Client::builder()
-    .user_agent("Rust Pushover API Library")
-    .build()
-    .unwrap();
+/// Checks that the proxies set in the environment are valid. Reqwest and ureq
+/// skip invalid ones, which would silently send requests without the proxy.
+#[cfg(all(
+  not(target_arch = "wasm32"),
+  any(feature = "reqwest", feature = "ureq")
+))]
+pub(crate) fn check_proxy_env() -> Result<()> {
+  for name in PROXY_ENV_VARS {
+    let url = match std::env::var(name) {
+      Ok(url) if !url.trim().is_empty() => url,
+      _ => continue,
+    };
+
+    #[cfg(feature = "ureq")]
+    let valid = ureq::Proxy::new(url.trim()).is_ok();
+    #[cfg(not(feature = "ureq"))]
+    let valid = reqwest::Proxy::all(url.trim()).is_ok();
+
+    if !valid {
+      return Err(PushoverError::InvalidArgument(format!(
+        "The {} environment variable isn't a valid proxy URL",
+        name
+      )));
+    }
+  }
+
+  Ok(())
 }
 
+/// The HTTP client to make HTTP requests with, creating it when it doesn't
+/// exist yet. Errors aren't kept, so a later call tries again.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub(crate) fn http_client() -> Result<HttpClient> {
+  if let Some(client) = HTTP_CLIENT.read().unwrap().as_ref() {
+    return Ok(client.clone());
+  }
+
+  let mut http_client = HTTP_CLIENT.write().unwrap();
+  if let Some(client) = http_client.as_ref() {
+    return Ok(client.clone());
+  }
+
+  let (connect_timeout, timeout) = *TIMEOUTS.read().unwrap();
+  let client = build_client(connect_timeout, timeout)?;
+  *http_client = Some(client.clone());
+  Ok(client)
+}
+
+/// Sets how long to wait for a connection to the Pushover API and for a whole
+/// request, for all requests made after calling this. The defaults are
+/// [`DEFAULT_CONNECT_TIMEOUT`](constant.DEFAULT_CONNECT_TIMEOUT.html) and
+/// [`DEFAULT_TIMEOUT`](constant.DEFAULT_TIMEOUT.html).
+///
+/// Requests that take too long fail with a
+/// [`Timeout`](enum.PushoverError.html#variant.Timeout) error.
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// pushover_api::set_timeouts(Duration::from_secs(5), Duration::from_secs(10))
+///   .unwrap();
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn set_timeouts(
+  connect_timeout: Duration,
+  timeout: Duration,
+) -> Result<()> {
+  let client = build_client(connect_timeout, timeout)?;
+  *HTTP_CLIENT.write().unwrap() = Some(client);
+  *TIMEOUTS.write().unwrap() = (connect_timeout, timeout);
+  Ok(())
+}
+
+/// Sets the user agent of all requests made after calling this, except
+/// those of a [`PushoverClient`](struct.PushoverClient.html) built with its
+/// own user agent. The default is the library's identifier.
+///
+/// ```rust
+/// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+/// # {
+/// use pushover_api::{Message, UserAgent};
+///
+/// # let server = pushover_api::test_support::MockServer::respond(vec![]);
+/// # server.set_api_url_var();
+/// pushover_api::set_user_agent(UserAgent::new("Backups/1.2").unwrap())
+///   .unwrap();
+///
+/// Message {
+///   token: "application token".into(),
+///   user: "user key".into(),
+///   message: "The backup finished".to_string(),
+///   ..Message::default()
+/// }
+/// .send()
+/// .unwrap();
+/// # assert_eq!(
+/// #   server.requests()[0].header("user-agent"),
+/// #   Some("Backups/1.2 Rust Pushover API Library")
+/// # );
+/// # }
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+pub fn set_user_agent(user_agent: UserAgent) -> Result<()> {
+  UserAgent::set_default(user_agent);
+
+  #[cfg(any(feature = "blocking", feature = "ureq"))]
+  {
+    let (connect_timeout, timeout) = *TIMEOUTS.read().unwrap();
+    let client = build_client(connect_timeout, timeout)?;
+    *HTTP_CLIENT.write().unwrap() = Some(client);
+  }
+
+  Ok(())
+}
+
+/// The maximum number of users or groups a single message can be sent to.
+pub const MAX_USERS_PER_MESSAGE: usize = 50;
+
 /// The base URL for the Pushover API.
-pub(crate) const PUSHOVER_API: &str = "https://api.pushover.net/1";
+pub const PUSHOVER_API: &str = "https://api.pushover.net/1";
+
+/// The environment variable that overrides the base URL for the Pushover API,
+/// for example to point at a mock server in integration tests.
+pub const PUSHOVER_API_URL_VAR: &str = "PUSHOVER_API_URL";
+
+/// The base URL for the Pushover API, from the
+/// [`PUSHOVER_API_URL_VAR`](constant.PUSHOVER_API_URL_VAR.html) environment
+/// variable when it's set to a valid URL and
+/// [`PUSHOVER_API`](constant.PUSHOVER_API.html) otherwise.
+pub(crate) fn api_base() -> String {
+  std::env::var(PUSHOVER_API_URL_VAR)
+    .ok()
+    .and_then(|url| check_base_url(&url).ok())
+    .unwrap_or_else(|| PUSHOVER_API.to_string())
+}
+
+/// Checks that a base URL has an HTTP or HTTPS scheme, returning it without
+/// any trailing slashes.
+pub(crate) fn check_base_url(url: &str) -> Result<String> {
+  let url = url.trim().trim_end_matches('/');
+  let rest = url
+    .strip_prefix("https://")
+    .or_else(|| url.strip_prefix("http://"));
+
+  match rest {
+    Some(host) if !host.is_empty() => Ok(url.to_string()),
+    _ => Err(PushoverError::InvalidArgument(format!(
+      "Invalid base URL {:?}, it must start with http:// or https://",
+      url
+    ))),
+  }
+}
 
 /// Convenience function to create a full URL with the API base URL.
 pub(crate) fn api_url(path: &str) -> String {
-  format!("{}/{}", PUSHOVER_API, path)
+  join_url(&api_base(), path)
+}
+
+/// Creates a full URL from a base URL and a path.
+pub(crate) fn join_url(base: &str, path: &str) -> String {
+  format!("{}/{}", base, path)
+}
+
+/// Serializes a value to a list of form fields, leaving out any that are
+/// `null`. Strings are sent as they are and everything else as JSON, so a
+/// value that doesn't serialize to an object returns an error.
+pub(crate) fn form_fields<T: Serialize>(
+  value: &T,
+) -> Result<Vec<(String, String)>> {
+  let fields = match serde_json::to_value(value)? {
+    serde_json::Value::Object(fields) => fields,
+    value => {
+      return Err(PushoverError::Serialization(
+        format!("Can't send {} as form fields, only objects", value).into(),
+      ))
+    }
+  };
+
+  let fields = fields
+    .into_iter()
+    .filter_map(|(key, value)| match value {
+      serde_json::Value::Null => None,
+      serde_json::Value::String(value) => Some((key, value)),
+      value => Some((key, value.to_string())),
+    })
+    .collect();
+
+  Ok(fields)
+}
+
+/// A random number that doesn't need to be any good, for jitter and
+/// multipart boundaries.
+#[cfg(any(
+  feature = "blocking",
+  feature = "ureq",
+  all(feature = "async", not(target_arch = "wasm32"))
+))]
+pub(crate) fn random_u64() -> u64 {
+  use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+  };
+
+  // Every RandomState is seeded with new keys, so hashing anything with it
+  // gives a different number.
+  let mut hasher = RandomState::new().build_hasher();
+  hasher.write_u8(0);
+  hasher.finish()
+}
+
+/// Percent-encodes a value to be used as a single segment of a URL path,
+/// leaving only the unreserved characters as they are.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub(crate) fn encode_path_segment(segment: &str) -> String {
+  let mut encoded = String::with_capacity(segment.len());
+  for byte in segment.bytes() {
+    match byte {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+        encoded.push(byte as char)
+      }
+      byte => encoded.push_str(&format!("%{:02X}", byte)),
+    }
+  }
+
+  encoded
 }
 
 /// The full message body to send to the Pushover API.
-#[derive(Debug, Default, Deserialize, Serialize)]
+///
+/// Any fields that are `None` are left out when the message is serialized.
+///
+/// ```rust
+/// use pushover_api::{Message, MessagePriority, Sound};
+///
+/// let message = Message {
+///   token: "application token".into(),
+///   user: "user key".into(),
+///   message: "Message".to_string(),
+///   device: vec!["phone".to_string(), "desktop".to_string()],
+///   priority: Some(MessagePriority::Emergency),
+///   sound: Some(Sound::Siren),
+///   retry: Some(60),
+///   expire: Some(3600),
+///   tags: vec!["outage".to_string()],
+///   html: Some(true),
+///   ..Message::default()
+/// };
+///
+/// let json = serde_json::to_string(&message).unwrap();
+/// assert_eq!(serde_json::from_str::<Message>(&json).unwrap(), message);
+/// ```
+///
+/// A message with only the required fields serializes to exactly those, and
+/// any missing fields are filled in with their defaults when deserializing.
+///
+/// ```rust
+/// use pushover_api::{Message, MessagePriority, Sound};
+///
+/// let message = Message {
+///   token: "application token".into(),
+///   user: "user key".into(),
+///   message: "Message".to_string(),
+///   ..Message::default()
+/// };
+///
+/// let json = serde_json::to_value(&message).unwrap();
+/// assert_eq!(
+///   json,
+///   serde_json::json!({
+///     "token": "application token",
+///     "user": "user key",
+///     "message": "Message",
+///   })
+/// );
+/// assert_eq!(json.as_object().unwrap().len(), 3);
+/// assert_eq!(serde_json::from_value::<Message>(json).unwrap(), message);
+/// assert_eq!(
+///   serde_json::from_str::<Message>(r#"{"message":"Message"}"#).unwrap(),
+///   Message {
+///     message: "Message".to_string(),
+///     ..Message::default()
+///   }
+/// );
+///
+/// let message = Message {
+///   token: "application token".into(),
+///   user: "user key".into(),
+///   message: "Message".to_string(),
+///   device: vec!["phone".to_string()],
+///   title: Some("Title".to_string()),
+///   url: Some("https://example.com".to_string()),
+///   url_title: Some("Example".to_string()),
+///   priority: Some(MessagePriority::Emergency),
+///   sound: Some(Sound::Siren),
+///   timestamp: Some(1_600_000_000),
+///   retry: Some(60),
+///   expire: Some(3600),
+///   callback: Some("https://example.com/acknowledged".to_string()),
+///   tags: vec!["outage".to_string()],
+///   html: Some(false),
+///   monospace: Some(true),
+///   ttl: Some(600),
+///   attachment_base64: Some("iVBORw0KGgo=".to_string()),
+///   attachment_type: Some("image/png".to_string()),
+///   ..Message::default()
+/// };
+///
+/// let json = serde_json::to_value(&message).unwrap();
+/// assert_eq!(json.as_object().unwrap().len(), 19);
+/// assert_eq!(serde_json::from_value::<Message>(json).unwrap(), message);
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
 pub struct Message {
   /// The application's API token. You can
   /// [register one here](https://pushover.net/apps/build) or
   /// [view your existing ones here](https://pushover.net/apps).
-  pub token: String,
+  ///
+  /// Like the user, it's a [`Secret`](struct.Secret.html) that doesn't show up
+  /// in debug output.
+  pub token: Secret,
   /// The user or group identifier to send the message to.
-  pub user: String,
+  pub user: Secret,
   /// The actual message to send.
   pub message: String,
-  /// A comma-separated list of devices to send the message to.
+  /// The devices to send the message to, sent to the Pushover API as a
+  /// comma-separated list.
   ///
   /// If any of the devices for the specified user/group is disabled or invalid,
-  /// or is set to `None`, the message will be sent to all active devices
-  /// for that user/group.
-  pub device: Option<String>,
+  /// or the list is empty, the message will be sent to all active devices
+  /// for that user/group. Device names that aren't valid will be caught by
+  /// [`validate()`](#method.validate) before that can happen.
+  ///
+  /// Deserializing accepts both a list and the comma-separated string older
+  /// versions of this crate serialized it as.
+  ///
+  /// ```rust
+  /// use pushover_api::Message;
+  ///
+  /// let message = Message {
+  ///   device: vec!["phone".to_string(), "desktop".to_string()],
+  ///   ..Message::default()
+  /// };
+  ///
+  /// let json = serde_json::to_value(&message).unwrap();
+  /// assert_eq!(json["device"], "phone,desktop");
+  /// assert_eq!(serde_json::from_value::<Message>(json).unwrap(), message);
+  ///
+  /// for old in &[
+  ///   r#"{"device":"phone,desktop"}"#,
+  ///   r#"{"device":"phone, desktop,"}"#,
+  ///   r#"{"device":["phone","desktop"]}"#,
+  /// ] {
+  ///   assert_eq!(serde_json::from_str::<Message>(old).unwrap(), message);
+  /// }
+  ///
+  /// let json = serde_json::to_value(&Message::default()).unwrap();
+  /// assert!(json.get("device").is_none());
+  /// for empty in &[r#"{"device":""}"#, r#"{"device":null}"#, "{}"] {
+  ///   let message = serde_json::from_str::<Message>(empty).unwrap();
+  ///   assert!(message.device.is_empty());
+  /// }
+  /// ```
+  ///
+  /// ```rust
+  /// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+  /// # {
+  /// use pushover_api::{test_support::FakeTransport, Message, PushoverClient};
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853c"}"#);
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853d"}"#);
+  ///
+  /// let client = PushoverClient::with_transport("token", &transport)
+  ///   .default_user("user key");
+  /// client
+  ///   .send(&Message {
+  ///     message: "Message".to_string(),
+  ///     device: vec!["phone".to_string(), "desktop".to_string()],
+  ///     ..Message::default()
+  ///   })
+  ///   .unwrap();
+  /// client.notify("Message").unwrap();
+  ///
+  /// let requests = transport.requests();
+  /// assert_eq!(requests[0].field("device").as_deref(), Some("phone,desktop"));
+  /// assert_eq!(requests[1].field("device"), None);
+  /// # }
+  /// ```
+  #[serde(skip_serializing_if = "Vec::is_empty", with = "comma_separated")]
+  pub device: Vec<String>,
   /// The title for the message, if set to `None` the application's name will
   /// be shown instead.
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub title: Option<String>,
   /// A supplementary URL to show with the message.
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub url: Option<String>,
   /// A title to use for the supplementary URL.
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub url_title: Option<String>,
   /// The priority of the message.
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub priority: Option<MessagePriority>,
-  /// The name of one of the sounds to use, see the
+  /// The sound to play with the notification, see the
   /// [Pushover documentation](https://pushover.net/api#sounds) for a list of
   /// all sounds.
-  pub sound: Option<String>,
+  ///
+  /// This used to be an `Option<String>`. Code that sets it to a string can
+  /// use [`set_sound()`](#method.set_sound) instead, or convert the string
+  /// with `Some(sound.into())`.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub sound: Option<Sound>,
   /// A Unix timestamp to use as the date time for the message instead of when
   /// the Pushover API received it.
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub timestamp: Option<i64>,
+  /// How often (in seconds) the Pushover servers will send the same
+  /// notification to the user, only used with
+  /// [`MessagePriority::Emergency`](enum.MessagePriority.html#variant.Emergency).
+  ///
+  /// The Pushover API requires this to be at least 30 seconds.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub retry: Option<u32>,
+  /// How many seconds the notification will continue to be retried for, only
+  /// used with
+  /// [`MessagePriority::Emergency`](enum.MessagePriority.html#variant.Emergency).
+  ///
+  /// The Pushover API allows a maximum of 10800 seconds (3 hours).
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub expire: Option<u32>,
+  /// A URL the Pushover servers will send a POST request to when the
+  /// notification has been acknowledged by the user.
+  ///
+  /// Only meaningful for
+  /// [`MessagePriority::Emergency`](enum.MessagePriority.html#variant.Emergency)
+  /// messages, the Pushover API ignores it for any other priority.
+  ///
+  /// ```rust
+  /// use pushover_api::{Message, MessagePriority};
+  ///
+  /// let message = Message {
+  ///   message: "The server is down".to_string(),
+  ///   priority: Some(MessagePriority::Emergency),
+  ///   retry: Some(60),
+  ///   expire: Some(3600),
+  ///   callback: Some("https://example.com/acknowledged".to_string()),
+  ///   ..Message::default()
+  /// };
+  ///
+  /// let json = serde_json::to_value(&message).unwrap();
+  /// assert_eq!(json["callback"], "https://example.com/acknowledged");
+  ///
+  /// let json = serde_json::to_value(&Message {
+  ///   message: "The server is up".to_string(),
+  ///   ..Message::default()
+  /// })
+  /// .unwrap();
+  /// assert!(json.get("callback").is_none());
+  /// ```
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub callback: Option<String>,
+  /// Tags to attach to the message, which can later be used to cancel the
+  /// retries of all emergency priority messages with a given tag at once.
+  ///
+  /// Tags can't be empty or contain commas or whitespace.
+  ///
+  /// ```rust
+  /// use pushover_api::{Message, ValidationError};
+  ///
+  /// let message = Message {
+  ///   message: "The server is down".to_string(),
+  ///   tags: vec!["outage".to_string(), "server-1".to_string()],
+  ///   ..Message::default()
+  /// };
+  /// assert!(message.validate().is_ok());
+  ///
+  /// let json = serde_json::to_value(&message).unwrap();
+  /// assert_eq!(json["tags"], "outage,server-1");
+  /// assert_eq!(serde_json::from_value::<Message>(json).unwrap(), message);
+  ///
+  /// let json = serde_json::to_value(&Message::default()).unwrap();
+  /// assert!(json.get("tags").is_none());
+  ///
+  /// let message = Message {
+  ///   tags: vec!["a,b".to_string(), "a b".to_string(), "".to_string()],
+  ///   ..Message::default()
+  /// };
+  /// assert_eq!(
+  ///   message.validate().unwrap_err().0,
+  ///   vec![
+  ///     ValidationError::InvalidTag("a,b".to_string()),
+  ///     ValidationError::InvalidTag("a b".to_string()),
+  ///     ValidationError::InvalidTag("".to_string()),
+  ///   ]
+  /// );
+  /// ```
+  #[serde(skip_serializing_if = "Vec::is_empty", with = "comma_separated")]
+  pub tags: Vec<String>,
+  /// Whether to render the message with
+  /// [HTML formatting](https://pushover.net/api#html), which supports the
+  /// `<b>`, `<i>`, `<u>`, `<font color="">` and `<a href="">` tags.
+  ///
+  /// See [`Html`](struct.Html.html) to compose messages with escaped text.
+  ///
+  /// ```rust,no_run
+  /// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+  /// # {
+  /// use pushover_api::Message;
+  ///
+  /// Message {
+  ///   token: "application token".into(),
+  ///   user: "user key".into(),
+  ///   message: "The build is <b>broken</b>!".to_string(),
+  ///   html: Some(true),
+  ///   ..Message::default()
+  /// }
+  /// .send()
+  /// .unwrap();
+  /// # }
+  /// ```
+  ///
+  /// The `html` parameter is only sent when it's set, as `1` or `0`.
+  ///
+  /// ```rust
+  /// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+  /// # {
+  /// use pushover_api::{test_support::FakeTransport, Message, PushoverClient};
+  ///
+  /// let transport = FakeTransport::new();
+  /// let client = PushoverClient::with_transport("token", &transport)
+  ///   .default_user("user key");
+  ///
+  /// for html in &[Some(true), Some(false), None] {
+  ///   transport.push_json(200, r#"{"status":1,"request":"5042853c"}"#);
+  ///   client
+  ///     .send(&Message {
+  ///       message: "The build is <b>broken</b>!".to_string(),
+  ///       html: *html,
+  ///       ..Message::default()
+  ///     })
+  ///     .unwrap();
+  /// }
+  ///
+  /// let requests = transport.requests();
+  /// assert_eq!(requests[0].field("html").as_deref(), Some("1"));
+  /// assert_eq!(requests[1].field("html").as_deref(), Some("0"));
+  /// assert_eq!(requests[2].field("html"), None);
+  /// assert_eq!(
+  ///   requests[2].field("message").as_deref(),
+  ///   Some("The build is <b>broken</b>!")
+  /// );
+  /// # }
+  /// ```
+  #[serde(skip_serializing_if = "Option::is_none", with = "int_bool")]
+  pub html: Option<bool>,
+  /// Whether to display the message in a monospace font.
+  ///
+  /// This can't be combined with [`html`](#structfield.html).
+  #[serde(skip_serializing_if = "Option::is_none", with = "int_bool")]
+  pub monospace: Option<bool>,
+  /// The number of seconds until the message is automatically deleted from
+  /// the user's devices, see [`set_ttl`](#method.set_ttl) to set it from a
+  /// [`Duration`](https://doc.rust-lang.org/std/time/struct.Duration.html).
+  ///
+  /// Emergency priority messages can't have a time to live.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub ttl: Option<u32>,
+  /// An image to attach to the message, sending a message with an attachment
+  /// uses a `multipart/form-data` request instead of JSON.
+  #[serde(skip)]
+  pub attachment: Option<Attachment>,
+  /// A base64-encoded image to attach to the message, as an alternative to
+  /// [`attachment`](#structfield.attachment) that can be sent as JSON. See
+  /// [`set_attachment_base64`](#method.set_attachment_base64) to encode it.
+  ///
+  /// Requires [`attachment_type`](#structfield.attachment_type) to be set.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub attachment_base64: Option<String>,
+  /// The content type of [`attachment_base64`](#structfield.attachment_base64),
+  /// like `image/png`.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub attachment_type: Option<String>,
+  /// Whether [`send()`](#method.send) should truncate the message, title and
+  /// URL title to fit Pushover's limits instead of failing validation, see
+  /// [`truncate_message`](fn.truncate_message.html).
+  ///
+  /// ```rust
+  /// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+  /// # {
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, Message, PushoverClient, MAX_MESSAGE_LENGTH,
+  ///   MAX_TITLE_LENGTH,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// let client = PushoverClient::with_transport("application token", &transport);
+  /// let message = Message {
+  ///   user: "user key".into(),
+  ///   message: "a".repeat(MAX_MESSAGE_LENGTH + 10),
+  ///   title: Some("b".repeat(MAX_TITLE_LENGTH + 10)),
+  ///   truncate_to_fit: true,
+  ///   ..Message::default()
+  /// };
+  /// client.send(&message).unwrap();
+  ///
+  /// let request = &transport.requests()[0];
+  /// let sent = request.field("message").unwrap();
+  /// assert_eq!(sent.chars().count(), MAX_MESSAGE_LENGTH);
+  /// assert!(sent.ends_with("a…"));
+  /// let title = request.field("title").unwrap();
+  /// assert_eq!(title.chars().count(), MAX_TITLE_LENGTH);
+  /// assert!(title.ends_with("b…"));
+  ///
+  /// // Without it, the message fails validation instead.
+  /// let message = Message {
+  ///   truncate_to_fit: false,
+  ///   ..message
+  /// };
+  /// assert!(client.send(&message).is_err());
+  /// assert_eq!(transport.requests().len(), 1);
+  /// # }
+  /// ```
+  #[serde(skip)]
+  pub truncate_to_fit: bool,
 }
 
 impl Message {
+  /// Creates a [`MessageBuilder`](struct.MessageBuilder.html) with the
+  /// required fields.
+  pub fn builder<T, U, M>(token: T, user: U, message: M) -> MessageBuilder
+  where
+    T: Into<String>,
+    U: Into<String>,
+    M: Into<String>,
+  {
+    MessageBuilder::new(token, user, message)
+  }
+
   /// Send this message to the Pushover API.
+  ///
+  /// The message is [validated](#method.validate) first and any problems will
+  /// be returned as an error without making a request, use
+  /// [`send_without_validation()`](#method.send_without_validation) to skip
+  /// this.
+  ///
+  /// ```rust
+  /// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+  /// # {
+  /// use http::StatusCode;
+  /// use pushover_api::{Message, MessagePriority, PushoverError};
+  /// # use pushover_api::{test_support::MockServer, HttpResponse};
+  ///
+  /// # let html = HttpResponse {
+  /// #   status: StatusCode::INTERNAL_SERVER_ERROR,
+  /// #   headers: vec![("content-type".into(), "text/html".into())],
+  /// #   body: "<html>Oops</html>".to_string(),
+  /// # };
+  /// # let server = MockServer::respond(vec![
+  /// #   HttpResponse::json(StatusCode::OK,
+  /// #     r#"{"status":1,"request":"5042853c","receipt":"rLqVuqTRh62UzxtmqiaLzQmVcPgiCy"}"#),
+  /// #   HttpResponse::json(StatusCode::BAD_REQUEST,
+  /// #     r#"{"user":"invalid","errors":["user identifier is invalid"],"status":0,"request":"5042853d"}"#),
+  /// #   HttpResponse::json(StatusCode::OK, r#"{"status":0,"request":"5042853e"}"#),
+  /// #   html,
+  /// #   HttpResponse::json(StatusCode::TOO_MANY_REQUESTS,
+  /// #     r#"{"errors":["application is over its limit"],"status":0,"request":"5042853f"}"#),
+  /// # ]);
+  /// # server.set_api_url_var();
+  /// let message = Message {
+  ///   token: "application token".into(),
+  ///   user: "user key".into(),
+  ///   message: "Message".to_string(),
+  ///   priority: Some(MessagePriority::Emergency),
+  ///   retry: Some(60),
+  ///   expire: Some(3600),
+  ///   ..Message::default()
+  /// };
+  ///
+  /// let response = message.send().unwrap();
+  /// assert_eq!(response.http_status, StatusCode::OK);
+  /// assert_eq!(response.request.as_str(), "5042853c");
+  /// assert_eq!(
+  ///   response.receipt.unwrap().as_str(),
+  ///   "rLqVuqTRh62UzxtmqiaLzQmVcPgiCy"
+  /// );
+  /// # let request = &server.requests()[0];
+  /// # assert_eq!((request.method.as_str(), request.target.as_str()), ("POST", "/1/messages.json"));
+  ///
+  /// match message.send().unwrap_err() {
+  ///   PushoverError::Api {
+  ///     errors,
+  ///     http_status,
+  ///     status,
+  ///     request,
+  ///     ..
+  ///   } => {
+  ///     assert_eq!(errors[0].message, "user identifier is invalid");
+  ///     assert_eq!(http_status, StatusCode::BAD_REQUEST);
+  ///     assert_eq!(status, 0);
+  ///     assert_eq!(request.unwrap().as_str(), "5042853d");
+  ///   }
+  ///   error => panic!("Unexpected error: {}", error),
+  /// }
+  ///
+  /// match message.send().unwrap_err() {
+  ///   PushoverError::Api { errors, status, .. } => {
+  ///     assert!(errors.is_empty());
+  ///     assert_eq!(status, 0);
+  ///   }
+  ///   error => panic!("Unexpected error: {}", error),
+  /// }
+  ///
+  /// match message.send().unwrap_err() {
+  ///   PushoverError::UnexpectedResponse {
+  ///     http_status,
+  ///     body,
+  ///     ..
+  ///   } => {
+  ///     assert_eq!(http_status, StatusCode::INTERNAL_SERVER_ERROR);
+  ///     assert_eq!(body, "<html>Oops</html>");
+  ///   }
+  ///   error => panic!("Unexpected error: {}", error),
+  /// }
+  ///
+  /// match message.send().unwrap_err() {
+  ///   PushoverError::RateLimited { errors, .. } => {
+  ///     assert_eq!(errors[0].message, "application is over its limit");
+  ///   }
+  ///   error => panic!("Unexpected error: {}", error),
+  /// }
+  ///
+  /// let invalid = Message {
+  ///   retry: None,
+  ///   ..message
+  /// };
+  /// assert!(matches!(
+  ///   invalid.send().unwrap_err(),
+  ///   PushoverError::Validation(_)
+  /// ));
+  /// # assert_eq!(server.requests().len(), 5);
+  /// # }
+  /// ```
+  #[cfg(any(feature = "blocking", feature = "ureq"))]
   pub fn send(&self) -> Result<MessageResponse> {
-    let response = REQWEST
-      .post(&api_url("messages.json"))
-      .header("content-type", "application/json")
-      .body(self.to_json()?)
-      .send()?;
-
-    let status = response.status();
-    let raw: RawMessageResponse = serde_json::from_str(&response.text()?)?;
-
-    if raw.errors.is_empty() {
-      Ok(MessageResponse {
-        http_status: status,
-        request: raw.request,
-        status: raw.status,
-      })
+    self.send_using(&http_client()?, &api_base())
+  }
+
+  /// Send this message to the Pushover API like [`send()`](#method.send),
+  /// using your own HTTP client instead of the one built into this library,
+  /// for example a Reqwest client or ureq agent with a proxy or custom TLS
+  /// settings.
+  ///
+  /// ```rust
+  /// # #[cfg(feature = "blocking")]
+  /// # {
+  /// use pushover_api::Message;
+  /// use reqwest::blocking::Client;
+  ///
+  /// # let server = pushover_api::test_support::MockServer::respond(vec![]);
+  /// # server.set_api_url_var();
+  /// let client = Client::builder().user_agent("my-alerting/1.0").build().unwrap();
+  ///
+  /// Message {
+  ///   token: "application token".into(),
+  ///   user: "user key".into(),
+  ///   message: "Message".to_string(),
+  ///   ..Message::default()
+  /// }
+  /// .send_with(&client)
+  /// .unwrap();
+  /// # let requests = server.requests();
+  /// # assert_eq!(requests[0].header("user-agent"), Some("my-alerting/1.0"));
+  /// # }
+  /// ```
+  #[cfg(any(feature = "blocking", feature = "ureq"))]
+  pub fn send_with(&self, client: &impl Transport) -> Result<MessageResponse> {
+    self.send_using(client, &api_base())
+  }
+
+  /// Validates and sends this message with the given Reqwest client to the
+  /// Pushover API at `base`.
+  #[cfg(any(feature = "blocking", feature = "ureq"))]
+  pub(crate) fn send_using(
+    &self,
+    transport: &impl Transport,
+    base: &str,
+  ) -> Result<MessageResponse> {
+    self
+      .checked()?
+      .send_without_validation_using(transport, base)
+  }
+
+  /// Returns this message [truncated](#structfield.truncate_to_fit) if needed,
+  /// after validating it.
+  pub(crate) fn checked(&self) -> Result<Cow<'_, Self>> {
+    let message = if self.truncate_to_fit {
+      Cow::Owned(self.truncated())
     } else {
-      Err(anyhow!("{}", raw.errors.join(", ")))
+      Cow::Borrowed(self)
+    };
+
+    message.validate()?;
+    Ok(message)
+  }
+
+  /// Send this message to multiple users or groups in a single request,
+  /// ignoring the message's own `user`.
+  ///
+  /// Each key is trimmed and the Pushover API accepts at most
+  /// [`MAX_USERS_PER_MESSAGE`](constant.MAX_USERS_PER_MESSAGE.html) keys,
+  /// any empty keys, keys containing commas or too many keys will return an
+  /// error without making a request. See
+  /// [`PushoverClient::send_to_users()`](struct.PushoverClient.html#method.send_to_users)
+  /// to do the same with a client.
+  ///
+  /// ```rust,no_run
+  /// use pushover_api::Message;
+  ///
+  /// Message {
+  ///   token: "application token".into(),
+  ///   message: "Message".to_string(),
+  ///   ..Message::default()
+  /// }
+  /// .send_to_users(&["first user key", "second user key"])
+  /// .unwrap();
+  /// ```
+  #[cfg(any(feature = "blocking", feature = "ureq"))]
+  pub fn send_to_users(&self, users: &[&str]) -> Result<MessageResponse> {
+    self.for_users(users)?.send()
+  }
+
+  /// Returns a copy of this message for the given users or groups, checking
+  /// the keys like [`send_to_users()`](#method.send_to_users).
+  #[cfg(any(feature = "blocking", feature = "ureq"))]
+  pub(crate) fn for_users(&self, users: &[&str]) -> Result<Self> {
+    if users.len() > MAX_USERS_PER_MESSAGE {
+      return Err(PushoverError::InvalidArgument(format!(
+        "Messages can be sent to at most {} users at once, got {}",
+        MAX_USERS_PER_MESSAGE,
+        users.len()
+      )));
+    }
+
+    let users = users.iter().map(|user| user.trim()).collect::<Vec<&str>>();
+    if let Some(user) = users
+      .iter()
+      .find(|user| user.is_empty() || user.contains(','))
+    {
+      return Err(PushoverError::InvalidArgument(format!(
+        "Invalid user key {:?}, user keys can't be empty or contain commas",
+        user
+      )));
+    }
+
+    let mut message = self.clone();
+    message.user = users.join(",").into();
+    Ok(message)
+  }
+
+  /// Send this message to the Pushover API without validating it first.
+  #[cfg(any(feature = "blocking", feature = "ureq"))]
+  pub fn send_without_validation(&self) -> Result<MessageResponse> {
+    self.send_without_validation_using(&http_client()?, &api_base())
+  }
+
+  /// Sends this message with the given transport to the Pushover API at
+  /// `base` without validating it.
+  #[cfg(any(feature = "blocking", feature = "ureq"))]
+  pub(crate) fn send_without_validation_using(
+    &self,
+    transport: &impl Transport,
+    base: &str,
+  ) -> Result<MessageResponse> {
+    #[cfg(feature = "tracing")]
+    let _span =
+      trace::message_span(self.user.expose_secret(), self.priority).entered();
+
+    Ok(MessageResponse::from_reply(api::execute(
+      transport,
+      self.to_request(base)?,
+    )?))
+  }
+
+  /// Creates the request to send this message to the Pushover API at `base`,
+  /// as multipart form data when it has an attachment and JSON otherwise.
+  pub(crate) fn to_request(&self, base: &str) -> Result<HttpRequest> {
+    let body = match &self.attachment {
+      Some(attachment) => {
+        attachment.check_size()?;
+        HttpBody::Multipart {
+          fields: form_fields(self)?,
+          attachment: attachment.clone(),
+        }
+      }
+      None => HttpBody::Json(self.to_json()?),
+    };
+
+    Ok(
+      HttpRequest::new(HttpMethod::Post, join_url(base, "messages.json"))
+        .with_body(body),
+    )
+  }
+
+  /// Sets the sound to play with the notification, from a
+  /// [`Sound`](enum.Sound.html) or its API identifier.
+  ///
+  /// ```rust
+  /// use pushover_api::{Message, Sound};
+  ///
+  /// let mut message = Message::default();
+  /// message.set_sound("cashregister");
+  /// assert_eq!(message.sound, Some(Sound::CashRegister));
+  ///
+  /// message.set_sound(String::from("my-sound"));
+  /// assert_eq!(message.sound, Some(Sound::Custom("my-sound".to_string())));
+  ///
+  /// message.set_sound(Sound::Siren);
+  /// assert_eq!(message.sound, Some(Sound::Siren));
+  /// ```
+  pub fn set_sound(&mut self, sound: impl Into<Sound>) {
+    self.sound = Some(sound.into());
+  }
+
+  /// Sets the time to live of the message, truncated to whole seconds.
+  ///
+  /// Returns an error when the duration is less than a second or doesn't fit
+  /// in a `u32`.
+  ///
+  /// ```rust
+  /// use pushover_api::Message;
+  /// use std::time::Duration;
+  ///
+  /// let mut message = Message::default();
+  /// message.set_ttl(Duration::from_secs(60 * 60)).unwrap();
+  /// assert_eq!(message.ttl, Some(3600));
+  ///
+  /// assert!(message.set_ttl(Duration::from_millis(500)).is_err());
+  /// ```
+  pub fn set_ttl(&mut self, ttl: Duration) -> Result<()> {
+    let seconds = ttl.as_secs();
+    if seconds == 0 {
+      return Err(PushoverError::InvalidArgument(
+        "The time to live must be at least 1 second".to_string(),
+      ));
+    }
+
+    self.ttl = Some(u32::try_from(seconds).map_err(|_| {
+      PushoverError::InvalidArgument(format!(
+        "The time to live of {} seconds is too large",
+        seconds
+      ))
+    })?);
+    Ok(())
+  }
+
+  /// Sets the timestamp of the message from a
+  /// [`SystemTime`](https://doc.rust-lang.org/std/time/struct.SystemTime.html).
+  ///
+  /// Returns an error when the time is before the Unix epoch or too far in the
+  /// future to fit in an `i64`, leaving the timestamp unchanged.
+  ///
+  /// ```rust
+  /// use pushover_api::Message;
+  /// use std::time::{Duration, UNIX_EPOCH};
+  ///
+  /// let mut message = Message::default();
+  /// let time = UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+  /// message.timestamp_from(time).unwrap();
+  /// assert_eq!(message.timestamp, Some(1_600_000_000));
+  ///
+  /// message.timestamp_from(UNIX_EPOCH).unwrap();
+  /// assert_eq!(message.timestamp, Some(0));
+  ///
+  /// let before_epoch = UNIX_EPOCH - Duration::from_secs(1);
+  /// assert!(message.timestamp_from(before_epoch).is_err());
+  /// assert_eq!(message.timestamp, Some(0));
+  /// ```
+  pub fn timestamp_from(&mut self, time: SystemTime) -> Result<()> {
+    let seconds = time
+      .duration_since(UNIX_EPOCH)
+      .map_err(|_| {
+        PushoverError::InvalidArgument(
+          "The timestamp can't be before the Unix epoch".to_string(),
+        )
+      })?
+      .as_secs();
+
+    self.timestamp = Some(i64::try_from(seconds).map_err(|_| {
+      PushoverError::InvalidArgument(format!(
+        "The timestamp of {} seconds is too large",
+        seconds
+      ))
+    })?);
+    Ok(())
+  }
+
+  /// Sets the timestamp of the message to the current time.
+  ///
+  /// ```rust
+  /// use pushover_api::Message;
+  /// use std::time::{SystemTime, UNIX_EPOCH};
+  ///
+  /// let before = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+  /// let mut message = Message::default();
+  /// message.timestamp_now().unwrap();
+  /// let after = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+  ///
+  /// let timestamp = message.timestamp.unwrap() as u64;
+  /// assert!(before.as_secs() <= timestamp && timestamp <= after.as_secs());
+  /// ```
+  pub fn timestamp_now(&mut self) -> Result<()> {
+    self.timestamp_from(SystemTime::now())
+  }
+
+  /// Sets the timestamp of the message from a chrono
+  /// [`DateTime`](https://docs.rs/chrono/0.4/chrono/struct.DateTime.html) in
+  /// any time zone, like `DateTime<Utc>` or `DateTime<FixedOffset>`.
+  ///
+  /// Returns an error when the time is before the Unix epoch.
+  ///
+  /// ```rust
+  /// use chrono::{DateTime, TimeZone, Utc};
+  /// use pushover_api::Message;
+  ///
+  /// let mut message = Message::default();
+  /// let offset = DateTime::parse_from_rfc3339("2020-09-13T14:26:40+02:00");
+  /// message.timestamp_from_datetime(&offset.unwrap()).unwrap();
+  /// assert_eq!(message.timestamp, Some(1_600_000_000));
+  ///
+  /// message
+  ///   .timestamp_from_datetime(&Utc.timestamp(1_600_000_000, 0))
+  ///   .unwrap();
+  /// assert_eq!(message.timestamp, Some(1_600_000_000));
+  ///
+  /// let before_epoch = DateTime::parse_from_rfc3339("1969-12-31T23:59:59Z");
+  /// assert!(message
+  ///   .timestamp_from_datetime(&before_epoch.unwrap())
+  ///   .is_err());
+  /// ```
+  #[cfg(feature = "chrono")]
+  pub fn timestamp_from_datetime<Tz: chrono::TimeZone>(
+    &mut self,
+    time: &chrono::DateTime<Tz>,
+  ) -> Result<()> {
+    let seconds = time.timestamp();
+    if seconds < 0 {
+      return Err(PushoverError::InvalidArgument(
+        "The timestamp can't be before the Unix epoch".to_string(),
+      ));
+    }
+
+    self.timestamp = Some(seconds);
+    Ok(())
+  }
+
+  /// Sets the timestamp of the message from a `SystemTime`.
+  #[deprecated(note = "Renamed to `timestamp_from`")]
+  pub fn set_timestamp_from(&mut self, time: SystemTime) -> Result<()> {
+    self.timestamp_from(time)
+  }
+
+  /// Sets the timestamp of the message to the current time.
+  #[deprecated(note = "Renamed to `timestamp_now`")]
+  pub fn set_timestamp_now(&mut self) -> Result<()> {
+    self.timestamp_now()
+  }
+
+  /// Sets the timestamp of the message from a chrono `DateTime`.
+  #[cfg(feature = "chrono")]
+  #[deprecated(note = "Renamed to `timestamp_from_datetime`")]
+  pub fn set_timestamp_from_datetime<Tz: chrono::TimeZone>(
+    &mut self,
+    time: &chrono::DateTime<Tz>,
+  ) -> Result<()> {
+    self.timestamp_from_datetime(time)
+  }
+
+  /// Base64-encodes an image and sets it as the
+  /// [`attachment_base64`](#structfield.attachment_base64) with the given
+  /// content type.
+  ///
+  /// ```rust
+  /// use pushover_api::Message;
+  ///
+  /// let mut message = Message::default();
+  /// message.set_attachment_base64(b"\x89PNG\r\n\x1a\n", "image/png");
+  ///
+  /// assert_eq!(message.attachment_base64.unwrap(), "iVBORw0KGgo=");
+  /// assert_eq!(message.attachment_type.unwrap(), "image/png");
+  /// ```
+  pub fn set_attachment_base64(&mut self, bytes: &[u8], mime: &str) {
+    self.attachment_base64 = Some(base64::encode(bytes));
+    self.attachment_type = Some(mime.to_string());
+  }
+
+  /// Checks the message for anything the Pushover API would reject, returning
+  /// all the problems that were found.
+  ///
+  /// With the `url-validation` feature enabled, the `url` and `callback`
+  /// fields are also checked to be absolute `http` or `https` URLs.
+  ///
+  /// ```rust
+  /// use pushover_api::{Message, ValidationError, MAX_TITLE_LENGTH};
+  ///
+  /// let message = Message {
+  ///   message: "Message".to_string(),
+  ///   title: Some("a".repeat(MAX_TITLE_LENGTH + 10)),
+  ///   html: Some(true),
+  ///   monospace: Some(true),
+  ///   ..Message::default()
+  /// };
+  ///
+  /// let errors = message.validate().unwrap_err();
+  /// assert_eq!(errors.0.len(), 2);
+  /// assert_eq!(
+  ///   errors.0[0].to_string(),
+  ///   "The title is 10 characters over the limit of 250"
+  /// );
+  /// ```
+  pub fn validate(&self) -> Result<(), ValidationErrors> {
+    let mut errors = vec![];
+
+    let lengths = [
+      ("message", Some(&self.message), MAX_MESSAGE_LENGTH),
+      ("title", self.title.as_ref(), MAX_TITLE_LENGTH),
+      ("url", self.url.as_ref(), MAX_URL_LENGTH),
+      ("url_title", self.url_title.as_ref(), MAX_URL_TITLE_LENGTH),
+    ];
+    errors.extend(lengths.iter().filter_map(|(field, value, limit)| {
+      value
+        .and_then(|value| ValidationError::check_length(field, value, *limit))
+    }));
+
+    #[cfg(feature = "url-validation")]
+    {
+      let urls = [("url", &self.url), ("callback", &self.callback)];
+      errors.extend(urls.iter().filter_map(|(field, value)| {
+        value
+          .as_ref()
+          .and_then(|value| ValidationError::check_url(field, value))
+      }));
+    }
+
+    errors.extend(
+      self
+        .device
+        .iter()
+        .filter(|device| !validation::is_valid_device_name(device))
+        .map(|device| ValidationError::InvalidDevice(device.clone())),
+    );
+
+    if let Some(MessagePriority::Emergency) = self.priority {
+      if self.retry.is_none() || self.expire.is_none() {
+        errors.push(ValidationError::MissingRetryOrExpire);
+      }
+
+      if self.ttl.is_some() {
+        errors.push(ValidationError::EmergencyWithTtl);
+      }
+    }
+
+    errors.extend(
+      self
+        .tags
+        .iter()
+        .filter(|tag| {
+          tag.is_empty()
+            || tag.contains(',')
+            || tag.contains(char::is_whitespace)
+        })
+        .map(|tag| ValidationError::InvalidTag(tag.clone())),
+    );
+
+    if self.attachment.is_some() && self.attachment_base64.is_some() {
+      errors.push(ValidationError::ConflictingAttachments);
+    }
+
+    if self.attachment_base64.is_some() != self.attachment_type.is_some() {
+      errors.push(ValidationError::IncompleteBase64Attachment);
+    }
+
+    if self.html == Some(true) && self.monospace == Some(true) {
+      errors.push(ValidationError::HtmlAndMonospace);
+    }
+
+    if errors.is_empty() {
+      Ok(())
+    } else {
+      Err(ValidationErrors(errors))
     }
   }
 
@@ -125,7 +1678,22 @@ impl Message {
 }
 
 /// The [message priority](https://pushover.net/api#priority).
-#[derive(Debug, Serialize_repr, Deserialize_repr)]
+///
+/// Priorities are serialized as their number, but can be deserialized from
+/// either their number or their case-insensitive name.
+///
+/// ```rust
+/// use pushover_api::MessagePriority;
+///
+/// let from_number: MessagePriority = serde_json::from_str("1").unwrap();
+/// let from_name: MessagePriority = serde_json::from_str("\"High\"").unwrap();
+/// assert_eq!(from_number, MessagePriority::High);
+/// assert_eq!(from_name, MessagePriority::High);
+///
+/// assert!(serde_json::from_str::<MessagePriority>("3").is_err());
+/// assert!(serde_json::from_str::<MessagePriority>("\"urgent\"").is_err());
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Serialize_repr)]
 #[repr(i8)]
 pub enum MessagePriority {
   /// From the Pushover documentation:
@@ -153,6 +1721,7 @@ pub enum MessagePriority {
   /// > center.
   ///
   /// > If a user has quiet hours set and your message is received during those times, your message will be delivered as though it had a priority of `-1`.
+  #[default]
   Normal = 0,
   /// From the Pushover documentation:
   ///
@@ -163,51 +1732,542 @@ pub enum MessagePriority {
   ///
   /// > High-priority messages are highlighted in red in the device clients.
   High = 1,
+  /// From the Pushover documentation:
+  ///
+  /// > Emergency-priority notifications are similar to high-priority
+  /// > notifications, but they are repeated until the notification is
+  /// > acknowledged by the user.
+  ///
+  /// Messages with this priority require both
+  /// [`retry`](struct.Message.html#structfield.retry) and
+  /// [`expire`](struct.Message.html#structfield.expire) to be set.
+  ///
+  /// ```rust,no_run
+  /// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+  /// # {
+  /// use pushover_api::{Message, MessagePriority};
+  ///
+  /// let response = Message {
+  ///   token: "application token".into(),
+  ///   user: "user key".into(),
+  ///   message: "The server is on fire!".to_string(),
+  ///   priority: Some(MessagePriority::Emergency),
+  ///   retry: Some(60),
+  ///   expire: Some(3600),
+  ///   ..Message::default()
+  /// }
+  /// .send()
+  /// .unwrap();
+  ///
+  /// dbg!(response.receipt);
+  /// # }
+  /// ```
+  Emergency = 2,
 }
 
-impl From<&str> for MessagePriority {
-  fn from(input: &str) -> Self {
-    match input {
-      "-2" | "lowest" => MessagePriority::Lowest,
-      "-1" | "low" => MessagePriority::Low,
-      "0" | "normal" => MessagePriority::Normal,
-      "1" | "high" => MessagePriority::High,
-      _ => unreachable!(),
+impl MessagePriority {
+  /// Returns the priority with the given number.
+  pub(crate) fn from_number(number: i64) -> Option<Self> {
+    match number {
+      -2 => Some(MessagePriority::Lowest),
+      -1 => Some(MessagePriority::Low),
+      0 => Some(MessagePriority::Normal),
+      1 => Some(MessagePriority::High),
+      2 => Some(MessagePriority::Emergency),
+      _ => None,
+    }
+  }
+
+  /// Returns the priority with the given case-insensitive name or number.
+  pub(crate) fn from_name(name: &str) -> Option<Self> {
+    match name.to_lowercase().as_str() {
+      "lowest" => Some(MessagePriority::Lowest),
+      "low" => Some(MessagePriority::Low),
+      "normal" => Some(MessagePriority::Normal),
+      "high" => Some(MessagePriority::High),
+      "emergency" => Some(MessagePriority::Emergency),
+      number => number.parse().ok().and_then(Self::from_number),
+    }
+  }
+
+  /// The lowercase name of the priority.
+  pub fn name(self) -> &'static str {
+    match self {
+      MessagePriority::Lowest => "lowest",
+      MessagePriority::Low => "low",
+      MessagePriority::Normal => "normal",
+      MessagePriority::High => "high",
+      MessagePriority::Emergency => "emergency",
+    }
+  }
+}
+
+/// Displays the name of the priority, or its number with the alternate flag.
+///
+/// ```rust
+/// use pushover_api::MessagePriority;
+///
+/// assert_eq!(MessagePriority::High.to_string(), "high");
+/// assert_eq!(format!("{:#}", MessagePriority::Lowest), "-2");
+/// ```
+impl fmt::Display for MessagePriority {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if f.alternate() {
+      write!(f, "{}", i8::from(*self))
+    } else {
+      f.write_str(self.name())
+    }
+  }
+}
+
+/// Parses a priority from its case-insensitive name or its number.
+///
+/// ```rust
+/// use pushover_api::MessagePriority;
+///
+/// assert_eq!("lowest".parse(), Ok(MessagePriority::Lowest));
+/// assert_eq!("Low".parse(), Ok(MessagePriority::Low));
+/// assert_eq!("0".parse(), Ok(MessagePriority::Normal));
+/// assert_eq!("HIGH".parse(), Ok(MessagePriority::High));
+/// assert_eq!("2".parse(), Ok(MessagePriority::Emergency));
+///
+/// let error = "urgent".parse::<MessagePriority>().unwrap_err();
+/// assert_eq!(
+///   error.to_string(),
+///   "Invalid priority \"urgent\", expected lowest, low, normal, high, \
+///    emergency or -2 to 2"
+/// );
+/// assert!("3".parse::<MessagePriority>().is_err());
+/// ```
+impl FromStr for MessagePriority {
+  type Err = InvalidPriority;
+
+  fn from_str(input: &str) -> Result<Self, Self::Err> {
+    Self::from_name(input.trim())
+      .ok_or_else(|| InvalidPriority(input.to_string()))
+  }
+}
+
+/// Parses a priority the same way as [`FromStr`](#impl-FromStr), returning
+/// an error instead of panicking for invalid input.
+///
+/// ```rust
+/// use pushover_api::MessagePriority;
+/// use std::convert::TryFrom;
+///
+/// assert_eq!(MessagePriority::try_from("high"), Ok(MessagePriority::High));
+/// assert_eq!(MessagePriority::try_from("-2"), Ok(MessagePriority::Lowest));
+/// assert!(MessagePriority::try_from("urgent").is_err());
+/// ```
+impl TryFrom<&str> for MessagePriority {
+  type Error = InvalidPriority;
+
+  fn try_from(input: &str) -> Result<Self, Self::Error> {
+    input.parse()
+  }
+}
+
+/// Converts a number from -2 to 2 into a priority.
+///
+/// ```rust
+/// use pushover_api::MessagePriority;
+/// use std::convert::TryFrom;
+///
+/// for number in -2..=2 {
+///   let priority = MessagePriority::try_from(number).unwrap();
+///   assert_eq!(i8::from(priority), number);
+/// }
+///
+/// assert!(MessagePriority::try_from(3i8).is_err());
+/// ```
+impl TryFrom<i8> for MessagePriority {
+  type Error = InvalidPriority;
+
+  fn try_from(number: i8) -> Result<Self, Self::Error> {
+    Self::from_number(number.into())
+      .ok_or_else(|| InvalidPriority(number.to_string()))
+  }
+}
+
+impl From<MessagePriority> for i8 {
+  fn from(priority: MessagePriority) -> Self {
+    priority as i8
+  }
+}
+
+/// The error returned when parsing an invalid
+/// [`MessagePriority`](enum.MessagePriority.html), with the invalid input.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvalidPriority(pub String);
+
+impl fmt::Display for InvalidPriority {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "Invalid priority {:?}, expected lowest, low, normal, high, emergency \
+       or -2 to 2",
+      self.0
+    )
+  }
+}
+
+impl std::error::Error for InvalidPriority {}
+
+impl<'de> Deserialize<'de> for MessagePriority {
+  fn deserialize<D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> std::result::Result<Self, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumberOrName {
+      Number(i64),
+      Name(String),
+    }
+
+    match NumberOrName::deserialize(deserializer)? {
+      NumberOrName::Number(number) => {
+        Self::from_number(number).ok_or_else(|| {
+          D::Error::custom(format!("invalid priority {}", number))
+        })
+      }
+      NumberOrName::Name(name) => Self::from_name(&name).ok_or_else(|| {
+        D::Error::custom(format!("invalid priority {:?}", name))
+      }),
     }
   }
 }
 
 /// The response from Pushover and the HTTP status code after a message was
 /// successfully sent.
-#[derive(Debug)]
+///
+/// It can be serialized, for example to keep an audit log of sent messages.
+///
+/// ```rust
+/// use pushover_api::{MessageResponse, ResponseStatus};
+///
+/// let json = r#"{
+///   "http_status": 200,
+///   "receipt": null,
+///   "request": "5042853c-402d-4a18-abcb-168734a801de",
+///   "status": 1,
+///   "limit": 10000,
+///   "remaining": 7496,
+///   "reset": 1393653600
+/// }"#;
+///
+/// let response: MessageResponse = serde_json::from_str(json).unwrap();
+/// assert!(response.is_success());
+/// assert_eq!(response.status, ResponseStatus::Success);
+///
+/// let serialized = serde_json::to_string(&response).unwrap();
+/// assert_eq!(
+///   serde_json::from_str::<MessageResponse>(&serialized).unwrap(),
+///   response
+/// );
+/// ```
+///
+/// The limits are read from the headers of the response, and left as `None`
+/// when a header is missing or isn't a number.
+///
+/// ```rust
+/// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+/// # {
+/// use http::StatusCode;
+/// use pushover_api::{
+///   test_support::FakeTransport, HttpResponse, PushoverClient,
+/// };
+///
+/// let body = r#"{"status":1,"request":"5042853c"}"#;
+/// let transport = FakeTransport::new();
+/// transport.push_response(Ok(HttpResponse::json(StatusCode::OK, body)
+///   .with_header("X-Limit-App-Limit", "10000")
+///   .with_header("X-Limit-App-Remaining", " 7496 ")
+///   .with_header("X-Limit-App-Reset", "1393653600")));
+/// transport.push_json(200, body);
+/// transport.push_response(Ok(HttpResponse::json(StatusCode::OK, body)
+///   .with_header("X-Limit-App-Limit", "lots")
+///   .with_header("X-Limit-App-Remaining", "-1")));
+///
+/// let client = PushoverClient::with_transport("token", &transport)
+///   .default_user("user key");
+///
+/// let response = client.notify("Message").unwrap();
+/// assert_eq!(response.limit, Some(10000));
+/// assert_eq!(response.remaining, Some(7496));
+/// assert_eq!(response.reset, Some(1393653600));
+///
+/// for _ in 0..2 {
+///   let response = client.notify("Message").unwrap();
+///   assert_eq!(response.limit, None);
+///   assert_eq!(response.remaining, None);
+///   assert_eq!(response.reset, None);
+///   assert!(response.is_success());
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct MessageResponse {
+  /// The HTTP status code, serialized as its numeric value.
+  #[serde(with = "status_code")]
   pub http_status: StatusCode,
-  pub request: String,
-  pub status: i32,
+  /// The receipt for an emergency priority message, can be used to poll
+  /// whether the message has been acknowledged.
+  pub receipt: Option<Receipt>,
+  /// The identifier of the request.
+  pub request: RequestId,
+  /// The status of the response, serialized as its numeric value.
+  pub status: ResponseStatus,
+  /// The number of messages the application can send per month, from the
+  /// `X-Limit-App-Limit` header.
+  pub limit: Option<u32>,
+  /// The number of messages the application has left this month, from the
+  /// `X-Limit-App-Remaining` header.
+  pub remaining: Option<u32>,
+  /// When the limit resets as a Unix timestamp, from the `X-Limit-App-Reset`
+  /// header.
+  pub reset: Option<i64>,
+  /// Any other keys of the response.
+  #[serde(default)]
+  pub extras: ResponseExtras,
 }
 
-/// The response from Pushover after an API call is made, including any errors.
-///
-/// Only used internally, errors should be returned with `Err`.
-#[derive(Debug, Deserialize, Serialize)]
-pub(crate) struct RawMessageResponse {
+impl MessageResponse {
+  /// Whether the Pushover API reported the request as successful.
+  pub fn is_success(&self) -> bool {
+    self.status == ResponseStatus::Success
+  }
+
+  /// Cancels the retries of the emergency priority message this response is
+  /// for, see [`cancel_receipt()`](fn.cancel_receipt.html) and
+  /// [`PushoverClient::cancel_retries()`](struct.PushoverClient.html#method.cancel_retries).
+  ///
+  /// Returns an error when the response has no receipt, which is the case for
+  /// any message that isn't emergency priority.
+  ///
+  /// ```rust,no_run
+  /// use pushover_api::{Message, MessagePriority};
+  ///
+  /// let token = "application token";
+  /// let response = Message {
+  ///   token: token.into(),
+  ///   user: "user key".into(),
+  ///   message: "The server is on fire!".to_string(),
+  ///   priority: Some(MessagePriority::Emergency),
+  ///   retry: Some(60),
+  ///   expire: Some(3600),
+  ///   ..Message::default()
+  /// }
+  /// .send()
+  /// .unwrap();
+  ///
+  /// // The fire has been put out.
+  /// response.cancel_retries(token).unwrap();
+  /// ```
+  #[cfg(any(feature = "blocking", feature = "ureq"))]
+  pub fn cancel_retries(&self, token: &str) -> Result<()> {
+    let receipt = self.receipt.as_ref().ok_or(PushoverError::MissingReceipt)?;
+    receipts::cancel_receipt(token, receipt)
+  }
+
+  /// Creates the response of sending a message from the Pushover API's reply.
+  pub(crate) fn from_reply(reply: ApiReply<MessagePayload>) -> Self {
+    MessageResponse {
+      http_status: reply.http_status,
+      receipt: reply.response.payload.receipt,
+      request: reply.response.request.unwrap_or_default(),
+      status: reply.response.status.into(),
+      limit: reply.limit,
+      remaining: reply.remaining,
+      reset: reply.reset,
+      extras: reply.response.payload.extras,
+    }
+  }
+}
+
+/// The `status` of a Pushover API response, which is `1` when the request
+/// was successful.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(from = "i32", into = "i32")]
+pub enum ResponseStatus {
+  /// The request was successful.
+  Success,
+  /// The request failed, with the status returned by the Pushover API.
+  Failure(i32),
+}
+
+impl From<i32> for ResponseStatus {
+  fn from(status: i32) -> Self {
+    match status {
+      1 => ResponseStatus::Success,
+      status => ResponseStatus::Failure(status),
+    }
+  }
+}
+
+impl From<ResponseStatus> for i32 {
+  fn from(status: ResponseStatus) -> Self {
+    match status {
+      ResponseStatus::Success => 1,
+      ResponseStatus::Failure(status) => status,
+    }
+  }
+}
+
+/// The keys of the Pushover API's response to sending a message.
+#[derive(Debug, Deserialize)]
+pub(crate) struct MessagePayload {
   #[serde(default)]
-  pub errors: Vec<String>,
-  pub request: String,
-  pub status: i32,
+  pub receipt: Option<Receipt>,
+  #[serde(flatten)]
+  pub extras: ResponseExtras,
 }
 
 /// Convenience function to send a simple message without having to construct
 /// the [`Message`](struct.Message.html) yourself.
+///
+/// ```rust
+/// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+/// # {
+/// use pushover_api::{
+///   send_message_with_priority, send_simple_message,
+///   send_simple_message_with_title, send_simple_message_with_url,
+///   MessagePriority,
+/// };
+/// use serde_json::json;
+///
+/// # let server = pushover_api::test_support::MockServer::respond(vec![]);
+/// # server.set_api_url_var();
+/// let (token, user) = ("application token", "user key");
+///
+/// send_simple_message(token, user, "Message").unwrap();
+/// send_simple_message_with_title(token, user, "Message", "Title").unwrap();
+/// send_message_with_priority(token, user, "Message", MessagePriority::High)
+///   .unwrap();
+/// send_simple_message_with_url(
+///   token,
+///   user,
+///   "Message",
+///   "https://example.com",
+///   None,
+/// )
+/// .unwrap();
+///
+/// let required = json!({"token":token,"user":user,"message":"Message"});
+/// let mut expected = vec![required.clone(); 4];
+/// expected[1]["title"] = json!("Title");
+/// expected[2]["priority"] = json!(1);
+/// expected[3]["url"] = json!("https://example.com");
+/// # let bodies = server.requests().iter().map(|r| r.json()).collect::<Vec<_>>();
+/// assert_eq!(bodies, expected);
+/// # }
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
 pub fn send_simple_message(
   token: &str,
   user: &str,
   message: &str,
 ) -> Result<MessageResponse> {
   Message {
-    token: token.to_string(),
-    user: user.to_string(),
+    token: token.into(),
+    user: user.into(),
+    message: message.to_string(),
+    ..Message::default()
+  }
+  .send()
+}
+
+/// Convenience function to send a message with a title.
+///
+/// ```rust,no_run
+/// use pushover_api::send_simple_message_with_title;
+///
+/// send_simple_message_with_title(
+///   "application token",
+///   "user key",
+///   "Message",
+///   "Title",
+/// )
+/// .unwrap();
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn send_simple_message_with_title(
+  token: &str,
+  user: &str,
+  message: &str,
+  title: &str,
+) -> Result<MessageResponse> {
+  Message {
+    token: token.into(),
+    user: user.into(),
+    message: message.to_string(),
+    title: Some(title.to_string()),
+    ..Message::default()
+  }
+  .send()
+}
+
+/// Convenience function to send a message with a priority.
+///
+/// Since emergency priority messages also require `retry` and `expire`, they
+/// have to be sent with a [`Message`](struct.Message.html) instead.
+///
+/// ```rust,no_run
+/// use pushover_api::{send_message_with_priority, MessagePriority};
+///
+/// send_message_with_priority(
+///   "application token",
+///   "user key",
+///   "Message",
+///   MessagePriority::High,
+/// )
+/// .unwrap();
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn send_message_with_priority(
+  token: &str,
+  user: &str,
+  message: &str,
+  priority: MessagePriority,
+) -> Result<MessageResponse> {
+  Message {
+    token: token.into(),
+    user: user.into(),
+    message: message.to_string(),
+    priority: Some(priority),
+    ..Message::default()
+  }
+  .send()
+}
+
+/// Convenience function to send a message with a supplementary URL and an
+/// optional title for it.
+///
+/// ```rust,no_run
+/// use pushover_api::send_simple_message_with_url;
+///
+/// send_simple_message_with_url(
+///   "application token",
+///   "user key",
+///   "Message",
+///   "https://example.com",
+///   Some("Example"),
+/// )
+/// .unwrap();
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn send_simple_message_with_url(
+  token: &str,
+  user: &str,
+  message: &str,
+  url: &str,
+  url_title: Option<&str>,
+) -> Result<MessageResponse> {
+  Message {
+    token: token.into(),
+    user: user.into(),
     message: message.to_string(),
+    url: Some(url.to_string()),
+    url_title: url_title.map(String::from),
     ..Message::default()
   }
   .send()
@@ -0,0 +1,247 @@
+//! Assigning Pushover licenses to users.
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+use crate::{
+  api, api_base, http_client, join_url, HttpBody, HttpMethod, HttpRequest,
+  PushoverClient, PushoverError, Result, Transport,
+};
+use serde::Deserialize;
+use std::fmt;
+
+/// The platforms a license can be restricted to.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum LicenseOs {
+  /// Android devices.
+  Android,
+  /// iPhones and iPads.
+  Ios,
+  /// The desktop web client.
+  Desktop,
+}
+
+impl LicenseOs {
+  /// Returns the name of the platform as the Pushover API expects it.
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      LicenseOs::Android => "Android",
+      LicenseOs::Ios => "iOS",
+      LicenseOs::Desktop => "Desktop",
+    }
+  }
+}
+
+impl fmt::Display for LicenseOs {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+/// The license credits an application has left, as returned by
+/// [`assign_license()`](fn.assign_license.html) and
+/// [`check_license_credits()`](fn.check_license_credits.html).
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct LicenseCredits {
+  /// The number of licenses that can still be assigned.
+  #[serde(default)]
+  pub credits: u32,
+}
+
+/// Assigns a license to a user, identified by exactly one of their user key
+/// or their email address, returning the credits that are left. A license
+/// for an email address without an account is used when they sign up.
+///
+/// When `os` is given, the license only works on that platform. Returns an
+/// error without making a request when both or neither of `user` and `email`
+/// are given.
+///
+/// ```rust,no_run
+/// use pushover_api::LicenseOs;
+///
+/// let credits = pushover_api::assign_license(
+///   "application token",
+///   None,
+///   Some("new.teammate@example.com"),
+///   Some(LicenseOs::Ios),
+/// )
+/// .unwrap();
+///
+/// println!("{} licenses left", credits.credits);
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn assign_license(
+  token: &str,
+  user: Option<&str>,
+  email: Option<&str>,
+  os: Option<LicenseOs>,
+) -> Result<LicenseCredits> {
+  assign_license_using(&http_client()?, &api_base(), token, user, email, os)
+}
+
+/// Assigns a license with the given transport and base URL.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+fn assign_license_using(
+  transport: &impl Transport,
+  base: &str,
+  token: &str,
+  user: Option<&str>,
+  email: Option<&str>,
+  os: Option<LicenseOs>,
+) -> Result<LicenseCredits> {
+  let recipient = match (user, email) {
+    (Some(user), None) => ("user", user),
+    (None, Some(email)) => ("email", email),
+    _ => {
+      return Err(PushoverError::InvalidArgument(
+        "A license is assigned to exactly one of a user key or an email"
+          .to_string(),
+      ))
+    }
+  };
+
+  let mut fields = vec![
+    ("token".to_string(), token.to_string()),
+    (recipient.0.to_string(), recipient.1.to_string()),
+  ];
+  if let Some(os) = os {
+    fields.push(("os".to_string(), os.to_string()));
+  }
+
+  let request =
+    HttpRequest::new(HttpMethod::Post, join_url(base, "licenses/assign.json"))
+      .with_body(HttpBody::Form(fields));
+  Ok(api::execute(transport, request)?.response.payload)
+}
+
+/// Gets the license credits an application has left, without assigning any.
+///
+/// An invalid token is returned as an
+/// [`Api`](enum.PushoverError.html#variant.Api) error, whose
+/// [`ApiError::kind()`](struct.ApiError.html#method.kind) is
+/// [`InvalidToken`](enum.ApiErrorKind.html#variant.InvalidToken).
+///
+/// ```rust,no_run
+/// let credits =
+///   pushover_api::check_license_credits("application token").unwrap();
+/// if credits.credits == 0 {
+///   eprintln!("Out of license credits, buy more before onboarding");
+/// }
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn check_license_credits(token: &str) -> Result<LicenseCredits> {
+  check_license_credits_using(&http_client()?, &api_base(), token)
+}
+
+/// Gets the license credits with the given transport and base URL.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+fn check_license_credits_using(
+  transport: &impl Transport,
+  base: &str,
+  token: &str,
+) -> Result<LicenseCredits> {
+  let query = serde_urlencoded::to_string([("token", token)])?;
+  let request = HttpRequest::new(
+    HttpMethod::Get,
+    format!("{}?{}", join_url(base, "licenses.json"), query),
+  );
+
+  Ok(api::execute(transport, request)?.response.payload)
+}
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+impl<T: Transport> PushoverClient<T> {
+  /// Assigns a license to a user by their user key or email address, see
+  /// [`assign_license()`](fn.assign_license.html).
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, LicenseOs, PushoverClient, PushoverError,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(200, r#"{"status":1,"credits":4,"request":"5042853c"}"#);
+  /// transport.push_json(200, r#"{"status":1,"credits":3,"request":"5042853c"}"#);
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"errors":["application does not have enough license credits"],
+  ///     "status":0,"request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport);
+  ///
+  /// let credits = client
+  ///   .assign_license(Some("user key"), None, Some(LicenseOs::Android))
+  ///   .unwrap();
+  /// assert_eq!(credits.credits, 4);
+  ///
+  /// let credits = client
+  ///   .assign_license(None, Some("new.teammate@example.com"), None)
+  ///   .unwrap();
+  /// assert_eq!(credits.credits, 3);
+  ///
+  /// let error = client
+  ///   .assign_license(Some("user key"), None, None)
+  ///   .unwrap_err();
+  /// assert!(error.api_errors()[0].message.contains("enough license credits"));
+  ///
+  /// let both = client.assign_license(Some("user key"), Some("a@example.com"), None);
+  /// assert!(matches!(both, Err(PushoverError::InvalidArgument(_))));
+  /// let neither = client.assign_license(None, None, None);
+  /// assert!(matches!(neither, Err(PushoverError::InvalidArgument(_))));
+  ///
+  /// let requests = transport.requests();
+  /// assert_eq!(requests.len(), 3);
+  /// assert!(requests[0].url.ends_with("/1/licenses/assign.json"));
+  /// assert_eq!(requests[0].field("user").as_deref(), Some("user key"));
+  /// assert_eq!(requests[0].field("os").as_deref(), Some("Android"));
+  /// assert_eq!(requests[1].field("user"), None);
+  /// assert_eq!(
+  ///   requests[1].field("email").as_deref(),
+  ///   Some("new.teammate@example.com")
+  /// );
+  /// ```
+  pub fn assign_license(
+    &self,
+    user: Option<&str>,
+    email: Option<&str>,
+    os: Option<LicenseOs>,
+  ) -> Result<LicenseCredits> {
+    assign_license_using(
+      &self.transport,
+      &self.base_url,
+      self.token(),
+      user,
+      email,
+      os,
+    )
+  }
+
+  /// Gets the license credits the application has left, see
+  /// [`check_license_credits()`](fn.check_license_credits.html).
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, ApiErrorKind, PushoverClient,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(200, r#"{"status":1,"credits":12,"request":"5042853c"}"#);
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"token":"invalid","errors":["application token is invalid"],
+  ///     "status":0,"request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport);
+  ///
+  /// assert_eq!(client.check_license_credits().unwrap().credits, 12);
+  ///
+  /// let error = client.check_license_credits().unwrap_err();
+  /// assert_eq!(error.api_errors()[0].kind(), ApiErrorKind::InvalidToken);
+  ///
+  /// let request = &transport.requests()[0];
+  /// assert!(request.url.ends_with("/1/licenses.json?token=application+token"));
+  /// ```
+  pub fn check_license_credits(&self) -> Result<LicenseCredits> {
+    check_license_credits_using(&self.transport, &self.base_url, self.token())
+  }
+}
@@ -0,0 +1,63 @@
+//! Loading messages from TOML and JSON files.
+
+use crate::{Message, PushoverError, Result};
+use std::{fs, path::Path};
+
+impl Message {
+  /// Loads a message from a TOML or JSON file, depending on its extension.
+  ///
+  /// Any field can be left out, so the token and user can be filled in after
+  /// loading. The priority can be its name and devices and tags can be lists.
+  ///
+  /// ```rust
+  /// use pushover_api::{Message, MessagePriority, Sound};
+  ///
+  /// let mut message = Message::from_path("examples/deploy_finished.toml")
+  ///   .unwrap();
+  /// message.token = "application token".into();
+  /// message.user = "user key".into();
+  ///
+  /// assert_eq!(message.priority, Some(MessagePriority::Low));
+  /// assert_eq!(message.device, vec!["phone", "desktop"]);
+  ///
+  /// let message = Message::from_path("examples/backup_failed.json").unwrap();
+  /// assert_eq!(message.priority, Some(MessagePriority::Emergency));
+  /// assert_eq!(message.sound, Some(Sound::Siren));
+  /// ```
+  pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path)?;
+
+    match path.extension().and_then(|extension| extension.to_str()) {
+      Some("toml") => Self::from_toml_str(&contents),
+      Some("json") => Self::from_json_str(&contents),
+      _ => Err(PushoverError::InvalidArgument(format!(
+        "Failed to load {}, only .toml and .json files are supported",
+        path.display()
+      ))),
+    }
+  }
+
+  /// Loads a message from a TOML string.
+  ///
+  /// Errors include the path to the offending field.
+  ///
+  /// ```rust
+  /// use pushover_api::Message;
+  ///
+  /// let error = Message::from_toml_str("priority = \"urgent\"").unwrap_err();
+  /// assert!(error.to_string().starts_with("priority: "));
+  /// ```
+  pub fn from_toml_str(input: &str) -> Result<Self> {
+    let mut deserializer = toml::Deserializer::new(input);
+    serde_path_to_error::deserialize(&mut deserializer).map_err(Into::into)
+  }
+
+  /// Loads a message from a JSON string.
+  ///
+  /// Errors include the path to the offending field.
+  pub fn from_json_str(input: &str) -> Result<Self> {
+    let mut deserializer = serde_json::Deserializer::from_str(input);
+    serde_path_to_error::deserialize(&mut deserializer).map_err(Into::into)
+  }
+}
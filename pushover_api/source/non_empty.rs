@@ -0,0 +1,15 @@
+//! Serde helpers for strings where an empty string means there is no value.
+
+use serde::{Deserialize, Deserializer};
+
+/// Deserializes an optional string where an empty string and `null` result in
+/// `None`, which is what the Pushover API returns for values that aren't set.
+pub(crate) fn deserialize<'de, D>(
+  deserializer: D,
+) -> Result<Option<String>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  let value = Option::<String>::deserialize(deserializer)?;
+  Ok(value.filter(|value| !value.is_empty()))
+}
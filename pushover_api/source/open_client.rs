@@ -0,0 +1,789 @@
+//! The [Open Client API](https://pushover.net/api/client), for building
+//! clients that receive messages instead of sending them.
+//!
+//! A client logs in as a user with [`login()`](fn.login.html), which returns
+//! the user's secret that the other functions take to act on their behalf.
+//! These functions don't use an application token.
+//!
+//! Every function is also available on an [`OpenClient`](struct.OpenClient.html)
+//! with its own [`Transport`](../trait.Transport.html), to use a custom HTTP
+//! client or test without a network.
+//!
+//! With the `websocket` feature, [`listen()`](fn.listen.html) connects to
+//! Pushover's WebSocket server to be told about new messages as they arrive,
+//! instead of polling for them.
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+use crate::{
+  api, api_base, encode_path_segment, http_client, join_url, validation,
+  DeviceId, HttpBody, HttpMethod, HttpRequest, ResponseExtras, Transport,
+  MAX_DEVICE_NAME_LENGTH,
+};
+use crate::{
+  int_bool, MessagePriority, PushoverError, Receipt, Result, Secret, Sound,
+};
+#[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+use futures::{stream, SinkExt, Stream, StreamExt};
+use serde::Deserialize;
+#[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+use tokio_tungstenite::tungstenite;
+
+/// A user that logged in, as returned by [`login()`](fn.login.html).
+///
+/// The secret is a [`Secret`](../struct.Secret.html), so it doesn't show up
+/// in debug output.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+pub struct UserSecret {
+  /// The key of the user.
+  #[serde(rename = "id")]
+  pub user: String,
+  /// The secret to authenticate the user's requests with.
+  pub secret: Secret,
+}
+
+/// A message downloaded with
+/// [`download_messages()`](fn.download_messages.html).
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+pub struct IncomingMessage {
+  /// The identifier of the message on the device, which increases with every
+  /// message.
+  pub id: u64,
+  /// The identifier of the message across all of the user's devices.
+  pub umid: u64,
+  /// The title of the message, if it has one.
+  #[serde(default)]
+  pub title: Option<String>,
+  /// The text of the message.
+  pub message: String,
+  /// The name of the application that sent the message.
+  #[serde(default)]
+  pub app: String,
+  /// The identifier of the application that sent the message.
+  #[serde(default)]
+  pub aid: u64,
+  /// The identifier of the application's icon, to download it from
+  /// `https://api.pushover.net/icons/{icon}.png`.
+  #[serde(default)]
+  pub icon: String,
+  /// The Unix timestamp of the message.
+  pub date: i64,
+  /// The priority of the message.
+  #[serde(default)]
+  pub priority: MessagePriority,
+  /// The sound to play for the message, if it has one.
+  #[serde(default)]
+  pub sound: Option<Sound>,
+  /// The supplementary URL of the message.
+  #[serde(default)]
+  pub url: Option<String>,
+  /// The title of the supplementary URL.
+  #[serde(default)]
+  pub url_title: Option<String>,
+  /// The receipt of an emergency priority message, to acknowledge it with.
+  #[serde(default)]
+  pub receipt: Option<Receipt>,
+  /// Whether the message is formatted with HTML.
+  #[serde(default, deserialize_with = "int_bool::deserialize_flag")]
+  pub html: bool,
+  /// Whether the emergency priority message has been acknowledged.
+  #[serde(default, deserialize_with = "int_bool::deserialize_flag")]
+  pub acked: bool,
+}
+
+/// The keys of the Pushover API's response to downloading messages.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+#[derive(Debug, Deserialize)]
+struct MessagesPayload {
+  #[serde(default)]
+  messages: Vec<IncomingMessage>,
+}
+
+/// The keys of the Pushover API's response to registering a device.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+#[derive(Debug, Deserialize)]
+struct RegisteredPayload {
+  id: DeviceId,
+}
+
+/// A client for the Open Client API that sends its requests with a
+/// [`Transport`](../trait.Transport.html).
+///
+/// ```rust
+/// use pushover_api::{
+///   open_client::OpenClient, test_support::FakeTransport, ApiErrorKind,
+/// };
+///
+/// let transport = FakeTransport::new();
+/// transport.push_json(
+///   200,
+///   r#"{"status":1,"id":"uQiRzpo4DXghDmr9QzzfQu27cmVRsG",
+///     "secret":"zz94tr1uiQe3TgU9fixqSQmH1BXr5GX5","request":"5042853c"}"#,
+/// );
+/// transport.push_json(
+///   400,
+///   r#"{"status":0,"errors":["invalid email and/or password"],
+///     "request":"5042853c"}"#,
+/// );
+/// transport.push_json(
+///   412,
+///   r#"{"status":0,"errors":["two-factor authentication is required"],
+///     "request":"5042853c"}"#,
+/// );
+///
+/// let client = OpenClient::with_transport(&transport);
+///
+/// let user = client
+///   .login("user@example.com", "password", Some("123456"))
+///   .unwrap();
+/// assert_eq!(user.user, "uQiRzpo4DXghDmr9QzzfQu27cmVRsG");
+/// assert_eq!(user.secret.expose_secret(), "zz94tr1uiQe3TgU9fixqSQmH1BXr5GX5");
+/// assert!(!format!("{:?}", user).contains("zz94tr1uiQe3TgU9fixqSQmH1BXr5GX5"));
+///
+/// let error = client.login("user@example.com", "wrong", None).unwrap_err();
+/// assert_eq!(error.api_errors()[0].kind(), ApiErrorKind::InvalidCredentials);
+///
+/// let error = client.login("user@example.com", "password", None).unwrap_err();
+/// assert_eq!(error.api_errors()[0].kind(), ApiErrorKind::TwoFactorRequired);
+///
+/// let requests = transport.requests();
+/// assert!(requests[0].url.ends_with("/1/users/login.json"));
+/// assert_eq!(requests[0].field("email").as_deref(), Some("user@example.com"));
+/// assert_eq!(requests[0].field("password").as_deref(), Some("password"));
+/// assert_eq!(requests[0].field("twofa").as_deref(), Some("123456"));
+/// assert_eq!(requests[1].field("twofa"), None);
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+#[derive(Clone, Debug)]
+pub struct OpenClient<T> {
+  transport: T,
+  base_url: String,
+}
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+impl<T: Transport> OpenClient<T> {
+  /// Creates a client that sends its requests with `transport`.
+  pub fn with_transport(transport: T) -> Self {
+    Self {
+      transport,
+      base_url: api_base(),
+    }
+  }
+
+  /// Logs in as a user with their email address and password, and their
+  /// two-factor authentication code when they have it enabled.
+  ///
+  /// Wrong credentials are returned as an
+  /// [`Api`](../enum.PushoverError.html#variant.Api) error, whose
+  /// [`ApiError::kind()`](../struct.ApiError.html#method.kind) is
+  /// [`InvalidCredentials`](../enum.ApiErrorKind.html#variant.InvalidCredentials),
+  /// and a missing or wrong two-factor code is
+  /// [`TwoFactorRequired`](../enum.ApiErrorKind.html#variant.TwoFactorRequired).
+  pub fn login(
+    &self,
+    email: &str,
+    password: &str,
+    twofa: Option<&str>,
+  ) -> Result<UserSecret> {
+    let mut fields = vec![
+      ("email".to_string(), email.to_string()),
+      ("password".to_string(), password.to_string()),
+    ];
+    if let Some(twofa) = twofa {
+      fields.push(("twofa".to_string(), twofa.to_string()));
+    }
+
+    let request = HttpRequest::new(
+      HttpMethod::Post,
+      join_url(&self.base_url, "users/login.json"),
+    )
+    .with_body(HttpBody::Form(fields));
+    Ok(api::execute(&self.transport, request)?.response.payload)
+  }
+
+  /// Registers a device for a user, returning its identifier to download the
+  /// user's messages with.
+  ///
+  /// The name has to be at most
+  /// [`MAX_DEVICE_NAME_LENGTH`](../constant.MAX_DEVICE_NAME_LENGTH.html)
+  /// characters of ASCII letters, numbers, underscores and dashes, otherwise
+  /// an error is returned without making a request. A name the user already
+  /// has a device with is returned as an
+  /// [`Api`](../enum.PushoverError.html#variant.Api) error, whose
+  /// [`ApiError::kind()`](../struct.ApiError.html#method.kind) is
+  /// [`DeviceNameTaken`](../enum.ApiErrorKind.html#variant.DeviceNameTaken),
+  /// and [`TooManyDevices`](../enum.ApiErrorKind.html#variant.TooManyDevices)
+  /// when the user can't have any more devices.
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   open_client::OpenClient, test_support::FakeTransport, ApiErrorKind,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"status":1,"id":"HtL5lLn6wBzYX8jMBE3YS6sNCo1ei7Pr",
+  ///     "request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"status":0,"errors":{"name":["has already been taken"]},
+  ///     "request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"status":0,"errors":["you have too many active devices"],
+  ///     "request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = OpenClient::with_transport(&transport);
+  ///
+  /// let device = client.register_device("user secret", "headless-box").unwrap();
+  /// assert_eq!(device.as_str(), "HtL5lLn6wBzYX8jMBE3YS6sNCo1ei7Pr");
+  ///
+  /// let error = client
+  ///   .register_device("user secret", "headless-box")
+  ///   .unwrap_err();
+  /// assert_eq!(error.api_errors()[0].kind(), ApiErrorKind::DeviceNameTaken);
+  ///
+  /// let error = client.register_device("user secret", "another").unwrap_err();
+  /// assert_eq!(error.api_errors()[0].kind(), ApiErrorKind::TooManyDevices);
+  ///
+  /// assert!(client.register_device("user secret", "headless box").is_err());
+  ///
+  /// let requests = transport.requests();
+  /// assert_eq!(requests.len(), 3);
+  /// assert!(requests[0].url.ends_with("/1/devices.json"));
+  /// assert_eq!(requests[0].field("secret").as_deref(), Some("user secret"));
+  /// assert_eq!(requests[0].field("name").as_deref(), Some("headless-box"));
+  /// assert_eq!(requests[0].field("os").as_deref(), Some("O"));
+  /// ```
+  pub fn register_device(&self, secret: &str, name: &str) -> Result<DeviceId> {
+    if !validation::is_valid_device_name(name) {
+      return Err(PushoverError::InvalidArgument(format!(
+        "Invalid device name {:?}, it must be at most {} ASCII letters, \
+         numbers, underscores and dashes",
+        name, MAX_DEVICE_NAME_LENGTH
+      )));
+    }
+
+    let request = HttpRequest::new(
+      HttpMethod::Post,
+      join_url(&self.base_url, "devices.json"),
+    )
+    .with_body(HttpBody::Form(vec![
+      ("secret".to_string(), secret.to_string()),
+      ("name".to_string(), name.to_string()),
+      ("os".to_string(), "O".to_string()),
+    ]));
+
+    let payload: RegisteredPayload =
+      api::execute(&self.transport, request)?.response.payload;
+    Ok(payload.id)
+  }
+
+  /// Downloads the messages of a device that haven't been deleted yet, sorted
+  /// by their identifier.
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   open_client::OpenClient, test_support::FakeTransport, MessagePriority,
+  ///   Sound,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"messages":[],"user":{"quiet_hours":false},"status":1,
+  ///     "request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"messages":[
+  ///     {"id":3,"id_str":"3","umid":7,"umid_str":"7","title":"Backup",
+  ///       "message":"The backup <b>failed</b>","app":"Backups","aid":23,
+  ///       "aid_str":"23","icon":"backups","date":1394566934,"priority":2,
+  ///       "sound":"siren","acked":1,"receipt":"rLqVuqTRh62UzxtmqiaLzQmVcPgiCy",
+  ///       "html":1},
+  ///     {"id":2,"id_str":"2","umid":6,"umid_str":"6",
+  ///       "message":"This is a test","app":"Pushover","aid":1,"aid_str":"1",
+  ///       "icon":"pushover","date":1394566833,"priority":0,
+  ///       "url":"https://example.com","url_title":"Example"}
+  ///   ],"user":{"quiet_hours":false},"status":1,"request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = OpenClient::with_transport(&transport);
+  ///
+  /// assert!(client
+  ///   .download_messages("user secret", "device id")
+  ///   .unwrap()
+  ///   .is_empty());
+  ///
+  /// let messages = client
+  ///   .download_messages("user secret", "device id")
+  ///   .unwrap();
+  /// assert_eq!(messages.len(), 2);
+  ///
+  /// let test = &messages[0];
+  /// assert_eq!((test.id, test.umid), (2, 6));
+  /// assert_eq!(test.title, None);
+  /// assert_eq!(test.priority, MessagePriority::Normal);
+  /// assert_eq!(test.url_title.as_deref(), Some("Example"));
+  /// assert!(!test.html && !test.acked);
+  ///
+  /// let backup = &messages[1];
+  /// assert_eq!(backup.id, 3);
+  /// assert_eq!(backup.app, "Backups");
+  /// assert_eq!((backup.aid, backup.icon.as_str()), (23, "backups"));
+  /// assert_eq!(backup.priority, MessagePriority::Emergency);
+  /// assert_eq!(backup.sound, Some(Sound::Siren));
+  /// assert!(backup.html && backup.acked);
+  /// assert!(backup.receipt.is_some());
+  ///
+  /// let request = &transport.requests()[0];
+  /// assert!(request
+  ///   .url
+  ///   .ends_with("/1/messages.json?secret=user+secret&device_id=device+id"));
+  /// ```
+  pub fn download_messages(
+    &self,
+    secret: &str,
+    device_id: &str,
+  ) -> Result<Vec<IncomingMessage>> {
+    let query = serde_urlencoded::to_string([
+      ("secret", secret),
+      ("device_id", device_id),
+    ])?;
+    let request = HttpRequest::new(
+      HttpMethod::Get,
+      format!("{}?{}", join_url(&self.base_url, "messages.json"), query),
+    );
+
+    let payload: MessagesPayload =
+      api::execute(&self.transport, request)?.response.payload;
+    let mut messages = payload.messages;
+    messages.sort_by_key(|message| message.id);
+    Ok(messages)
+  }
+
+  /// Deletes the messages of a device up to and including `message_id`, to
+  /// call with the highest [`IncomingMessage::id`](struct.IncomingMessage.html#structfield.id)
+  /// after processing downloaded messages.
+  ///
+  /// Messages stay on Pushover's servers until they're deleted, so a client
+  /// that skips this downloads the same messages again every time. An id of
+  /// 0 or an empty device identifier is returned as an error without making a
+  /// request, and a device that doesn't belong to the user as an
+  /// [`Api`](../enum.PushoverError.html#variant.Api) error, whose
+  /// [`ApiError::kind()`](../struct.ApiError.html#method.kind) is
+  /// [`InvalidDevice`](../enum.ApiErrorKind.html#variant.InvalidDevice).
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   open_client::OpenClient, test_support::FakeTransport, ApiErrorKind,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853c"}"#);
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"device":"invalid","status":0,
+  ///     "errors":["device not found for this user"],"request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = OpenClient::with_transport(&transport);
+  ///
+  /// client
+  ///   .delete_messages_up_to("user secret", "device id", 3)
+  ///   .unwrap();
+  ///
+  /// let error = client
+  ///   .delete_messages_up_to("user secret", "other device", 3)
+  ///   .unwrap_err();
+  /// assert!(matches!(
+  ///   error.api_errors()[0].kind(),
+  ///   ApiErrorKind::InvalidDevice(_)
+  /// ));
+  ///
+  /// assert!(client
+  ///   .delete_messages_up_to("user secret", "device id", 0)
+  ///   .is_err());
+  /// assert!(client.delete_messages_up_to("user secret", "", 3).is_err());
+  ///
+  /// let requests = transport.requests();
+  /// assert_eq!(requests.len(), 2);
+  /// assert!(requests[0]
+  ///   .url
+  ///   .ends_with("/1/devices/device%20id/update_highest_message.json"));
+  /// assert_eq!(requests[0].field("secret").as_deref(), Some("user secret"));
+  /// assert_eq!(requests[0].field("message").as_deref(), Some("3"));
+  /// ```
+  pub fn delete_messages_up_to(
+    &self,
+    secret: &str,
+    device_id: &str,
+    message_id: u64,
+  ) -> Result<()> {
+    if message_id == 0 {
+      return Err(PushoverError::InvalidArgument(
+        "Message ids start at 1, deleting up to 0 wouldn't delete anything"
+          .to_string(),
+      ));
+    }
+
+    if device_id.is_empty() {
+      return Err(PushoverError::InvalidArgument(
+        "The device id to delete messages of is empty".to_string(),
+      ));
+    }
+
+    let path = format!(
+      "devices/{}/update_highest_message.json",
+      encode_path_segment(device_id)
+    );
+    let request =
+      HttpRequest::new(HttpMethod::Post, join_url(&self.base_url, &path))
+        .with_body(HttpBody::Form(vec![
+          ("secret".to_string(), secret.to_string()),
+          ("message".to_string(), message_id.to_string()),
+        ]));
+
+    api::execute::<ResponseExtras>(&self.transport, request)?;
+    Ok(())
+  }
+
+  /// Acknowledges an emergency priority message with its
+  /// [`IncomingMessage::receipt`](struct.IncomingMessage.html#structfield.receipt),
+  /// which stops its retries on all of the user's devices.
+  ///
+  /// A message that's already been acknowledged, like from another device,
+  /// is returned as an [`Api`](../enum.PushoverError.html#variant.Api) error,
+  /// whose [`ApiError::kind()`](../struct.ApiError.html#method.kind) is
+  /// [`AlreadyAcknowledged`](../enum.ApiErrorKind.html#variant.AlreadyAcknowledged),
+  /// and a receipt that doesn't exist or has expired is
+  /// [`ReceiptNotFound`](../enum.ApiErrorKind.html#variant.ReceiptNotFound).
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   open_client::OpenClient, test_support::FakeTransport, ApiErrorKind,
+  ///   Receipt,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853c"}"#);
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"status":0,"errors":["receipt has already been acknowledged"],
+  ///     "request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   404,
+  ///   r#"{"receipt":"not found","status":0,
+  ///     "errors":["receipt not found; may be invalid or expired"],
+  ///     "request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = OpenClient::with_transport(&transport);
+  /// let receipt = Receipt::from("rLqVuqTRh62UzxtmqiaLzQmVcPgiCy");
+  ///
+  /// client.acknowledge("user secret", &receipt).unwrap();
+  ///
+  /// let error = client.acknowledge("user secret", &receipt).unwrap_err();
+  /// assert_eq!(error.api_errors()[0].kind(), ApiErrorKind::AlreadyAcknowledged);
+  ///
+  /// let error = client
+  ///   .acknowledge("user secret", &Receipt::from("expired"))
+  ///   .unwrap_err();
+  /// assert_eq!(error.api_errors()[0].kind(), ApiErrorKind::ReceiptNotFound);
+  ///
+  /// let requests = transport.requests();
+  /// assert!(requests[0]
+  ///   .url
+  ///   .ends_with("/1/receipts/rLqVuqTRh62UzxtmqiaLzQmVcPgiCy/acknowledge.json"));
+  /// assert_eq!(requests[0].field("secret").as_deref(), Some("user secret"));
+  /// ```
+  pub fn acknowledge(&self, secret: &str, receipt: &Receipt) -> Result<()> {
+    let request = HttpRequest::new(
+      HttpMethod::Post,
+      join_url(&self.base_url, &receipt.acknowledge_path()),
+    )
+    .with_body(HttpBody::Form(vec![(
+      "secret".to_string(),
+      secret.to_string(),
+    )]));
+
+    api::execute::<ResponseExtras>(&self.transport, request)?;
+    Ok(())
+  }
+}
+
+/// Logs in as a user, see [`OpenClient::login()`](struct.OpenClient.html#method.login).
+///
+/// ```rust,no_run
+/// use pushover_api::{open_client, ApiErrorKind};
+///
+/// let user = match open_client::login("user@example.com", "password", None) {
+///   Ok(user) => user,
+///   Err(error)
+///     if error.api_errors().iter().any(|error| {
+///       error.kind() == ApiErrorKind::TwoFactorRequired
+///     }) =>
+///   {
+///     open_client::login("user@example.com", "password", Some("123456"))
+///       .unwrap()
+///   }
+///   Err(error) => panic!("{}", error),
+/// };
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn login(
+  email: &str,
+  password: &str,
+  twofa: Option<&str>,
+) -> Result<UserSecret> {
+  OpenClient::with_transport(http_client()?).login(email, password, twofa)
+}
+
+/// Registers a device for a user, see
+/// [`OpenClient::register_device()`](struct.OpenClient.html#method.register_device).
+///
+/// ```rust,no_run
+/// use pushover_api::open_client;
+///
+/// let user = open_client::login("user@example.com", "password", None).unwrap();
+/// let device =
+///   open_client::register_device(user.secret.expose_secret(), "headless-box")
+///     .unwrap();
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn register_device(secret: &str, name: &str) -> Result<DeviceId> {
+  OpenClient::with_transport(http_client()?).register_device(secret, name)
+}
+
+/// Downloads the messages of a device, see
+/// [`OpenClient::download_messages()`](struct.OpenClient.html#method.download_messages).
+///
+/// ```rust,no_run
+/// use pushover_api::open_client;
+///
+/// let messages =
+///   open_client::download_messages("user secret", "device id").unwrap();
+/// for message in &messages {
+///   println!("{}: {}", message.app, message.message);
+/// }
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn download_messages(
+  secret: &str,
+  device_id: &str,
+) -> Result<Vec<IncomingMessage>> {
+  OpenClient::with_transport(http_client()?)
+    .download_messages(secret, device_id)
+}
+
+/// Deletes the messages of a device up to and including `message_id`, see
+/// [`OpenClient::delete_messages_up_to()`](struct.OpenClient.html#method.delete_messages_up_to).
+///
+/// ```rust,no_run
+/// use pushover_api::open_client;
+///
+/// let messages =
+///   open_client::download_messages("user secret", "device id").unwrap();
+/// if let Some(last) = messages.last() {
+///   open_client::delete_messages_up_to("user secret", "device id", last.id)
+///     .unwrap();
+/// }
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn delete_messages_up_to(
+  secret: &str,
+  device_id: &str,
+  message_id: u64,
+) -> Result<()> {
+  OpenClient::with_transport(http_client()?)
+    .delete_messages_up_to(secret, device_id, message_id)
+}
+
+/// Acknowledges an emergency priority message, see
+/// [`OpenClient::acknowledge()`](struct.OpenClient.html#method.acknowledge).
+///
+/// ```rust,no_run
+/// use pushover_api::{open_client, ApiErrorKind};
+///
+/// let messages =
+///   open_client::download_messages("user secret", "device id").unwrap();
+/// for receipt in messages.iter().filter_map(|message| message.receipt.as_ref())
+/// {
+///   match open_client::acknowledge("user secret", receipt) {
+///     Ok(()) => println!("Acknowledged"),
+///     Err(error)
+///       if error.api_errors().iter().any(|error| {
+///         error.kind() == ApiErrorKind::AlreadyAcknowledged
+///       }) =>
+///     {
+///       println!("Already acknowledged elsewhere")
+///     }
+///     Err(error) => eprintln!("{}", error),
+///   }
+/// }
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn acknowledge(secret: &str, receipt: &Receipt) -> Result<()> {
+  OpenClient::with_transport(http_client()?).acknowledge(secret, receipt)
+}
+
+#[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+/// The URL of the Open Client WebSocket server.
+pub const PUSHOVER_WEBSOCKET: &str = "wss://client.pushover.net/push";
+
+#[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+/// The environment variable that overrides the URL of the Open Client
+/// WebSocket server, for example to point at a mock server in integration
+/// tests.
+pub const PUSHOVER_WEBSOCKET_URL_VAR: &str = "PUSHOVER_WEBSOCKET_URL";
+
+/// An event received from the Open Client WebSocket server, see
+/// [`listen()`](fn.listen.html).
+#[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ClientEvent {
+  /// Nothing happened, the server sends this regularly to keep the
+  /// connection open.
+  KeepAlive,
+  /// A new message arrived, to download with
+  /// [`download_messages()`](fn.download_messages.html).
+  NewMessage,
+  /// The client should disconnect and connect again, like when the server
+  /// restarts.
+  Reload,
+  /// A permanent error, like the device being removed. The client shouldn't
+  /// connect again without the user logging in again.
+  Error,
+  /// The device logged in from another session, which closed this one.
+  SessionClosed,
+  /// A frame this library doesn't know about.
+  Other(u8),
+}
+
+#[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+impl ClientEvent {
+  /// Converts the single byte of a frame into an event.
+  fn from_frame(frame: u8) -> Self {
+    match frame {
+      b'#' => ClientEvent::KeepAlive,
+      b'!' => ClientEvent::NewMessage,
+      b'R' => ClientEvent::Reload,
+      b'E' => ClientEvent::Error,
+      b'A' => ClientEvent::SessionClosed,
+      other => ClientEvent::Other(other),
+    }
+  }
+}
+
+/// Connects to the Open Client WebSocket server and logs in as a device,
+/// returning a stream of the events the server sends.
+///
+/// The stream ends when the server closes the connection, which it does
+/// after [`Error`](enum.ClientEvent.html#variant.Error) and
+/// [`SessionClosed`](enum.ClientEvent.html#variant.SessionClosed). After
+/// [`Reload`](enum.ClientEvent.html#variant.Reload) the client should drop
+/// the stream and listen again. Errors of the connection are returned as
+/// [`WebSocket`](../enum.PushoverError.html#variant.WebSocket).
+///
+/// It needs the `websocket` feature and a Tokio runtime.
+///
+/// ```rust
+/// # #[cfg(feature = "websocket")]
+/// # #[tokio::main]
+/// # async fn main() {
+/// # use futures::SinkExt;
+/// # use tokio_tungstenite::tungstenite::Message;
+/// # let mut listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+/// #   .await
+/// #   .unwrap();
+/// # std::env::set_var(
+/// #   pushover_api::open_client::PUSHOVER_WEBSOCKET_URL_VAR,
+/// #   format!("ws://{}/push", listener.local_addr().unwrap()),
+/// # );
+/// # // Checks the login and sends every kind of frame, then closes.
+/// # let server = tokio::spawn(async move {
+/// #   let (stream, _) = listener.accept().await.unwrap();
+/// #   let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+/// #   let login = socket.next().await.unwrap().unwrap();
+/// #   assert_eq!(login.into_text().unwrap(), "login:device id:user secret\n");
+/// #   for frame in b"#!RAE?" {
+/// #     socket.send(Message::Binary(vec![*frame])).await.unwrap();
+/// #   }
+/// #   socket.close(None).await.unwrap();
+/// # });
+/// use futures::StreamExt;
+/// use pushover_api::open_client::{self, ClientEvent};
+///
+/// let events = open_client::listen("user secret", "device id")
+///   .await
+///   .unwrap()
+///   .map(Result::unwrap)
+///   .collect::<Vec<_>>()
+///   .await;
+///
+/// assert_eq!(
+///   events,
+///   vec![
+///     ClientEvent::KeepAlive,
+///     ClientEvent::NewMessage,
+///     ClientEvent::Reload,
+///     ClientEvent::SessionClosed,
+///     ClientEvent::Error,
+///     ClientEvent::Other(b'?'),
+///   ]
+/// );
+/// # server.await.unwrap();
+/// # }
+/// # #[cfg(not(feature = "websocket"))]
+/// # fn main() {}
+/// ```
+#[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+pub async fn listen(
+  secret: &str,
+  device_id: &str,
+) -> Result<impl Stream<Item = Result<ClientEvent>> + Unpin> {
+  let url = std::env::var(PUSHOVER_WEBSOCKET_URL_VAR)
+    .ok()
+    .filter(|url| url.starts_with("ws://") || url.starts_with("wss://"))
+    .unwrap_or_else(|| PUSHOVER_WEBSOCKET.to_string());
+
+  let (mut socket, _) = tokio_tungstenite::connect_async(url.as_str())
+    .await
+    .map_err(websocket_error)?;
+  socket
+    .send(tungstenite::Message::Text(format!(
+      "login:{}:{}\n",
+      device_id, secret
+    )))
+    .await
+    .map_err(websocket_error)?;
+
+  let events = socket.flat_map(|frame| {
+    let events = match frame {
+      Ok(tungstenite::Message::Binary(frame)) => frame
+        .into_iter()
+        .map(|frame| Ok(ClientEvent::from_frame(frame)))
+        .collect(),
+      Ok(tungstenite::Message::Text(frame)) => frame
+        .bytes()
+        .map(|frame| Ok(ClientEvent::from_frame(frame)))
+        .collect(),
+      Ok(_) => vec![],
+      Err(error) => vec![Err(websocket_error(error))],
+    };
+    stream::iter(events)
+  });
+
+  Ok(events)
+}
+
+#[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+/// Wraps an error of the WebSocket connection.
+fn websocket_error(error: tungstenite::Error) -> PushoverError {
+  PushoverError::WebSocket(Box::new(error))
+}
@@ -0,0 +1,1027 @@
+//! Emergency priority message receipts.
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+use crate::{
+  api, api_base, encode_path_segment, http_client, join_url, HttpBody,
+  HttpMethod, HttpRequest, Message, MessageResponse, PushoverClient,
+  PushoverError, ResponseExtras, Transport,
+};
+use crate::{epoch, int_bool, non_empty, Receipt, RequestId, Result};
+use serde::Deserialize;
+use std::time::Duration;
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+use std::{
+  sync::atomic::{AtomicBool, Ordering},
+  thread,
+  time::Instant,
+};
+
+/// The minimum time between polls of a receipt, as recommended by Pushover.
+pub const MIN_RECEIPT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The status of an emergency priority message's receipt, as returned by
+/// [`get_receipt()`](fn.get_receipt.html).
+///
+/// Like in [`ReceiptCallback`](struct.ReceiptCallback.html), its flags are
+/// converted into booleans and any timestamps that are missing or `0` are
+/// `None`.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct ReceiptStatus {
+  /// Whether the message has been acknowledged.
+  #[serde(default, deserialize_with = "int_bool::deserialize_flag")]
+  pub acknowledged: bool,
+  /// The Unix timestamp of when the message was acknowledged.
+  #[serde(default, deserialize_with = "epoch::deserialize")]
+  pub acknowledged_at: Option<i64>,
+  /// The key of the user that acknowledged the message.
+  #[serde(default, deserialize_with = "non_empty::deserialize")]
+  pub acknowledged_by: Option<String>,
+  /// The name of the device the message was acknowledged on.
+  #[serde(default, deserialize_with = "non_empty::deserialize")]
+  pub acknowledged_by_device: Option<String>,
+  /// The Unix timestamp of when the message was last delivered.
+  #[serde(default, deserialize_with = "epoch::deserialize")]
+  pub last_delivered_at: Option<i64>,
+  /// Whether the message has expired.
+  #[serde(default, deserialize_with = "int_bool::deserialize_flag")]
+  pub expired: bool,
+  /// The Unix timestamp of when the message expires, or expired.
+  #[serde(default, deserialize_with = "epoch::deserialize")]
+  pub expires_at: Option<i64>,
+  /// Whether the message's callback URL has been called.
+  #[serde(default, deserialize_with = "int_bool::deserialize_flag")]
+  pub called_back: bool,
+  /// The Unix timestamp of when the callback URL was called.
+  #[serde(default, deserialize_with = "epoch::deserialize")]
+  pub called_back_at: Option<i64>,
+}
+
+#[cfg(feature = "chrono")]
+impl ReceiptStatus {
+  /// Returns when the message was acknowledged as a chrono
+  /// [`DateTime`](https://docs.rs/chrono/0.4/chrono/struct.DateTime.html).
+  ///
+  /// Like the other `_datetime` accessors, it's `None` when the timestamp is
+  /// missing, `0` or out of chrono's range.
+  ///
+  /// ```rust
+  /// use pushover_api::ReceiptStatus;
+  ///
+  /// let status = ReceiptStatus {
+  ///   acknowledged: true,
+  ///   acknowledged_at: Some(1593892465),
+  ///   expires_at: Some(32503680000),
+  ///   last_delivered_at: Some(0),
+  ///   called_back_at: Some(i64::MAX),
+  ///   ..ReceiptStatus::default()
+  /// };
+  ///
+  /// assert_eq!(
+  ///   status.acknowledged_at_datetime().unwrap().to_rfc3339(),
+  ///   "2020-07-04T19:54:25+00:00"
+  /// );
+  /// assert_eq!(
+  ///   status.expires_at_datetime().unwrap().to_rfc3339(),
+  ///   "3000-01-01T00:00:00+00:00"
+  /// );
+  /// assert_eq!(status.last_delivered_at_datetime(), None);
+  /// assert_eq!(status.called_back_at_datetime(), None);
+  /// assert_eq!(ReceiptStatus::default().acknowledged_at_datetime(), None);
+  /// ```
+  pub fn acknowledged_at_datetime(
+    &self,
+  ) -> Option<chrono::DateTime<chrono::Utc>> {
+    self.acknowledged_at.and_then(epoch::to_datetime)
+  }
+
+  /// Returns when the message was last delivered as a chrono `DateTime`.
+  pub fn last_delivered_at_datetime(
+    &self,
+  ) -> Option<chrono::DateTime<chrono::Utc>> {
+    self.last_delivered_at.and_then(epoch::to_datetime)
+  }
+
+  /// Returns when the message expires, or expired, as a chrono `DateTime`.
+  pub fn expires_at_datetime(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+    self.expires_at.and_then(epoch::to_datetime)
+  }
+
+  /// Returns when the callback URL was called as a chrono `DateTime`.
+  pub fn called_back_at_datetime(
+    &self,
+  ) -> Option<chrono::DateTime<chrono::Utc>> {
+    self.called_back_at.and_then(epoch::to_datetime)
+  }
+}
+
+/// Gets the status of an emergency priority message's receipt.
+///
+/// Pushover asks to not poll a receipt more often than every
+/// [`MIN_RECEIPT_POLL_INTERVAL`](constant.MIN_RECEIPT_POLL_INTERVAL.html).
+///
+/// ```rust,no_run
+/// use pushover_api::Receipt;
+///
+/// let receipt = Receipt::from("rLqVuqTRh62UzxtmqiaLzQmVcPgiCy");
+/// let status =
+///   pushover_api::get_receipt("application token", &receipt).unwrap();
+///
+/// if status.acknowledged {
+///   println!("Acknowledged by {:?}", status.acknowledged_by);
+/// }
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn get_receipt(token: &str, receipt: &Receipt) -> Result<ReceiptStatus> {
+  get_receipt_using(&http_client()?, &api_base(), token, receipt)
+}
+
+/// Gets the status of a receipt with the given transport and base URL.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+fn get_receipt_using(
+  transport: &impl Transport,
+  base: &str,
+  token: &str,
+  receipt: &Receipt,
+) -> Result<ReceiptStatus> {
+  let query = serde_urlencoded::to_string([("token", token)])?;
+  let request = HttpRequest::new(
+    HttpMethod::Get,
+    format!("{}?{}", join_url(base, &receipt.status_path()), query),
+  );
+
+  Ok(api::execute(transport, request)?.response.payload)
+}
+
+/// Cancels the retries of an emergency priority message, so its recipients
+/// stop being notified before it expires.
+///
+/// A receipt that doesn't exist or has already expired is returned as an
+/// [`Api`](enum.PushoverError.html#variant.Api) error, whose
+/// [`ApiError::kind()`](struct.ApiError.html#method.kind) is
+/// [`ReceiptNotFound`](enum.ApiErrorKind.html#variant.ReceiptNotFound).
+///
+/// ```rust,no_run
+/// use pushover_api::Receipt;
+///
+/// let receipt = Receipt::from("rLqVuqTRh62UzxtmqiaLzQmVcPgiCy");
+/// pushover_api::cancel_receipt("application token", &receipt).unwrap();
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn cancel_receipt(token: &str, receipt: &Receipt) -> Result<()> {
+  cancel_receipt_using(&http_client()?, &api_base(), token, receipt)
+}
+
+/// Cancels the retries of a receipt with the given transport and base URL.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+fn cancel_receipt_using(
+  transport: &impl Transport,
+  base: &str,
+  token: &str,
+  receipt: &Receipt,
+) -> Result<()> {
+  let request =
+    HttpRequest::new(HttpMethod::Post, join_url(base, &receipt.cancel_path()))
+      .with_body(HttpBody::Form(vec![(
+        "token".to_string(),
+        token.to_string(),
+      )]));
+
+  api::execute::<ResponseExtras>(transport, request)?;
+  Ok(())
+}
+
+/// Polls a receipt until its message is acknowledged, it expires, `timeout`
+/// elapses or `cancel` is set, checking it every `interval`.
+///
+/// The interval is raised to
+/// [`MIN_RECEIPT_POLL_INTERVAL`](constant.MIN_RECEIPT_POLL_INTERVAL.html) if
+/// it's any shorter. The cancel flag is checked while sleeping too, so
+/// setting it from another thread or a signal handler stops polling within a
+/// fraction of a second. Errors of the status requests are returned right
+/// away.
+///
+/// ```rust,no_run
+/// use pushover_api::{AckOutcome, Receipt};
+/// use std::{sync::atomic::AtomicBool, time::Duration};
+///
+/// let receipt = Receipt::from("rLqVuqTRh62UzxtmqiaLzQmVcPgiCy");
+/// let cancel = AtomicBool::new(false);
+/// let outcome = pushover_api::poll_receipt_until_done(
+///   "application token",
+///   &receipt,
+///   Duration::from_secs(30),
+///   Duration::from_secs(600),
+///   &cancel,
+/// )
+/// .unwrap();
+///
+/// match outcome {
+///   AckOutcome::Acknowledged { acknowledged_by, .. } => {
+///     println!("Acknowledged by {}", acknowledged_by)
+///   }
+///   _ => eprintln!("Nobody responded"),
+/// }
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn poll_receipt_until_done(
+  token: &str,
+  receipt: &Receipt,
+  interval: Duration,
+  timeout: Duration,
+  cancel: &AtomicBool,
+) -> Result<AckOutcome> {
+  poll_receipt_using(
+    &http_client()?,
+    &api_base(),
+    token,
+    receipt,
+    &PollSchedule::new(interval, timeout),
+    cancel,
+  )
+}
+
+/// How often to poll a receipt, when to give up and how to wait between the
+/// polls.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+struct PollSchedule<'a> {
+  /// The time between two polls.
+  interval: Duration,
+  /// How long to keep polling.
+  timeout: Duration,
+  /// Waits for the given time unless the cancel flag is set, which tests
+  /// replace so they don't have to actually wait.
+  sleep: &'a dyn Fn(Duration, &AtomicBool),
+}
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+impl PollSchedule<'static> {
+  /// Polls every `interval`, raised to
+  /// [`MIN_RECEIPT_POLL_INTERVAL`](constant.MIN_RECEIPT_POLL_INTERVAL.html),
+  /// until `timeout` elapses.
+  fn new(interval: Duration, timeout: Duration) -> Self {
+    Self {
+      interval: interval.max(MIN_RECEIPT_POLL_INTERVAL),
+      timeout,
+      sleep: &sleep_unless_cancelled,
+    }
+  }
+}
+
+/// Polls a receipt with the given transport and base URL.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+fn poll_receipt_using(
+  transport: &impl Transport,
+  base: &str,
+  token: &str,
+  receipt: &Receipt,
+  schedule: &PollSchedule<'_>,
+  cancel: &AtomicBool,
+) -> Result<AckOutcome> {
+  let receipt = receipt.clone();
+  let started = Instant::now();
+
+  loop {
+    if cancel.load(Ordering::SeqCst) {
+      return Ok(AckOutcome::Cancelled { receipt });
+    }
+
+    let status = get_receipt_using(transport, base, token, &receipt)?;
+    if status.acknowledged {
+      return Ok(AckOutcome::Acknowledged {
+        receipt,
+        acknowledged_by: status.acknowledged_by.unwrap_or_default(),
+        acknowledged_by_device: status
+          .acknowledged_by_device
+          .unwrap_or_default(),
+        acknowledged_at: status.acknowledged_at.unwrap_or_default(),
+      });
+    }
+
+    if status.expired {
+      return Ok(AckOutcome::Expired {
+        receipt,
+        expires_at: status.expires_at.unwrap_or_default(),
+      });
+    }
+
+    if started.elapsed() + schedule.interval > schedule.timeout {
+      return Ok(AckOutcome::TimedOut { receipt });
+    }
+
+    (schedule.sleep)(schedule.interval, cancel);
+  }
+}
+
+/// Sleeps for `duration` in short steps, returning early when `cancel` is set.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+fn sleep_unless_cancelled(duration: Duration, cancel: &AtomicBool) {
+  let step = Duration::from_millis(100);
+  let until = Instant::now() + duration;
+
+  while !cancel.load(Ordering::SeqCst) {
+    let now = Instant::now();
+    if now >= until {
+      break;
+    }
+
+    thread::sleep(step.min(until - now));
+  }
+}
+
+/// The response to cancelling the retries of emergency priority messages by
+/// their tag, as returned by [`cancel_by_tag()`](fn.cancel_by_tag.html).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CancelByTagResponse {
+  /// The number of messages whose retries were cancelled, when the Pushover
+  /// API reports it.
+  pub canceled: Option<u32>,
+  /// The identifier of the request.
+  pub request: RequestId,
+}
+
+/// The keys of the Pushover API's response to cancelling by tag.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+#[derive(Debug, Deserialize)]
+struct CancelByTagPayload {
+  #[serde(default)]
+  canceled: Option<u32>,
+}
+
+/// Cancels the retries of all emergency priority messages that were sent with
+/// the tag in their [`tags`](struct.Message.html#structfield.tags) and are
+/// still being retried.
+///
+/// ```rust,no_run
+/// let response =
+///   pushover_api::cancel_by_tag("application token", "outage").unwrap();
+///
+/// println!("Cancelled {:?} messages", response.canceled);
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn cancel_by_tag(token: &str, tag: &str) -> Result<CancelByTagResponse> {
+  cancel_by_tag_using(&http_client()?, &api_base(), token, tag)
+}
+
+/// Cancels the retries of messages by tag with the given transport and base
+/// URL.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+fn cancel_by_tag_using(
+  transport: &impl Transport,
+  base: &str,
+  token: &str,
+  tag: &str,
+) -> Result<CancelByTagResponse> {
+  if tag.is_empty() {
+    return Err(PushoverError::InvalidArgument(
+      "The tag can't be empty".to_string(),
+    ));
+  }
+
+  let path =
+    format!("receipts/cancel_by_tag/{}.json", encode_path_segment(tag));
+  let request =
+    HttpRequest::new(HttpMethod::Post, join_url(base, &path)).with_body(
+      HttpBody::Form(vec![("token".to_string(), token.to_string())]),
+    );
+
+  let reply = api::execute::<CancelByTagPayload>(transport, request)?;
+  Ok(CancelByTagResponse {
+    canceled: reply.response.payload.canceled,
+    request: reply.response.request.unwrap_or_default(),
+  })
+}
+
+/// The payload Pushover sends to an emergency priority message's
+/// [`callback`](struct.Message.html#structfield.callback) URL when it's
+/// acknowledged.
+///
+/// Its flags are converted into booleans and any timestamps that are missing
+/// or `0` are `None`.
+///
+/// ```rust
+/// use pushover_api::ReceiptCallback;
+///
+/// let body = b"receipt=rLqVuqTRh62UzxtmqiaLzQmVcPgiCy\
+///   &acknowledged=1&acknowledged_at=1593892465\
+///   &acknowledged_by=uQiRzpo4DXghDmr9QzzfQu27cmVRsG\
+///   &acknowledged_by_device=iphone&last_delivered_at=1593892362\
+///   &expired=0&expires_at=1593895962&called_back_at=0";
+///
+/// let callback = ReceiptCallback::from_form_bytes(body).unwrap();
+/// assert!(callback.acknowledged);
+/// assert!(!callback.expired);
+/// assert_eq!(callback.acknowledged_at, Some(1593892465));
+/// assert_eq!(callback.called_back_at, None);
+///
+/// let partial = b"receipt=rLqVuqTRh62UzxtmqiaLzQmVcPgiCy&acknowledged=0";
+/// let callback = ReceiptCallback::from_form_bytes(partial).unwrap();
+/// assert!(!callback.acknowledged);
+/// assert_eq!(callback.acknowledged_by, None);
+/// ```
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+pub struct ReceiptCallback {
+  /// The receipt of the message.
+  pub receipt: Receipt,
+  /// Whether the message has been acknowledged.
+  #[serde(default, deserialize_with = "int_bool::deserialize_flag")]
+  pub acknowledged: bool,
+  /// The Unix timestamp of when the message was acknowledged.
+  #[serde(default, deserialize_with = "epoch::deserialize")]
+  pub acknowledged_at: Option<i64>,
+  /// The key of the user that acknowledged the message.
+  #[serde(default)]
+  pub acknowledged_by: Option<String>,
+  /// The name of the device the message was acknowledged on.
+  #[serde(default)]
+  pub acknowledged_by_device: Option<String>,
+  /// The Unix timestamp of when the message was last delivered.
+  #[serde(default, deserialize_with = "epoch::deserialize")]
+  pub last_delivered_at: Option<i64>,
+  /// Whether the message has expired.
+  #[serde(default, deserialize_with = "int_bool::deserialize_flag")]
+  pub expired: bool,
+  /// The Unix timestamp of when the message expires.
+  #[serde(default, deserialize_with = "epoch::deserialize")]
+  pub expires_at: Option<i64>,
+  /// The Unix timestamp of when the callback URL was called.
+  #[serde(default, deserialize_with = "epoch::deserialize")]
+  pub called_back_at: Option<i64>,
+}
+
+impl ReceiptCallback {
+  /// Parses the `application/x-www-form-urlencoded` body of a callback
+  /// request.
+  pub fn from_form_bytes(body: &[u8]) -> Result<Self> {
+    serde_urlencoded::from_bytes(body).map_err(Into::into)
+  }
+}
+
+/// How waiting for an emergency priority message to be acknowledged ended, as
+/// returned by [`poll_receipt_until_done()`](fn.poll_receipt_until_done.html)
+/// and [`Message::send_and_wait_for_ack()`](struct.Message.html#method.send_and_wait_for_ack).
+///
+/// Every outcome includes the message's receipt, so retries can still be
+/// cancelled afterwards.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AckOutcome {
+  /// A user acknowledged the message.
+  Acknowledged {
+    /// The message's receipt.
+    receipt: Receipt,
+    /// The key of the user that acknowledged the message.
+    acknowledged_by: String,
+    /// The name of the device the message was acknowledged on.
+    acknowledged_by_device: String,
+    /// The Unix timestamp of when the message was acknowledged.
+    acknowledged_at: i64,
+  },
+  /// The message expired without being acknowledged.
+  Expired {
+    /// The message's receipt.
+    receipt: Receipt,
+    /// The Unix timestamp of when the message expired.
+    expires_at: i64,
+  },
+  /// The timeout elapsed before the message was acknowledged or expired.
+  TimedOut {
+    /// The message's receipt.
+    receipt: Receipt,
+  },
+  /// The cancel flag was set before the message was acknowledged or expired.
+  Cancelled {
+    /// The message's receipt.
+    receipt: Receipt,
+  },
+}
+
+impl AckOutcome {
+  /// Returns the receipt of the message.
+  pub fn receipt(&self) -> &Receipt {
+    match self {
+      AckOutcome::Acknowledged { receipt, .. }
+      | AckOutcome::Expired { receipt, .. }
+      | AckOutcome::TimedOut { receipt }
+      | AckOutcome::Cancelled { receipt } => receipt,
+    }
+  }
+}
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+impl Message {
+  /// Send this emergency priority message and block until it's acknowledged,
+  /// it expires or `timeout` elapses, checking its receipt every
+  /// `poll_interval`.
+  ///
+  /// The poll interval is raised to
+  /// [`MIN_RECEIPT_POLL_INTERVAL`](constant.MIN_RECEIPT_POLL_INTERVAL.html)
+  /// if it's any shorter.
+  ///
+  /// ```rust,no_run
+  /// use pushover_api::{AckOutcome, Message, MessagePriority};
+  /// use std::time::Duration;
+  ///
+  /// let outcome = Message {
+  ///   token: "application token".into(),
+  ///   user: "user key".into(),
+  ///   message: "The server is on fire!".to_string(),
+  ///   priority: Some(MessagePriority::Emergency),
+  ///   retry: Some(60),
+  ///   expire: Some(3600),
+  ///   ..Message::default()
+  /// }
+  /// .send_and_wait_for_ack(Duration::from_secs(30), Duration::from_secs(600))
+  /// .unwrap();
+  ///
+  /// if let AckOutcome::Acknowledged { acknowledged_by, .. } = outcome {
+  ///   println!("Acknowledged by {}", acknowledged_by);
+  /// }
+  /// ```
+  pub fn send_and_wait_for_ack(
+    &self,
+    poll_interval: Duration,
+    timeout: Duration,
+  ) -> Result<AckOutcome> {
+    let receipt = self.send()?.receipt.ok_or(PushoverError::MissingReceipt)?;
+
+    poll_receipt_until_done(
+      self.token.expose_secret(),
+      &receipt,
+      poll_interval,
+      timeout,
+      &AtomicBool::new(false),
+    )
+  }
+}
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+impl<T: Transport> PushoverClient<T> {
+  /// Gets the status of an emergency priority message's receipt, see
+  /// [`get_receipt()`](fn.get_receipt.html).
+  ///
+  /// ```rust
+  /// use pushover_api::{test_support::FakeTransport, PushoverClient, Receipt};
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"status":1,"acknowledged":0,"acknowledged_at":0,
+  ///     "acknowledged_by":"","acknowledged_by_device":"",
+  ///     "last_delivered_at":1593892362,"expired":0,"expires_at":1593895962,
+  ///     "called_back":0,"called_back_at":0,"request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"status":1,"acknowledged":1,"acknowledged_at":1593892465,
+  ///     "acknowledged_by":"uQiRzpo4DXghDmr9QzzfQu27cmVRsG",
+  ///     "acknowledged_by_device":"iphone","last_delivered_at":1593892362,
+  ///     "expired":0,"expires_at":1593895962,"called_back":1,
+  ///     "called_back_at":1593892466,"request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"status":1,"acknowledged":0,"acknowledged_at":0,
+  ///     "acknowledged_by":"","acknowledged_by_device":"",
+  ///     "last_delivered_at":1593895902,"expired":1,"expires_at":1593895962,
+  ///     "called_back":0,"called_back_at":0,"request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport);
+  /// let receipt = Receipt::from("rLqVuqTRh62UzxtmqiaLzQmVcPgiCy");
+  ///
+  /// let pending = client.get_receipt(&receipt).unwrap();
+  /// assert!(!pending.acknowledged && !pending.expired);
+  /// assert_eq!(pending.acknowledged_at, None);
+  /// assert_eq!(pending.acknowledged_by, None);
+  /// assert_eq!(pending.last_delivered_at, Some(1593892362));
+  /// assert_eq!(pending.called_back_at, None);
+  ///
+  /// let acknowledged = client.get_receipt(&receipt).unwrap();
+  /// assert!(acknowledged.acknowledged && acknowledged.called_back);
+  /// assert_eq!(acknowledged.acknowledged_at, Some(1593892465));
+  /// assert_eq!(acknowledged.acknowledged_by_device.as_deref(), Some("iphone"));
+  /// assert_eq!(acknowledged.called_back_at, Some(1593892466));
+  ///
+  /// let expired = client.get_receipt(&receipt).unwrap();
+  /// assert!(expired.expired && !expired.acknowledged);
+  /// assert_eq!(expired.expires_at, Some(1593895962));
+  ///
+  /// let request = &transport.requests()[0];
+  /// assert!(request.url.ends_with(
+  ///   "/1/receipts/rLqVuqTRh62UzxtmqiaLzQmVcPgiCy.json?token=application+token"
+  /// ));
+  /// ```
+  pub fn get_receipt(&self, receipt: &Receipt) -> Result<ReceiptStatus> {
+    get_receipt_using(&self.transport, &self.base_url, self.token(), receipt)
+  }
+
+  /// Polls a receipt until its message is acknowledged, it expires,
+  /// `timeout` elapses or `cancel` is set, see
+  /// [`poll_receipt_until_done()`](fn.poll_receipt_until_done.html).
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, AckOutcome, PushoverClient, Receipt,
+  /// };
+  /// use std::{
+  ///   sync::{
+  ///     atomic::{AtomicBool, Ordering},
+  ///     Arc,
+  ///   },
+  ///   thread,
+  ///   time::Duration,
+  /// };
+  ///
+  /// let pending = r#"{"status":1,"acknowledged":0,"expired":0,
+  ///   "expires_at":1593895962,"request":"5042853c"}"#;
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"status":1,"acknowledged":1,"acknowledged_at":1593892465,
+  ///     "acknowledged_by":"uQiRzpo4DXghDmr9QzzfQu27cmVRsG",
+  ///     "acknowledged_by_device":"iphone","expired":0,
+  ///     "expires_at":1593895962,"request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"status":1,"acknowledged":0,"expired":1,"expires_at":1593895962,
+  ///     "request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport);
+  /// let receipt = Receipt::from("rLqVuqTRh62UzxtmqiaLzQmVcPgiCy");
+  /// let never = AtomicBool::new(false);
+  ///
+  /// let outcome = client
+  ///   .poll_receipt_until_done(
+  ///     &receipt,
+  ///     Duration::from_millis(1),
+  ///     Duration::from_secs(60),
+  ///     &never,
+  ///   )
+  ///   .unwrap();
+  /// assert_eq!(
+  ///   outcome,
+  ///   AckOutcome::Acknowledged {
+  ///     receipt: receipt.clone(),
+  ///     acknowledged_by: "uQiRzpo4DXghDmr9QzzfQu27cmVRsG".to_string(),
+  ///     acknowledged_by_device: "iphone".to_string(),
+  ///     acknowledged_at: 1593892465,
+  ///   }
+  /// );
+  /// assert_eq!(transport.requests().len(), 1);
+  ///
+  /// let outcome = client
+  ///   .poll_receipt_until_done(
+  ///     &receipt,
+  ///     Duration::from_secs(30),
+  ///     Duration::from_secs(60),
+  ///     &never,
+  ///   )
+  ///   .unwrap();
+  /// assert!(matches!(
+  ///   outcome,
+  ///   AckOutcome::Expired {
+  ///     expires_at: 1593895962,
+  ///     ..
+  ///   }
+  /// ));
+  ///
+  /// // Setting the cancel flag from another thread stops the sleep early.
+  /// transport.push_json(200, pending);
+  /// let cancel = Arc::new(AtomicBool::new(false));
+  /// let setter = Arc::clone(&cancel);
+  /// thread::spawn(move || {
+  ///   thread::sleep(Duration::from_millis(50));
+  ///   setter.store(true, Ordering::SeqCst);
+  /// });
+  ///
+  /// let outcome = client
+  ///   .poll_receipt_until_done(
+  ///     &receipt,
+  ///     Duration::from_secs(30),
+  ///     Duration::from_secs(60),
+  ///     &cancel,
+  ///   )
+  ///   .unwrap();
+  /// assert_eq!(outcome, AckOutcome::Cancelled { receipt });
+  /// assert_eq!(transport.requests().len(), 3);
+  /// ```
+  pub fn poll_receipt_until_done(
+    &self,
+    receipt: &Receipt,
+    interval: Duration,
+    timeout: Duration,
+    cancel: &AtomicBool,
+  ) -> Result<AckOutcome> {
+    poll_receipt_using(
+      &self.transport,
+      &self.base_url,
+      self.token(),
+      receipt,
+      &PollSchedule::new(interval, timeout),
+      cancel,
+    )
+  }
+
+  /// Sends an emergency priority message and blocks until it's acknowledged,
+  /// it expires or `timeout` elapses, see
+  /// [`Message::send_and_wait_for_ack()`](struct.Message.html#method.send_and_wait_for_ack).
+  /// The message is sent like with [`send()`](#method.send).
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, AckOutcome, Message, MessagePriority,
+  ///   PushoverClient, PushoverError,
+  /// };
+  /// use std::time::Duration;
+  ///
+  /// let sent = r#"{"status":1,"request":"5042853c",
+  ///   "receipt":"rLqVuqTRh62UzxtmqiaLzQmVcPgiCy"}"#;
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(200, sent);
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"status":1,"acknowledged":1,"acknowledged_at":1593892465,
+  ///     "acknowledged_by":"uQiRzpo4DXghDmr9QzzfQu27cmVRsG",
+  ///     "acknowledged_by_device":"iphone","expired":0,
+  ///     "expires_at":1593895962,"request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(200, sent);
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"status":1,"acknowledged":0,"expired":1,"expires_at":1593895962,
+  ///     "request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853c"}"#);
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport)
+  ///   .default_user("user key");
+  /// let message = Message {
+  ///   message: "The server is on fire!".to_string(),
+  ///   priority: Some(MessagePriority::Emergency),
+  ///   retry: Some(60),
+  ///   expire: Some(3600),
+  ///   ..Message::default()
+  /// };
+  ///
+  /// let outcome = client
+  ///   .send_and_wait_for_ack(&message, Duration::ZERO, Duration::from_secs(60))
+  ///   .unwrap();
+  /// assert!(matches!(
+  ///   outcome,
+  ///   AckOutcome::Acknowledged { acknowledged_at: 1593892465, .. }
+  /// ));
+  ///
+  /// let outcome = client
+  ///   .send_and_wait_for_ack(&message, Duration::ZERO, Duration::from_secs(60))
+  ///   .unwrap();
+  /// assert_eq!(outcome.receipt().as_str(), "rLqVuqTRh62UzxtmqiaLzQmVcPgiCy");
+  /// assert!(matches!(outcome, AckOutcome::Expired { .. }));
+  ///
+  /// // A response without a receipt can't be waited for.
+  /// assert!(matches!(
+  ///   client.send_and_wait_for_ack(
+  ///     &message,
+  ///     Duration::ZERO,
+  ///     Duration::from_secs(60)
+  ///   ),
+  ///   Err(PushoverError::MissingReceipt)
+  /// ));
+  ///
+  /// let requests = transport.requests();
+  /// assert_eq!(requests.len(), 5);
+  /// assert_eq!(requests[0].field("user").as_deref(), Some("user key"));
+  /// assert!(requests[1]
+  ///   .url
+  ///   .contains("/1/receipts/rLqVuqTRh62UzxtmqiaLzQmVcPgiCy.json"));
+  /// ```
+  pub fn send_and_wait_for_ack(
+    &self,
+    message: &Message,
+    poll_interval: Duration,
+    timeout: Duration,
+  ) -> Result<AckOutcome> {
+    let message = self.prepare(message);
+    let receipt = self
+      .send(&message)?
+      .receipt
+      .ok_or(PushoverError::MissingReceipt)?;
+
+    poll_receipt_using(
+      &self.transport,
+      &self.base_url,
+      message.token.expose_secret(),
+      &receipt,
+      &PollSchedule::new(poll_interval, timeout),
+      &AtomicBool::new(false),
+    )
+  }
+
+  /// Cancels the retries of an emergency priority message, see
+  /// [`cancel_receipt()`](fn.cancel_receipt.html).
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, ApiErrorKind, PushoverClient, Receipt,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853c"}"#);
+  /// transport.push_json(
+  ///   404,
+  ///   r#"{"receipt":"not found",
+  ///     "errors":["receipt not found; may be invalid or expired"],
+  ///     "status":0,"request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport);
+  /// let receipt = Receipt::from("rLqVuqTRh62UzxtmqiaLzQmVcPgiCy");
+  ///
+  /// client.cancel_receipt(&receipt).unwrap();
+  ///
+  /// let error = client.cancel_receipt(&receipt).unwrap_err();
+  /// assert_eq!(error.api_errors()[0].kind(), ApiErrorKind::ReceiptNotFound);
+  /// assert!(!error.is_connect() && !error.is_timeout());
+  ///
+  /// let request = &transport.requests()[0];
+  /// assert!(request
+  ///   .url
+  ///   .ends_with("/1/receipts/rLqVuqTRh62UzxtmqiaLzQmVcPgiCy/cancel.json"));
+  /// assert_eq!(request.field("token").as_deref(), Some("application token"));
+  /// ```
+  pub fn cancel_receipt(&self, receipt: &Receipt) -> Result<()> {
+    cancel_receipt_using(&self.transport, &self.base_url, self.token(), receipt)
+  }
+
+  /// Cancels the retries of the emergency priority message a response is
+  /// for, see
+  /// [`MessageResponse::cancel_retries()`](struct.MessageResponse.html#method.cancel_retries).
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, Message, MessagePriority, PushoverClient,
+  ///   PushoverError,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"status":1,"request":"5042853c",
+  ///     "receipt":"rLqVuqTRh62UzxtmqiaLzQmVcPgiCy"}"#,
+  /// );
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853d"}"#);
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853e"}"#);
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport)
+  ///   .default_user("user key");
+  /// let response = client
+  ///   .send(&Message {
+  ///     message: "The server is on fire!".to_string(),
+  ///     priority: Some(MessagePriority::Emergency),
+  ///     retry: Some(60),
+  ///     expire: Some(3600),
+  ///     ..Message::default()
+  ///   })
+  ///   .unwrap();
+  /// client.cancel_retries(&response).unwrap();
+  ///
+  /// // Only emergency priority messages have a receipt.
+  /// let response = client.notify("The fire is out").unwrap();
+  /// assert!(matches!(
+  ///   client.cancel_retries(&response),
+  ///   Err(PushoverError::MissingReceipt)
+  /// ));
+  ///
+  /// let requests = transport.requests();
+  /// assert_eq!(requests.len(), 3);
+  /// assert!(requests[1]
+  ///   .url
+  ///   .ends_with("/1/receipts/rLqVuqTRh62UzxtmqiaLzQmVcPgiCy/cancel.json"));
+  /// assert_eq!(
+  ///   requests[1].field("token").as_deref(),
+  ///   Some("application token")
+  /// );
+  /// ```
+  pub fn cancel_retries(&self, response: &MessageResponse) -> Result<()> {
+    let receipt = response
+      .receipt
+      .as_ref()
+      .ok_or(PushoverError::MissingReceipt)?;
+    self.cancel_receipt(receipt)
+  }
+
+  /// Cancels the retries of all emergency priority messages sent with a tag,
+  /// see [`cancel_by_tag()`](fn.cancel_by_tag.html).
+  ///
+  /// ```rust
+  /// use pushover_api::{test_support::FakeTransport, PushoverClient};
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(200, r#"{"status":1,"canceled":2,"request":"5042853c"}"#);
+  /// transport.push_json(200, r#"{"status":1,"canceled":0,"request":"5042853d"}"#);
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport);
+  ///
+  /// let response = client.cancel_by_tag("db/primary #1 & more").unwrap();
+  /// assert_eq!(response.canceled, Some(2));
+  /// assert_eq!(response.request.as_str(), "5042853c");
+  ///
+  /// let response = client.cancel_by_tag("unknown").unwrap();
+  /// assert_eq!(response.canceled, Some(0));
+  ///
+  /// let requests = transport.requests();
+  /// assert!(requests[0].url.ends_with(
+  ///   "/1/receipts/cancel_by_tag/db%2Fprimary%20%231%20%26%20more.json"
+  /// ));
+  /// assert!(requests[1].url.ends_with("/1/receipts/cancel_by_tag/unknown.json"));
+  ///
+  /// assert!(client.cancel_by_tag("").is_err());
+  /// assert_eq!(transport.requests().len(), 2);
+  /// ```
+  pub fn cancel_by_tag(&self, tag: &str) -> Result<CancelByTagResponse> {
+    cancel_by_tag_using(&self.transport, &self.base_url, self.token(), tag)
+  }
+}
+
+#[cfg(all(test, any(feature = "blocking", feature = "ureq")))]
+mod tests {
+  use super::*;
+  use crate::test_support::FakeTransport;
+  use std::sync::Mutex;
+
+  const PENDING: &str = r#"{"status":1,"acknowledged":0,"expired":0,
+    "expires_at":1593895962,"request":"5042853c"}"#;
+
+  #[test]
+  fn polls_at_most_every_minimum_interval() {
+    let transport = FakeTransport::new();
+    transport.push_json(200, PENDING);
+    transport.push_json(200, PENDING);
+    transport.push_json(
+      200,
+      r#"{"status":1,"acknowledged":1,"acknowledged_at":1593892465,
+        "acknowledged_by":"uQiRzpo4DXghDmr9QzzfQu27cmVRsG",
+        "acknowledged_by_device":"iphone","expired":0,
+        "expires_at":1593895962,"request":"5042853c"}"#,
+    );
+
+    let sleeps = Mutex::new(vec![]);
+    let schedule = PollSchedule {
+      sleep: &|duration, _| sleeps.lock().unwrap().push(duration),
+      ..PollSchedule::new(Duration::from_millis(1), Duration::from_secs(60))
+    };
+
+    let receipt = Receipt::from("rLqVuqTRh62UzxtmqiaLzQmVcPgiCy");
+    let outcome = poll_receipt_using(
+      &transport,
+      "https://api.pushover.net/1",
+      "token",
+      &receipt,
+      &schedule,
+      &AtomicBool::new(false),
+    )
+    .unwrap();
+
+    assert_eq!(
+      outcome,
+      AckOutcome::Acknowledged {
+        receipt,
+        acknowledged_by: "uQiRzpo4DXghDmr9QzzfQu27cmVRsG".to_string(),
+        acknowledged_by_device: "iphone".to_string(),
+        acknowledged_at: 1593892465,
+      }
+    );
+    assert_eq!(transport.requests().len(), 3);
+    assert_eq!(
+      *sleeps.lock().unwrap(),
+      vec![MIN_RECEIPT_POLL_INTERVAL, MIN_RECEIPT_POLL_INTERVAL]
+    );
+  }
+
+  #[test]
+  fn stops_when_cancelled_while_waiting() {
+    let transport = FakeTransport::new();
+    transport.push_json(200, PENDING);
+
+    let schedule = PollSchedule {
+      sleep: &|_, cancel: &AtomicBool| cancel.store(true, Ordering::SeqCst),
+      ..PollSchedule::new(Duration::from_secs(30), Duration::from_secs(60))
+    };
+
+    let receipt = Receipt::from("rLqVuqTRh62UzxtmqiaLzQmVcPgiCy");
+    let outcome = poll_receipt_using(
+      &transport,
+      "https://api.pushover.net/1",
+      "token",
+      &receipt,
+      &schedule,
+      &AtomicBool::new(false),
+    )
+    .unwrap();
+
+    assert_eq!(outcome, AckOutcome::Cancelled { receipt });
+    assert_eq!(transport.requests().len(), 1);
+  }
+}
@@ -0,0 +1,216 @@
+//! Retrying messages that failed to send.
+
+use crate::{random_u64, PushoverError, Result};
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+use crate::{Message, MessageResponse};
+use std::time::Duration;
+
+/// How to retry sending a message when it fails because of a transient
+/// problem, with exponential backoff between the attempts.
+///
+/// Only connection errors, timeouts, 5xx responses and rate limiting are
+/// retried. Errors that would happen again, like validation errors or an
+/// invalid token, are returned right away. When the Pushover API responds
+/// with a `Retry-After` header, that delay is used instead, unless it's longer
+/// than [`max_delay`](#structfield.max_delay).
+///
+/// ```rust,no_run
+/// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+/// # {
+/// use pushover_api::{Message, RetryPolicy};
+/// use std::time::Duration;
+///
+/// let policy = RetryPolicy {
+///   max_attempts: 5,
+///   base_delay: Duration::from_millis(500),
+///   ..RetryPolicy::default()
+/// };
+///
+/// Message {
+///   token: "application token".into(),
+///   user: "user key".into(),
+///   message: "Message".to_string(),
+///   ..Message::default()
+/// }
+/// .send_with_retries(&policy)
+/// .unwrap();
+/// # }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetryPolicy {
+  /// The maximum number of attempts, including the first one.
+  pub max_attempts: u32,
+  /// The delay after the first failed attempt, which doubles after every
+  /// following attempt.
+  pub base_delay: Duration,
+  /// The maximum delay between two attempts. When the Pushover API asks to
+  /// wait longer than this with a `Retry-After` header, like when the monthly
+  /// limit has been reached, the
+  /// [`RateLimited`](enum.PushoverError.html#variant.RateLimited) error is
+  /// returned right away instead.
+  ///
+  /// ```rust
+  /// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+  /// # {
+  /// use http::StatusCode;
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, HttpResponse, PushoverClient, PushoverError,
+  ///   RetryPolicy,
+  /// };
+  /// use std::time::Duration;
+  ///
+  /// let rate_limited = |retry_after: &str| {
+  ///   Ok(
+  ///     HttpResponse::json(
+  ///       StatusCode::TOO_MANY_REQUESTS,
+  ///       r#"{"status":0,"errors":["over the limit"],"request":"1"}"#,
+  ///     )
+  ///     .with_header("Retry-After", retry_after),
+  ///   )
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// let client = PushoverClient::with_transport("token", &transport)
+  ///   .default_user("user key")
+  ///   .retry_policy(RetryPolicy {
+  ///     max_attempts: 3,
+  ///     max_delay: Duration::from_secs(30),
+  ///     ..RetryPolicy::default()
+  ///   });
+  ///
+  /// // A short delay is waited out, and the next attempt succeeds.
+  /// transport.push_response(rate_limited("0"));
+  /// client.notify("Hello").unwrap();
+  /// assert_eq!(transport.requests().len(), 2);
+  ///
+  /// // A delay longer than the maximum gives up after the first attempt.
+  /// transport.push_response(rate_limited("3600"));
+  /// match client.notify("Hello").unwrap_err() {
+  ///   PushoverError::RateLimited { retry_after, .. } => {
+  ///     assert_eq!(retry_after, Some(Duration::from_secs(3600)));
+  ///   }
+  ///   error => panic!("{}", error),
+  /// }
+  /// assert_eq!(transport.requests().len(), 3);
+  /// # }
+  /// ```
+  pub max_delay: Duration,
+  /// Whether to randomize the delays, so many clients failing at the same
+  /// time don't all retry at the same time too.
+  pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+  /// Three attempts, starting with a delay of 1 second up to 30 seconds, with
+  /// jitter.
+  fn default() -> Self {
+    Self {
+      max_attempts: 3,
+      base_delay: Duration::from_secs(1),
+      max_delay: Duration::from_secs(30),
+      jitter: true,
+    }
+  }
+}
+
+impl RetryPolicy {
+  /// A policy that only makes a single attempt.
+  pub fn never() -> Self {
+    Self {
+      max_attempts: 1,
+      ..Self::default()
+    }
+  }
+
+  /// Calls `attempt` until it succeeds, fails with an error that shouldn't be
+  /// retried or the attempts run out.
+  #[cfg(any(feature = "blocking", feature = "ureq"))]
+  pub(crate) fn run<T>(
+    &self,
+    mut attempt: impl FnMut() -> Result<T>,
+  ) -> Result<T> {
+    let mut attempts = 0;
+    loop {
+      attempts += 1;
+      let error = match attempt() {
+        Ok(value) => return Ok(value),
+        Err(error) => error,
+      };
+
+      let (delay, _) = self.next_attempt(attempts, error)?;
+      std::thread::sleep(delay);
+    }
+  }
+
+  /// Decides what to do after the given number of attempts failed with
+  /// `error`, returning the delay before the next attempt along with the
+  /// error, or the error to give up with.
+  pub(crate) fn next_attempt(
+    &self,
+    attempts: u32,
+    error: PushoverError,
+  ) -> Result<(Duration, PushoverError)> {
+    if !error.is_retryable() || (attempts >= self.max_attempts && attempts == 1)
+    {
+      return Err(error);
+    } else if attempts >= self.max_attempts {
+      return Err(PushoverError::RetriesExhausted {
+        attempts,
+        last_error: Box::new(error),
+      });
+    }
+
+    let delay = match error {
+      PushoverError::RateLimited {
+        retry_after: Some(retry_after),
+        ..
+      } if retry_after > self.max_delay => return Err(error),
+      PushoverError::RateLimited {
+        retry_after: Some(retry_after),
+        ..
+      } => retry_after,
+      _ => self.delay(attempts),
+    };
+
+    #[cfg(feature = "tracing")]
+    crate::trace::retry(attempts, delay, &error);
+    Ok((delay, error))
+  }
+
+  /// The delay after the given number of failed attempts.
+  fn delay(&self, attempts: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+    let delay = self
+      .base_delay
+      .checked_mul(factor)
+      .map_or(self.max_delay, |delay| delay.min(self.max_delay));
+
+    if self.jitter {
+      delay.mul_f64(random_fraction())
+    } else {
+      delay
+    }
+  }
+}
+
+/// A random number between 0 and 1, which doesn't need to be any good.
+fn random_fraction() -> f64 {
+  (random_u64() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+impl Message {
+  /// Send this message to the Pushover API, retrying according to `policy`
+  /// when it fails because of a transient problem.
+  ///
+  /// When all attempts fail, a
+  /// [`RetriesExhausted`](enum.PushoverError.html#variant.RetriesExhausted)
+  /// error with the number of attempts and the last error is returned. With
+  /// a single attempt, its error is returned as is.
+  pub fn send_with_retries(
+    &self,
+    policy: &RetryPolicy,
+  ) -> Result<MessageResponse> {
+    policy.run(|| self.send())
+  }
+}
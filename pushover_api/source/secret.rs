@@ -0,0 +1,73 @@
+//! Strings like tokens and keys that shouldn't end up in logs.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use zeroize::Zeroize;
+
+/// A string like an application token or user key that is redacted in its
+/// `Debug` output and wiped from memory when it's dropped. It's serialized as
+/// the plain string, so the Pushover API gets the real value.
+///
+/// ```rust
+/// use pushover_api::{Message, Secret};
+///
+/// let message = Message {
+///   token: "azGDORePK8gMaC0QOYAMyEEuzJnyUi".into(),
+///   user: Secret::from("uQiRzpo4DXghDmr9QzzfQu27cmVRsG".to_string()),
+///   message: "Message".to_string(),
+///   ..Message::default()
+/// };
+///
+/// let debug = format!("{:?}", message);
+/// assert!(!debug.contains("azGDORePK8gMaC0QOYAMyEEuzJnyUi"));
+/// assert!(!debug.contains("uQiRzpo4DXghDmr9QzzfQu27cmVRsG"));
+///
+/// assert_eq!(message.token.expose_secret(), "azGDORePK8gMaC0QOYAMyEEuzJnyUi");
+/// assert!(serde_json::to_string(&message)
+///   .unwrap()
+///   .contains(r#""token":"azGDORePK8gMaC0QOYAMyEEuzJnyUi""#));
+/// ```
+#[derive(Clone, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+  /// Creates a secret from a string.
+  pub fn new<S: Into<String>>(secret: S) -> Self {
+    Self(secret.into())
+  }
+
+  /// Returns the real value of the secret.
+  pub fn expose_secret(&self) -> &str {
+    &self.0
+  }
+
+  /// Whether the secret is an empty string.
+  pub fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+}
+
+impl fmt::Debug for Secret {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("\"[redacted]\"")
+  }
+}
+
+impl Drop for Secret {
+  fn drop(&mut self) {
+    self.0.zeroize();
+  }
+}
+
+impl From<String> for Secret {
+  fn from(secret: String) -> Self {
+    Self(secret)
+  }
+}
+
+impl From<&str> for Secret {
+  fn from(secret: &str) -> Self {
+    Self(secret.to_string())
+  }
+}
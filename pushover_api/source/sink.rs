@@ -0,0 +1,237 @@
+//! Sending a stream of messages through a `Sink`.
+
+use crate::{Message, MessageResponse, PushoverError, Result};
+use futures::{
+  stream::{FuturesOrdered, StreamExt},
+  task::{Context, Poll},
+  Future, Sink,
+};
+use reqwest::Client;
+use std::{fmt, pin::Pin};
+
+/// A message being sent.
+type InFlight = Pin<Box<dyn Future<Output = Result<MessageResponse>> + Send>>;
+
+/// The handler of a sink's results.
+type ResultHandler = Box<dyn FnMut(Result<MessageResponse>) + Send>;
+
+/// A [`Sink`](https://docs.rs/futures/0.3/futures/sink/trait.Sink.html) that
+/// sends the messages it's given with an asynchronous Reqwest client, so a
+/// stream of messages can be forwarded into it.
+///
+/// At most `max_in_flight` messages are sent at the same time, after that the
+/// sink isn't ready until one of them finishes. Flushing or closing the sink
+/// waits for all messages in flight.
+///
+/// By default the first message that fails to send makes the sink return its
+/// error. With [`on_result()`](#method.on_result) every result is passed to a
+/// handler instead, in the order the messages were given, and the sink keeps
+/// going.
+///
+/// It needs the `sink` feature and, like Reqwest, a Tokio runtime.
+///
+/// ```rust
+/// # #[cfg(feature = "sink")]
+/// # #[tokio::main]
+/// # async fn main() {
+/// use futures::{stream, StreamExt};
+/// use pushover_api::{Message, MessageSink};
+/// use std::sync::{Arc, Mutex};
+///
+/// # use pushover_api::{test_support::MockServer, HttpResponse};
+/// # use http::StatusCode;
+/// # // Answers with the number of the message as its request identifier, and
+/// # // fails message 4.
+/// # MockServer::with_handler(|request| {
+/// #   let message = request.json()["message"].as_str().unwrap().to_string();
+/// #   match message.trim_start_matches("Alert ") {
+/// #     "4" => HttpResponse::json(
+/// #       StatusCode::BAD_REQUEST,
+/// #       r#"{"status":0,"request":"4","errors":["invalid"]}"#,
+/// #     ),
+/// #     number => HttpResponse::json(
+/// #       StatusCode::OK,
+/// #       &format!(r#"{{"status":1,"request":"{}"}}"#, number),
+/// #     ),
+/// #   }
+/// # })
+/// # .set_api_url_var();
+/// let results = Arc::new(Mutex::new(vec![]));
+/// let record = results.clone();
+/// let sink = MessageSink::new(reqwest::Client::new(), 3).on_result(
+///   move |result| {
+///     record.lock().unwrap().push(match result {
+///       Ok(response) => response.request.as_str().to_string(),
+///       Err(_) => "failed".to_string(),
+///     })
+///   },
+/// );
+///
+/// stream::iter(1..=6)
+///   .map(|number| {
+///     Ok(Message {
+///       token: "application token".into(),
+///       user: "user key".into(),
+///       message: format!("Alert {}", number),
+///       ..Message::default()
+///     })
+///   })
+///   .forward(sink)
+///   .await
+///   .unwrap();
+///
+/// assert_eq!(
+///   *results.lock().unwrap(),
+///   vec!["1", "2", "3", "failed", "5", "6"]
+/// );
+/// # }
+/// # #[cfg(not(feature = "sink"))]
+/// # fn main() {}
+/// ```
+pub struct MessageSink {
+  client: Client,
+  max_in_flight: usize,
+  in_flight: FuturesOrdered<InFlight>,
+  on_result: Option<ResultHandler>,
+}
+
+impl MessageSink {
+  /// Creates a sink that sends at most `max_in_flight` messages at the same
+  /// time with `client`. A `max_in_flight` of `0` is treated as `1`.
+  ///
+  /// Without a result handler, forwarding into the sink stops with the first
+  /// error:
+  ///
+  /// ```rust
+  /// # #[cfg(feature = "sink")]
+  /// # #[tokio::main]
+  /// # async fn main() {
+  /// use futures::{stream, StreamExt};
+  /// use pushover_api::{Message, MessageSink};
+  ///
+  /// # std::env::set_var(pushover_api::PUSHOVER_API_URL_VAR, "http://127.0.0.1:1/1");
+  /// let messages = (1..=3).map(|number| {
+  ///   Ok(Message {
+  ///     token: "application token".into(),
+  ///     user: "user key".into(),
+  ///     message: format!("Alert {}", number),
+  ///     ..Message::default()
+  ///   })
+  /// });
+  ///
+  /// let error = stream::iter(messages)
+  ///   .forward(MessageSink::new(reqwest::Client::new(), 1))
+  ///   .await
+  ///   .unwrap_err();
+  /// assert!(error.is_connect());
+  /// # }
+  /// # #[cfg(not(feature = "sink"))]
+  /// # fn main() {}
+  /// ```
+  pub fn new(client: Client, max_in_flight: usize) -> Self {
+    Self {
+      client,
+      max_in_flight: max_in_flight.max(1),
+      in_flight: FuturesOrdered::new(),
+      on_result: None,
+    }
+  }
+
+  /// Passes the result of every message to `handler`, in the order the
+  /// messages were given, instead of returning the first error from the
+  /// sink.
+  pub fn on_result<F>(mut self, handler: F) -> Self
+  where
+    F: FnMut(Result<MessageResponse>) + Send + 'static,
+  {
+    self.on_result = Some(Box::new(handler));
+    self
+  }
+
+  /// The number of messages that are being sent.
+  pub fn in_flight(&self) -> usize {
+    self.in_flight.len()
+  }
+
+  /// Waits for the next message in flight to finish, returning its error
+  /// unless there's a result handler.
+  fn poll_next_result(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+    let result = match self.in_flight.poll_next_unpin(cx) {
+      Poll::Ready(Some(result)) => result,
+      Poll::Ready(None) => return Poll::Ready(Ok(())),
+      Poll::Pending => return Poll::Pending,
+    };
+
+    Poll::Ready(match &mut self.on_result {
+      Some(handler) => {
+        handler(result);
+        Ok(())
+      }
+      None => result.map(|_| ()),
+    })
+  }
+
+  /// Waits for all messages in flight to finish.
+  fn poll_all(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+    while !self.in_flight.is_empty() {
+      match self.poll_next_result(cx) {
+        Poll::Ready(Ok(())) => {}
+        other => return other,
+      }
+    }
+
+    Poll::Ready(Ok(()))
+  }
+}
+
+impl Sink<Message> for MessageSink {
+  type Error = PushoverError;
+
+  fn poll_ready(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<Result<()>> {
+    let this = self.get_mut();
+    while this.in_flight.len() >= this.max_in_flight {
+      match this.poll_next_result(cx) {
+        Poll::Ready(Ok(())) => {}
+        other => return other,
+      }
+    }
+
+    Poll::Ready(Ok(()))
+  }
+
+  fn start_send(self: Pin<&mut Self>, message: Message) -> Result<()> {
+    let this = self.get_mut();
+    let client = this.client.clone();
+    this.in_flight.push_back(Box::pin(async move {
+      message.send_async_with(&client).await
+    }));
+    Ok(())
+  }
+
+  fn poll_flush(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<Result<()>> {
+    self.get_mut().poll_all(cx)
+  }
+
+  fn poll_close(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<Result<()>> {
+    self.get_mut().poll_all(cx)
+  }
+}
+
+impl fmt::Debug for MessageSink {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.debug_struct("MessageSink")
+      .field("max_in_flight", &self.max_in_flight)
+      .field("in_flight", &self.in_flight.len())
+      .field("on_result", &self.on_result.is_some())
+      .finish()
+  }
+}
@@ -0,0 +1,541 @@
+//! The notification sounds.
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+use crate::{
+  api, api_base, http_client, join_url, HttpMethod, HttpRequest,
+  PushoverClient, Transport,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+  collections::BTreeMap, convert::Infallible, fmt, str::FromStr, time::Duration,
+};
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+use std::{
+  sync::{Arc, Mutex},
+  time::Instant,
+};
+
+/// The default time a client keeps the sounds cached, see
+/// [`PushoverClient::cache_sounds()`](struct.PushoverClient.html#method.cache_sounds).
+pub const DEFAULT_SOUNDS_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// The [notification sound](https://pushover.net/api#sounds) to play with a
+/// message.
+///
+/// Any sound that isn't built into Pushover, like sounds uploaded to your
+/// account, can be used with [`Sound::Custom`](#variant.Custom). Sounds can be
+/// created from their API identifier with `From<&str>` or `FromStr`.
+///
+/// ```rust
+/// use pushover_api::Sound;
+///
+/// assert_eq!(Sound::from("cashregister"), Sound::CashRegister);
+/// assert_eq!(Sound::from("my-sound"), Sound::Custom("my-sound".to_string()));
+/// assert_eq!(Sound::SpaceAlarm.to_string(), "spacealarm");
+///
+/// let sounds = vec![
+///   Sound::Pushover,
+///   Sound::Bike,
+///   Sound::Bugle,
+///   Sound::CashRegister,
+///   Sound::Classical,
+///   Sound::Cosmic,
+///   Sound::Falling,
+///   Sound::Gamelan,
+///   Sound::Incoming,
+///   Sound::Intermission,
+///   Sound::Magic,
+///   Sound::Mechanical,
+///   Sound::PianoBar,
+///   Sound::Siren,
+///   Sound::SpaceAlarm,
+///   Sound::Tugboat,
+///   Sound::Alien,
+///   Sound::Climb,
+///   Sound::Persistent,
+///   Sound::Echo,
+///   Sound::UpDown,
+///   Sound::Vibrate,
+///   Sound::None,
+///   Sound::Custom("my-sound".to_string()),
+/// ];
+/// for sound in sounds {
+///   let json = serde_json::to_string(&sound).unwrap();
+///   assert_eq!(json, format!("\"{}\"", sound.as_str()));
+///   assert_eq!(serde_json::from_str::<Sound>(&json).unwrap(), sound);
+///   assert_eq!(sound.to_string().parse::<Sound>().unwrap(), sound);
+///   assert_eq!(Sound::from(sound.as_str()), sound);
+/// }
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Sound {
+  /// Pushover (default).
+  Pushover,
+  /// Bike.
+  Bike,
+  /// Bugle.
+  Bugle,
+  /// Cash Register.
+  CashRegister,
+  /// Classical.
+  Classical,
+  /// Cosmic.
+  Cosmic,
+  /// Falling.
+  Falling,
+  /// Gamelan.
+  Gamelan,
+  /// Incoming.
+  Incoming,
+  /// Intermission.
+  Intermission,
+  /// Magic.
+  Magic,
+  /// Mechanical.
+  Mechanical,
+  /// Piano Bar.
+  PianoBar,
+  /// Siren.
+  Siren,
+  /// Space Alarm.
+  SpaceAlarm,
+  /// Tug Boat.
+  Tugboat,
+  /// Alien Alarm (long).
+  Alien,
+  /// Climb (long).
+  Climb,
+  /// Persistent (long).
+  Persistent,
+  /// Pushover Echo (long).
+  Echo,
+  /// Up Down (long).
+  UpDown,
+  /// Vibrate Only.
+  Vibrate,
+  /// None (silent).
+  None,
+  /// A custom sound, using its API identifier.
+  Custom(String),
+}
+
+impl Sound {
+  /// Returns the identifier of the sound used by the Pushover API.
+  pub fn as_str(&self) -> &str {
+    match self {
+      Sound::Pushover => "pushover",
+      Sound::Bike => "bike",
+      Sound::Bugle => "bugle",
+      Sound::CashRegister => "cashregister",
+      Sound::Classical => "classical",
+      Sound::Cosmic => "cosmic",
+      Sound::Falling => "falling",
+      Sound::Gamelan => "gamelan",
+      Sound::Incoming => "incoming",
+      Sound::Intermission => "intermission",
+      Sound::Magic => "magic",
+      Sound::Mechanical => "mechanical",
+      Sound::PianoBar => "pianobar",
+      Sound::Siren => "siren",
+      Sound::SpaceAlarm => "spacealarm",
+      Sound::Tugboat => "tugboat",
+      Sound::Alien => "alien",
+      Sound::Climb => "climb",
+      Sound::Persistent => "persistent",
+      Sound::Echo => "echo",
+      Sound::UpDown => "updown",
+      Sound::Vibrate => "vibrate",
+      Sound::None => "none",
+      Sound::Custom(sound) => sound,
+    }
+  }
+
+  /// Whether the sound is in a list of sounds from
+  /// [`list_sounds()`](fn.list_sounds.html), which includes the custom sounds
+  /// of the application.
+  ///
+  /// ```rust
+  /// use pushover_api::Sound;
+  /// use std::collections::BTreeMap;
+  ///
+  /// let mut sounds = BTreeMap::new();
+  /// sounds.insert("siren".to_string(), "Siren".to_string());
+  /// sounds.insert("my-sound".to_string(), "My Sound".to_string());
+  ///
+  /// assert!(Sound::Siren.is_available(&sounds));
+  /// assert!(Sound::from("my-sound").is_available(&sounds));
+  /// assert!(!Sound::Bugle.is_available(&sounds));
+  /// ```
+  pub fn is_available(&self, sounds: &BTreeMap<String, String>) -> bool {
+    sounds.contains_key(self.as_str())
+  }
+}
+
+impl From<&str> for Sound {
+  fn from(input: &str) -> Self {
+    match input {
+      "pushover" => Sound::Pushover,
+      "bike" => Sound::Bike,
+      "bugle" => Sound::Bugle,
+      "cashregister" => Sound::CashRegister,
+      "classical" => Sound::Classical,
+      "cosmic" => Sound::Cosmic,
+      "falling" => Sound::Falling,
+      "gamelan" => Sound::Gamelan,
+      "incoming" => Sound::Incoming,
+      "intermission" => Sound::Intermission,
+      "magic" => Sound::Magic,
+      "mechanical" => Sound::Mechanical,
+      "pianobar" => Sound::PianoBar,
+      "siren" => Sound::Siren,
+      "spacealarm" => Sound::SpaceAlarm,
+      "tugboat" => Sound::Tugboat,
+      "alien" => Sound::Alien,
+      "climb" => Sound::Climb,
+      "persistent" => Sound::Persistent,
+      "echo" => Sound::Echo,
+      "updown" => Sound::UpDown,
+      "vibrate" => Sound::Vibrate,
+      "none" => Sound::None,
+      custom => Sound::Custom(custom.to_string()),
+    }
+  }
+}
+
+impl From<String> for Sound {
+  fn from(input: String) -> Self {
+    Sound::from(input.as_str())
+  }
+}
+
+impl FromStr for Sound {
+  type Err = Infallible;
+
+  fn from_str(input: &str) -> Result<Self, Self::Err> {
+    Ok(Sound::from(input))
+  }
+}
+
+impl fmt::Display for Sound {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.as_str())
+  }
+}
+
+impl Serialize for Sound {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(self.as_str())
+  }
+}
+
+impl<'de> Deserialize<'de> for Sound {
+  fn deserialize<D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Self, D::Error> {
+    String::deserialize(deserializer).map(Sound::from)
+  }
+}
+
+/// The sounds of a `sounds.json` response.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+#[derive(Debug, Deserialize)]
+struct SoundsPayload {
+  sounds: BTreeMap<String, String>,
+}
+
+/// Gets the sounds an application can use, including its custom sounds, as a
+/// map from their identifier to their name.
+///
+/// ```rust,no_run
+/// use pushover_api::Sound;
+///
+/// let sounds = pushover_api::list_sounds("application token").unwrap();
+/// for (identifier, name) in &sounds {
+///   println!("{}: {}", identifier, name);
+/// }
+///
+/// assert!(Sound::Pushover.is_available(&sounds));
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn list_sounds(token: &str) -> crate::Result<BTreeMap<String, String>> {
+  list_sounds_using(&http_client()?, &api_base(), token)
+}
+
+/// Gets the sounds of an application with the given transport and base URL.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+fn list_sounds_using(
+  transport: &impl Transport,
+  base: &str,
+  token: &str,
+) -> crate::Result<BTreeMap<String, String>> {
+  let query = serde_urlencoded::to_string([("token", token)])?;
+  let request = HttpRequest::new(
+    HttpMethod::Get,
+    format!("{}?{}", join_url(base, "sounds.json"), query),
+  );
+
+  let payload: SoundsPayload =
+    api::execute(transport, request)?.response.payload;
+  Ok(payload.sounds)
+}
+
+/// Sounds shared between a cache and its callers.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+type SharedSounds = Arc<BTreeMap<String, String>>;
+
+/// The sounds of an application, cached by a client and shared between its
+/// clones.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+#[derive(Debug)]
+pub(crate) struct SoundsCache {
+  ttl: Duration,
+  sounds: Mutex<Option<(Instant, SharedSounds)>>,
+  /// Held while fetching, so concurrent callers wait for a single request
+  /// without locking the cached sounds during it.
+  fetching: Mutex<()>,
+}
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+impl SoundsCache {
+  /// Creates an empty cache that keeps the sounds for `ttl`.
+  pub(crate) fn new(ttl: Duration) -> Self {
+    Self {
+      ttl,
+      sounds: Mutex::new(None),
+      fetching: Mutex::new(()),
+    }
+  }
+
+  /// Returns the cached sounds, if they haven't expired yet.
+  fn cached(&self) -> Option<SharedSounds> {
+    match &*self.sounds.lock().unwrap() {
+      Some((fetched_at, sounds)) if fetched_at.elapsed() < self.ttl => {
+        Some(Arc::clone(sounds))
+      }
+      _ => None,
+    }
+  }
+
+  /// Caches newly fetched sounds.
+  fn store(&self, sounds: BTreeMap<String, String>) -> SharedSounds {
+    let sounds = Arc::new(sounds);
+    *self.sounds.lock().unwrap() = Some((Instant::now(), Arc::clone(&sounds)));
+    sounds
+  }
+
+  /// Returns the cached sounds, or fetches and caches them when they're
+  /// missing or expired.
+  fn get_or_fetch(
+    &self,
+    fetch: impl FnOnce() -> crate::Result<BTreeMap<String, String>>,
+  ) -> crate::Result<SharedSounds> {
+    if let Some(sounds) = self.cached() {
+      return Ok(sounds);
+    }
+
+    let _fetching = self.fetching.lock().unwrap();
+    // Another caller may have fetched them while this one was waiting.
+    if let Some(sounds) = self.cached() {
+      return Ok(sounds);
+    }
+
+    Ok(self.store(fetch()?))
+  }
+
+  /// Fetches and caches the sounds, whether they're cached already or not.
+  fn refresh(
+    &self,
+    fetch: impl FnOnce() -> crate::Result<BTreeMap<String, String>>,
+  ) -> crate::Result<SharedSounds> {
+    let _fetching = self.fetching.lock().unwrap();
+    Ok(self.store(fetch()?))
+  }
+}
+
+/// Returns the sounds without copying them when nothing else shares them.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+fn into_owned(sounds: SharedSounds) -> BTreeMap<String, String> {
+  Arc::try_unwrap(sounds).unwrap_or_else(|sounds| (*sounds).clone())
+}
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+impl<T: Transport> PushoverClient<T> {
+  /// Gets the sounds the application can use, including its custom sounds,
+  /// see [`list_sounds()`](fn.list_sounds.html).
+  ///
+  /// When the client [caches the sounds](#method.cache_sounds), they're only
+  /// requested again after they expire.
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, ApiErrorKind, PushoverClient, Sound,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"sounds":{"pushover":"Pushover (default)","bike":"Bike",
+  ///     "my-sound":"My Sound"},"status":1,"request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"token":"invalid","errors":["application token is invalid"],
+  ///     "status":0,"request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport);
+  ///
+  /// let sounds = client.list_sounds().unwrap();
+  /// assert_eq!(sounds.len(), 3);
+  /// assert_eq!(sounds["pushover"], "Pushover (default)");
+  /// assert!(Sound::from("my-sound").is_available(&sounds));
+  ///
+  /// let error = client.list_sounds().unwrap_err();
+  /// assert_eq!(error.api_errors()[0].kind(), ApiErrorKind::InvalidToken);
+  ///
+  /// let request = &transport.requests()[0];
+  /// assert!(request.url.ends_with("/1/sounds.json?token=application+token"));
+  /// ```
+  pub fn list_sounds(&self) -> crate::Result<BTreeMap<String, String>> {
+    self.sounds().map(into_owned)
+  }
+
+  /// Gets the sounds like [`list_sounds()`](#method.list_sounds), sharing
+  /// cached sounds instead of copying them.
+  fn sounds(&self) -> crate::Result<SharedSounds> {
+    let fetch =
+      || list_sounds_using(&self.transport, &self.base_url, self.token());
+    match &self.sounds_cache {
+      Some(cache) => cache.get_or_fetch(fetch),
+      None => fetch().map(Arc::new),
+    }
+  }
+
+  /// Gets the sounds the application can use like
+  /// [`list_sounds()`](#method.list_sounds), but always requests them and
+  /// replaces any cached ones.
+  ///
+  /// ```rust
+  /// use pushover_api::{test_support::FakeTransport, PushoverClient};
+  /// use std::time::Duration;
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"sounds":{"pushover":"Pushover (default)"},"status":1,
+  ///     "request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"sounds":{"pushover":"Pushover (default)","my-sound":"My Sound"},
+  ///     "status":1,"request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport)
+  ///   .cache_sounds(Duration::from_secs(60 * 60));
+  ///
+  /// assert_eq!(client.list_sounds().unwrap().len(), 1);
+  /// assert_eq!(client.refresh_sounds().unwrap().len(), 2);
+  /// assert_eq!(client.list_sounds().unwrap().len(), 2);
+  /// assert_eq!(transport.requests().len(), 2);
+  /// ```
+  pub fn refresh_sounds(&self) -> crate::Result<BTreeMap<String, String>> {
+    let fetch =
+      || list_sounds_using(&self.transport, &self.base_url, self.token());
+    match &self.sounds_cache {
+      Some(cache) => cache.refresh(fetch).map(into_owned),
+      None => fetch(),
+    }
+  }
+
+  /// Checks whether the application can use a sound, with the sounds from
+  /// [`list_sounds()`](#method.list_sounds) so a client that caches them
+  /// doesn't make a request for every check.
+  ///
+  /// This check is opt-in: sending and validating messages never requests
+  /// the sounds, so call this first to catch sounds the application can't
+  /// use.
+  ///
+  /// ```rust
+  /// use pushover_api::{test_support::FakeTransport, PushoverClient, Sound};
+  /// use std::{thread, time::Duration};
+  ///
+  /// let sounds = r#"{"sounds":{"pushover":"Pushover (default)",
+  ///   "my-sound":"My Sound"},"status":1,"request":"5042853c"}"#;
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(200, sounds);
+  /// transport.push_json(200, sounds);
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport)
+  ///   .cache_sounds(Duration::from_millis(200));
+  /// let clone = client.clone();
+  ///
+  /// // Within the TTL the client and its clones share a single request.
+  /// assert!(client.is_sound_available(&Sound::Pushover).unwrap());
+  /// assert!(clone.is_sound_available(&Sound::from("my-sound")).unwrap());
+  /// assert!(!client.is_sound_available(&Sound::Bugle).unwrap());
+  /// assert_eq!(transport.requests().len(), 1);
+  ///
+  /// // After it, the sounds are requested again.
+  /// thread::sleep(Duration::from_millis(300));
+  /// assert!(client.is_sound_available(&Sound::Pushover).unwrap());
+  /// assert_eq!(transport.requests().len(), 2);
+  /// ```
+  pub fn is_sound_available(&self, sound: &Sound) -> crate::Result<bool> {
+    Ok(sound.is_available(&*self.sounds()?))
+  }
+}
+
+#[cfg(all(test, any(feature = "blocking", feature = "ureq")))]
+mod tests {
+  use super::*;
+  use std::{sync::mpsc, thread};
+
+  fn sounds(names: &[&str]) -> BTreeMap<String, String> {
+    names
+      .iter()
+      .map(|name| (name.to_string(), name.to_string()))
+      .collect()
+  }
+
+  #[test]
+  fn shares_cached_sounds_while_refreshing() {
+    let cache = Arc::new(SoundsCache::new(Duration::from_secs(60)));
+    let cached = cache.get_or_fetch(|| Ok(sounds(&["pushover"]))).unwrap();
+    let again = cache.get_or_fetch(|| panic!("Fetched again")).unwrap();
+    assert!(Arc::ptr_eq(&cached, &again));
+
+    let (started, wait_for_start) = mpsc::channel();
+    let (finish, wait_for_finish) = mpsc::channel::<()>();
+    let refreshing = {
+      let cache = Arc::clone(&cache);
+      thread::spawn(move || {
+        cache.refresh(|| {
+          started.send(()).unwrap();
+          wait_for_finish.recv().unwrap();
+          Ok(sounds(&["pushover", "my-sound"]))
+        })
+      })
+    };
+
+    // The cached sounds stay readable during the slow request.
+    wait_for_start.recv().unwrap();
+    let during = cache.get_or_fetch(|| panic!("Fetched again")).unwrap();
+    assert_eq!(during.len(), 1);
+
+    finish.send(()).unwrap();
+    assert_eq!(refreshing.join().unwrap().unwrap().len(), 2);
+    let after = cache.get_or_fetch(|| panic!("Fetched again")).unwrap();
+    assert_eq!(after.len(), 2);
+  }
+
+  #[test]
+  fn fetches_expired_sounds_again() {
+    let cache = SoundsCache::new(Duration::from_millis(0));
+    cache.get_or_fetch(|| Ok(sounds(&["pushover"]))).unwrap();
+
+    let fetched = cache.get_or_fetch(|| Ok(sounds(&["bike"]))).unwrap();
+    assert!(fetched.contains_key("bike"));
+  }
+}
@@ -0,0 +1,227 @@
+//! Splitting messages that are too long into multiple parts.
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+use crate::{
+  truncate_message, Message, PushoverClient, Result, Transport,
+  MAX_MESSAGE_LENGTH, MAX_TITLE_LENGTH,
+};
+use crate::{MessageResponse, PushoverError};
+use std::fmt;
+
+/// Splits `text` into parts of at most `limit` characters, preferring to break
+/// at line breaks and then at whitespace. Whitespace around the breaks is
+/// removed.
+///
+/// A `limit` of 0 is treated as 1, since no text fits in a part of zero
+/// characters.
+///
+/// ```rust
+/// use pushover_api::split_message;
+///
+/// let parts = split_message("one two three\nfour five", 10);
+/// assert_eq!(parts, vec!["one two", "three", "four five"]);
+///
+/// // A line break in the window wins over a later space.
+/// let parts = split_message("one\ntwo three", 10);
+/// assert_eq!(parts, vec!["one", "two three"]);
+///
+/// // Without a line break, the last space in the window is used.
+/// let parts = split_message("one two three", 10);
+/// assert_eq!(parts, vec!["one two", "three"]);
+///
+/// // Characters are counted, not bytes.
+/// let parts = split_message("🦀🦀🦀🦀🦀", 2);
+/// assert_eq!(parts, vec!["🦀🦀", "🦀🦀", "🦀"]);
+/// let parts = split_message("héllo wörld", 6);
+/// assert_eq!(parts, vec!["héllo", "wörld"]);
+///
+/// assert_eq!(split_message("abc", 1), vec!["a", "b", "c"]);
+/// assert_eq!(split_message("abc", 0), vec!["a", "b", "c"]);
+/// assert_eq!(split_message("", 0), vec![""]);
+/// ```
+pub fn split_message(text: &str, limit: usize) -> Vec<String> {
+  let limit = limit.max(1);
+  let mut parts = vec![];
+  let mut rest = text;
+
+  while rest.chars().count() > limit {
+    // The byte index of the first character that doesn't fit, so slicing
+    // up to it never cuts through a multi-byte character.
+    let end = rest
+      .char_indices()
+      .nth(limit)
+      .map(|(index, _)| index)
+      .unwrap_or_else(|| rest.len());
+    let window = &rest[..end];
+
+    let split_at = window
+      .rfind('\n')
+      .or_else(|| window.rfind(char::is_whitespace))
+      .filter(|index| !window[..*index].trim().is_empty())
+      .unwrap_or(end);
+
+    parts.push(rest[..split_at].trim_end().to_string());
+    rest = rest[split_at..].trim_start();
+  }
+
+  if !rest.is_empty() || parts.is_empty() {
+    parts.push(rest.to_string());
+  }
+
+  parts
+}
+
+/// The error returned by
+/// [`Message::send_split()`](struct.Message.html#method.send_split) when one
+/// of the parts fails to send. Parts after the failed one aren't sent.
+#[derive(Debug)]
+pub struct SplitSendError {
+  /// The responses for the parts that were sent successfully.
+  pub responses: Vec<MessageResponse>,
+  /// The part that failed to send, starting at 1.
+  pub part: usize,
+  /// The total number of parts.
+  pub parts: usize,
+  /// The error for the part that failed.
+  pub error: PushoverError,
+}
+
+impl fmt::Display for SplitSendError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "Failed to send part {} of {}: {}",
+      self.part, self.parts, self.error
+    )
+  }
+}
+
+impl std::error::Error for SplitSendError {}
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+impl Message {
+  /// Send this message to the Pushover API, splitting it into multiple
+  /// messages when it's longer than
+  /// [`MAX_MESSAGE_LENGTH`](constant.MAX_MESSAGE_LENGTH.html).
+  ///
+  /// When the message is split, each part gets numbered in its title like
+  /// `Title (1/3)` and the parts are sent in order. The title is truncated
+  /// when needed so the numbered title still fits in
+  /// [`MAX_TITLE_LENGTH`](constant.MAX_TITLE_LENGTH.html). Any attachment is
+  /// only sent with the first part.
+  ///
+  /// ```rust,no_run
+  /// use pushover_api::Message;
+  ///
+  /// let responses = Message {
+  ///   token: "application token".into(),
+  ///   user: "user key".into(),
+  ///   message: std::fs::read_to_string("error.log").unwrap(),
+  ///   title: Some("Error log".to_string()),
+  ///   ..Message::default()
+  /// }
+  /// .send_split()
+  /// .unwrap();
+  /// ```
+  #[allow(clippy::result_large_err)]
+  pub fn send_split(
+    &self,
+  ) -> std::result::Result<Vec<MessageResponse>, SplitSendError> {
+    self.send_split_with(Message::send)
+  }
+
+  /// Splits this message like
+  /// [`send_split()`](#method.send_split), sending each part with `send`.
+  #[allow(clippy::result_large_err)]
+  fn send_split_with(
+    &self,
+    mut send: impl FnMut(&Message) -> Result<MessageResponse>,
+  ) -> std::result::Result<Vec<MessageResponse>, SplitSendError> {
+    let chunks = split_message(&self.message, MAX_MESSAGE_LENGTH);
+    let parts = chunks.len();
+    let mut responses = vec![];
+
+    for (index, chunk) in chunks.into_iter().enumerate() {
+      let mut message = self.clone();
+      message.message = chunk;
+
+      if parts > 1 {
+        let number = format!("({}/{})", index + 1, parts);
+        message.title = Some(match &self.title {
+          Some(title) => {
+            let room = MAX_TITLE_LENGTH - number.chars().count() - 1;
+            format!("{} {}", truncate_message(title, room), number)
+          }
+          None => number,
+        });
+      }
+
+      if index > 0 {
+        message.attachment = None;
+        message.attachment_base64 = None;
+        message.attachment_type = None;
+      }
+
+      match send(&message) {
+        Ok(response) => responses.push(response),
+        Err(error) => {
+          return Err(SplitSendError {
+            responses,
+            part: index + 1,
+            parts,
+            error,
+          })
+        }
+      }
+    }
+
+    Ok(responses)
+  }
+}
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+impl<T: Transport> PushoverClient<T> {
+  /// Sends a message, splitting it into multiple messages when it's too long,
+  /// see [`Message::send_split()`](struct.Message.html#method.send_split).
+  /// The token and defaults are filled in like with
+  /// [`send()`](#method.send).
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, Message, PushoverClient, MAX_TITLE_LENGTH,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853c"}"#);
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853d"}"#);
+  ///
+  /// let client = PushoverClient::with_transport("token", &transport)
+  ///   .default_user("user key");
+  /// let message = Message {
+  ///   message: "word ".repeat(250),
+  ///   title: Some("t".repeat(MAX_TITLE_LENGTH)),
+  ///   ..Message::default()
+  /// };
+  ///
+  /// let responses = client.send_split(&message).unwrap();
+  /// assert_eq!(responses[1].request.as_str(), "5042853d");
+  ///
+  /// let requests = transport.requests();
+  /// assert_eq!(requests.len(), 2);
+  /// assert_eq!(requests[0].field("user").as_deref(), Some("user key"));
+  /// for (index, request) in requests.iter().enumerate() {
+  ///   let title = request.field("title").unwrap();
+  ///   assert_eq!(title.chars().count(), MAX_TITLE_LENGTH);
+  ///   assert!(title.ends_with(&format!("… ({}/2)", index + 1)));
+  /// }
+  /// ```
+  #[allow(clippy::result_large_err)]
+  pub fn send_split(
+    &self,
+    message: &Message,
+  ) -> std::result::Result<Vec<MessageResponse>, SplitSendError> {
+    self
+      .prepare(message)
+      .send_split_with(|message| self.send(message))
+  }
+}
@@ -0,0 +1,27 @@
+//! Serde helpers for HTTP status codes, which are represented by their
+//! numeric value.
+
+use http::StatusCode;
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+/// Serializes a status code as its numeric value.
+pub(crate) fn serialize<S>(
+  status: &StatusCode,
+  serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+  S: Serializer,
+{
+  serializer.serialize_u16(status.as_u16())
+}
+
+/// Deserializes a status code from its numeric value.
+pub(crate) fn deserialize<'de, D>(
+  deserializer: D,
+) -> Result<StatusCode, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  let status = u16::deserialize(deserializer)?;
+  StatusCode::from_u16(status).map_err(D::Error::custom)
+}
@@ -0,0 +1,170 @@
+//! Migrating users to subscriptions.
+
+use crate::Sound;
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+use crate::{
+  api, api_base, http_client, join_url, HttpBody, HttpMethod, HttpRequest,
+  PushoverClient, Result, SubscribedUserKey, Transport,
+};
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+use serde::Deserialize;
+
+/// The optional settings of a user that's migrated to a subscription.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SubscriptionOptions {
+  /// The name of the only device of the user that should receive the
+  /// subscription's messages, all of the user's devices when `None`.
+  pub device_name: Option<String>,
+  /// The sound to play for the subscription's messages by default.
+  pub sound: Option<Sound>,
+}
+
+/// The keys of the Pushover API's response to migrating a subscription.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+#[derive(Debug, Deserialize)]
+struct MigratedPayload {
+  subscribed_user_key: SubscribedUserKey,
+}
+
+/// Migrates a user that you have the user key of to one of your application's
+/// [subscriptions](https://pushover.net/api/subscriptions), returning the
+/// subscribed user key to send them messages with instead.
+///
+/// `subscription` is the code of the subscription, from the URL of its page
+/// on the Pushover website.
+///
+/// ```rust,no_run
+/// use pushover_api::{Message, SubscriptionOptions};
+///
+/// let token = "application token";
+/// let key = pushover_api::migrate_subscription(
+///   token,
+///   "subscription code",
+///   "user key",
+///   &SubscriptionOptions::default(),
+/// )
+/// .unwrap();
+///
+/// Message {
+///   token: token.into(),
+///   user: key.into(),
+///   message: "Welcome to the subscription!".to_string(),
+///   ..Message::default()
+/// }
+/// .send()
+/// .unwrap();
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn migrate_subscription(
+  token: &str,
+  subscription: &str,
+  user: &str,
+  options: &SubscriptionOptions,
+) -> Result<SubscribedUserKey> {
+  migrate_subscription_using(
+    &http_client()?,
+    &api_base(),
+    token,
+    subscription,
+    user,
+    options,
+  )
+}
+
+/// Migrates a user to a subscription with the given transport and base URL.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+fn migrate_subscription_using(
+  transport: &impl Transport,
+  base: &str,
+  token: &str,
+  subscription: &str,
+  user: &str,
+  options: &SubscriptionOptions,
+) -> Result<SubscribedUserKey> {
+  let mut fields = vec![
+    ("token".to_string(), token.to_string()),
+    ("subscription".to_string(), subscription.to_string()),
+    ("user".to_string(), user.to_string()),
+  ];
+  if let Some(device_name) = &options.device_name {
+    fields.push(("device_name".to_string(), device_name.clone()));
+  }
+  if let Some(sound) = &options.sound {
+    fields.push(("sound".to_string(), sound.to_string()));
+  }
+
+  let request = HttpRequest::new(
+    HttpMethod::Post,
+    join_url(base, "subscriptions/migrate.json"),
+  )
+  .with_body(HttpBody::Form(fields));
+
+  let payload: MigratedPayload =
+    api::execute(transport, request)?.response.payload;
+  Ok(payload.subscribed_user_key)
+}
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+impl<T: Transport> PushoverClient<T> {
+  /// Migrates a user to one of the application's subscriptions, see
+  /// [`migrate_subscription()`](fn.migrate_subscription.html).
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, PushoverClient, Sound, SubscriptionOptions,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"subscribed_user_key":"u4fKzVjGPUbnEoQ8ri9WJ2PmFBDhXh",
+  ///     "status":1,"request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"subscription":"invalid","errors":["subscription code is invalid"],
+  ///     "status":0,"request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport);
+  /// let options = SubscriptionOptions {
+  ///   device_name: Some("pixel".to_string()),
+  ///   sound: Some(Sound::Bike),
+  /// };
+  ///
+  /// let key = client
+  ///   .migrate_subscription("subscription code", "user key", &options)
+  ///   .unwrap();
+  /// assert_eq!(key.as_str(), "u4fKzVjGPUbnEoQ8ri9WJ2PmFBDhXh");
+  ///
+  /// let error = client
+  ///   .migrate_subscription("invalid", "user key", &options)
+  ///   .unwrap_err();
+  /// assert_eq!(error.api_errors()[0].message, "subscription code is invalid");
+  ///
+  /// let request = &transport.requests()[0];
+  /// assert!(request.url.ends_with("/1/subscriptions/migrate.json"));
+  /// assert_eq!(
+  ///   request.field("subscription").as_deref(),
+  ///   Some("subscription code")
+  /// );
+  /// assert_eq!(request.field("user").as_deref(), Some("user key"));
+  /// assert_eq!(request.field("device_name").as_deref(), Some("pixel"));
+  /// assert_eq!(request.field("sound").as_deref(), Some("bike"));
+  /// ```
+  pub fn migrate_subscription(
+    &self,
+    subscription: &str,
+    user: &str,
+    options: &SubscriptionOptions,
+  ) -> Result<SubscribedUserKey> {
+    migrate_subscription_using(
+      &self.transport,
+      &self.base_url,
+      self.token(),
+      subscription,
+      user,
+      options,
+    )
+  }
+}
@@ -0,0 +1,291 @@
+//! Managing the users of a Pushover for Teams account.
+
+use crate::Secret;
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+use crate::{
+  api, api_base, http_client, join_url, HttpBody, HttpMethod, HttpRequest,
+  PushoverClient, PushoverError, RequestId, ResponseExtras, Result, Transport,
+};
+
+/// A user to add to a [Pushover for Teams](https://pushover.net/api/teams)
+/// account, see [`team_add_user()`](fn.team_add_user.html).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct NewTeamUser {
+  /// The email address of the user, which they get their invitation at.
+  pub email: String,
+  /// The name of the user, shown to the team's administrators.
+  pub name: Option<String>,
+  /// The password of the user's new account, when they don't have one yet.
+  /// Without it, the user chooses one when accepting their invitation.
+  pub password: Option<Secret>,
+  /// Whether to add the user right away, without them having to accept an
+  /// invitation first.
+  pub instant: bool,
+  /// Whether the user becomes an administrator of the team.
+  pub admin: bool,
+}
+
+/// The outcome of adding a user to a team, as returned by
+/// [`team_add_user()`](fn.team_add_user.html).
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TeamAddOutcome {
+  /// Whether the user was sent an invitation to accept, instead of being
+  /// added right away.
+  pub invited: bool,
+  /// The identifier of the request.
+  pub request: RequestId,
+}
+
+/// Adds a user to a Pushover for Teams account, using one of the team's
+/// licenses. `token` is the team's API token, from its settings page.
+///
+/// An empty email address is returned as an error without making a request.
+/// A user that's already on the team is returned as an
+/// [`Api`](enum.PushoverError.html#variant.Api) error, whose
+/// [`ApiError::kind()`](struct.ApiError.html#method.kind) is
+/// [`AlreadyOnTeam`](enum.ApiErrorKind.html#variant.AlreadyOnTeam), and a
+/// team without any licenses left is
+/// [`NoLicensesAvailable`](enum.ApiErrorKind.html#variant.NoLicensesAvailable).
+///
+/// ```rust,no_run
+/// use pushover_api::NewTeamUser;
+///
+/// let user = NewTeamUser {
+///   email: "new.teammate@example.com".to_string(),
+///   name: Some("New Teammate".to_string()),
+///   ..NewTeamUser::default()
+/// };
+/// let outcome = pushover_api::team_add_user("team token", &user).unwrap();
+/// if outcome.invited {
+///   println!("Invitation sent to {}", user.email);
+/// }
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn team_add_user(
+  token: &str,
+  user: &NewTeamUser,
+) -> Result<TeamAddOutcome> {
+  team_add_user_using(&http_client()?, &api_base(), token, user)
+}
+
+/// Adds a user to a team with the given transport and base URL.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+fn team_add_user_using(
+  transport: &impl Transport,
+  base: &str,
+  token: &str,
+  user: &NewTeamUser,
+) -> Result<TeamAddOutcome> {
+  if user.email.trim().is_empty() {
+    return Err(PushoverError::InvalidArgument(
+      "The email address of the user to add to the team is empty".to_string(),
+    ));
+  }
+
+  let mut fields = vec![
+    ("token".to_string(), token.to_string()),
+    ("email".to_string(), user.email.trim().to_string()),
+  ];
+  if let Some(name) = &user.name {
+    fields.push(("name".to_string(), name.clone()));
+  }
+  if let Some(password) = &user.password {
+    let password = password.expose_secret().to_string();
+    fields.push(("password".to_string(), password));
+  }
+  if user.instant {
+    fields.push(("instant".to_string(), "true".to_string()));
+  }
+  if user.admin {
+    fields.push(("admin".to_string(), "true".to_string()));
+  }
+
+  let request =
+    HttpRequest::new(HttpMethod::Post, join_url(base, "teams/add_user.json"))
+      .with_body(HttpBody::Form(fields));
+  let reply = api::execute::<ResponseExtras>(transport, request)?;
+  Ok(TeamAddOutcome {
+    invited: !user.instant,
+    request: reply.response.request.unwrap_or_default(),
+  })
+}
+
+/// Removes a user from a Pushover for Teams account by their email address,
+/// which frees up their license. `token` is the team's API token.
+///
+/// The Teams API only removes users, it has no way to suspend them. An email
+/// address that isn't on the team is returned as an
+/// [`Api`](enum.PushoverError.html#variant.Api) error, whose
+/// [`ApiError::kind()`](struct.ApiError.html#method.kind) is
+/// [`NotOnTeam`](enum.ApiErrorKind.html#variant.NotOnTeam), so removing a user
+/// that's already gone can be ignored.
+///
+/// ```rust,no_run
+/// use pushover_api::ApiErrorKind;
+///
+/// match pushover_api::team_remove_user("team token", "former@example.com") {
+///   Ok(()) => println!("Removed"),
+///   Err(error)
+///     if error
+///       .api_errors()
+///       .iter()
+///       .any(|error| error.kind() == ApiErrorKind::NotOnTeam) => {}
+///   Err(error) => panic!("{}", error),
+/// }
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn team_remove_user(token: &str, email: &str) -> Result<()> {
+  team_remove_user_using(&http_client()?, &api_base(), token, email)
+}
+
+/// Removes a user from a team with the given transport and base URL.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+fn team_remove_user_using(
+  transport: &impl Transport,
+  base: &str,
+  token: &str,
+  email: &str,
+) -> Result<()> {
+  let email = email.trim();
+  if email.is_empty() {
+    return Err(PushoverError::InvalidArgument(
+      "The email address of the user to remove from the team is empty"
+        .to_string(),
+    ));
+  }
+
+  let request = HttpRequest::new(
+    HttpMethod::Post,
+    join_url(base, "teams/remove_user.json"),
+  )
+  .with_body(HttpBody::Form(vec![
+    ("token".to_string(), token.to_string()),
+    ("email".to_string(), email.to_string()),
+  ]));
+
+  api::execute::<ResponseExtras>(transport, request)?;
+  Ok(())
+}
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+impl<T: Transport> PushoverClient<T> {
+  /// Adds a user to a Pushover for Teams account, see
+  /// [`team_add_user()`](fn.team_add_user.html). The client's token has to
+  /// be the team's API token.
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, ApiErrorKind, NewTeamUser, PushoverClient,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853c"}"#);
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853d"}"#);
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"status":0,"errors":["user is already a member of this team"],
+  ///     "request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"status":0,"errors":["team has no licenses available"],
+  ///     "request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = PushoverClient::with_transport("team token", &transport);
+  ///
+  /// let invited = NewTeamUser {
+  ///   email: "new.teammate@example.com".to_string(),
+  ///   name: Some("New Teammate".to_string()),
+  ///   ..NewTeamUser::default()
+  /// };
+  /// let outcome = client.team_add_user(&invited).unwrap();
+  /// assert!(outcome.invited);
+  /// assert_eq!(outcome.request.as_str(), "5042853c");
+  ///
+  /// let instant = NewTeamUser {
+  ///   email: "admin@example.com".to_string(),
+  ///   password: Some("hunter2".into()),
+  ///   instant: true,
+  ///   admin: true,
+  ///   ..NewTeamUser::default()
+  /// };
+  /// assert!(!client.team_add_user(&instant).unwrap().invited);
+  ///
+  /// let error = client.team_add_user(&invited).unwrap_err();
+  /// assert_eq!(error.api_errors()[0].kind(), ApiErrorKind::AlreadyOnTeam);
+  ///
+  /// let error = client.team_add_user(&invited).unwrap_err();
+  /// assert_eq!(
+  ///   error.api_errors()[0].kind(),
+  ///   ApiErrorKind::NoLicensesAvailable
+  /// );
+  ///
+  /// assert!(client.team_add_user(&NewTeamUser::default()).is_err());
+  ///
+  /// let requests = transport.requests();
+  /// assert_eq!(requests.len(), 4);
+  /// assert!(requests[0].url.ends_with("/1/teams/add_user.json"));
+  /// assert_eq!(requests[0].field("token").as_deref(), Some("team token"));
+  /// assert_eq!(
+  ///   requests[0].field("email").as_deref(),
+  ///   Some("new.teammate@example.com")
+  /// );
+  /// assert_eq!(requests[0].field("name").as_deref(), Some("New Teammate"));
+  /// assert_eq!(requests[0].field("instant"), None);
+  /// assert_eq!(requests[1].field("password").as_deref(), Some("hunter2"));
+  /// assert_eq!(requests[1].field("instant").as_deref(), Some("true"));
+  /// assert_eq!(requests[1].field("admin").as_deref(), Some("true"));
+  /// ```
+  pub fn team_add_user(&self, user: &NewTeamUser) -> Result<TeamAddOutcome> {
+    team_add_user_using(&self.transport, &self.base_url, self.token(), user)
+  }
+
+  /// Removes a user from a Pushover for Teams account, see
+  /// [`team_remove_user()`](fn.team_remove_user.html). The client's token has
+  /// to be the team's API token.
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, ApiErrorKind, PushoverClient,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(200, r#"{"status":1,"request":"5042853c"}"#);
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"status":0,"errors":["user is not a member of this team"],
+  ///     "request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"token":"invalid","status":0,
+  ///     "errors":["application token is invalid"],"request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = PushoverClient::with_transport("team token", &transport);
+  ///
+  /// client.team_remove_user("former@example.com").unwrap();
+  ///
+  /// let error = client.team_remove_user("former@example.com").unwrap_err();
+  /// assert_eq!(error.api_errors()[0].kind(), ApiErrorKind::NotOnTeam);
+  ///
+  /// let error = client.team_remove_user("former@example.com").unwrap_err();
+  /// assert_eq!(error.api_errors()[0].kind(), ApiErrorKind::InvalidToken);
+  ///
+  /// assert!(client.team_remove_user(" ").is_err());
+  ///
+  /// let requests = transport.requests();
+  /// assert_eq!(requests.len(), 3);
+  /// assert!(requests[0].url.ends_with("/1/teams/remove_user.json"));
+  /// assert_eq!(requests[0].field("token").as_deref(), Some("team token"));
+  /// assert_eq!(
+  ///   requests[0].field("email").as_deref(),
+  ///   Some("former@example.com")
+  /// );
+  /// ```
+  pub fn team_remove_user(&self, email: &str) -> Result<()> {
+    team_remove_user_using(&self.transport, &self.base_url, self.token(), email)
+  }
+}
@@ -0,0 +1,181 @@
+//! Interpolating values into messages.
+
+use std::{
+  borrow::Borrow,
+  collections::{BTreeMap, HashMap},
+  fmt,
+  hash::{BuildHasher, Hash},
+};
+
+/// A source of values for [`Template::render()`](struct.Template.html#method.render).
+pub trait TemplateVars {
+  /// Returns the value for a placeholder name, if there is one.
+  fn get(&self, name: &str) -> Option<&str>;
+}
+
+impl<K, V, S> TemplateVars for HashMap<K, V, S>
+where
+  K: Borrow<str> + Eq + Hash,
+  V: AsRef<str>,
+  S: BuildHasher,
+{
+  fn get(&self, name: &str) -> Option<&str> {
+    HashMap::get(self, name).map(AsRef::as_ref)
+  }
+}
+
+impl<K, V> TemplateVars for BTreeMap<K, V>
+where
+  K: Borrow<str> + Ord,
+  V: AsRef<str>,
+{
+  fn get(&self, name: &str) -> Option<&str> {
+    BTreeMap::get(self, name).map(AsRef::as_ref)
+  }
+}
+
+/// The reason a template couldn't be parsed or rendered.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TemplateError {
+  /// A `{` was never closed.
+  UnclosedPlaceholder,
+  /// A `}` was found without a matching `{`, use `}}` for a literal `}`.
+  UnmatchedBrace,
+  /// A placeholder has no name.
+  EmptyPlaceholder,
+  /// There is no value for a placeholder.
+  MissingVariable(String),
+}
+
+impl fmt::Display for TemplateError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      TemplateError::UnclosedPlaceholder => {
+        write!(f, "The template has a placeholder that isn't closed")
+      }
+      TemplateError::UnmatchedBrace => write!(
+        f,
+        "The template has a closing brace without an opening one, \
+          use }}}} for a literal brace"
+      ),
+      TemplateError::EmptyPlaceholder => {
+        write!(f, "The template has a placeholder without a name")
+      }
+      TemplateError::MissingVariable(name) => {
+        write!(f, "There is no value for the placeholder {:?}", name)
+      }
+    }
+  }
+}
+
+impl std::error::Error for TemplateError {}
+
+/// A part of a parsed template.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Part {
+  /// Text that's rendered as-is.
+  Literal(String),
+  /// A placeholder that's replaced with a value.
+  Placeholder(String),
+}
+
+/// A message pattern with `{name}` placeholders, for sending the same kind of
+/// message with different values. Use `{{` and `}}` for literal braces.
+///
+/// ```rust
+/// use pushover_api::{Template, TemplateError};
+/// use std::collections::HashMap;
+///
+/// let template = Template::parse("{service} on {host} is {state} {{!}}")
+///   .unwrap();
+///
+/// let mut vars = HashMap::new();
+/// vars.insert("service", "nginx");
+/// vars.insert("host", "web-01");
+/// vars.insert("state", "down ⚠️");
+/// assert_eq!(
+///   template.render(&vars).unwrap(),
+///   "nginx on web-01 is down ⚠️ {!}"
+/// );
+///
+/// vars.remove("state");
+/// assert_eq!(
+///   template.render(&vars),
+///   Err(TemplateError::MissingVariable("state".to_string()))
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Template {
+  parts: Vec<Part>,
+}
+
+impl Template {
+  /// Parses a pattern into a template.
+  pub fn parse(pattern: &str) -> Result<Self, TemplateError> {
+    let mut parts = vec![];
+    let mut literal = String::new();
+    let mut characters = pattern.chars().peekable();
+
+    while let Some(character) = characters.next() {
+      match character {
+        '{' if characters.peek() == Some(&'{') => {
+          characters.next();
+          literal.push('{');
+        }
+        '}' if characters.peek() == Some(&'}') => {
+          characters.next();
+          literal.push('}');
+        }
+        '{' => {
+          let mut name = String::new();
+          loop {
+            match characters.next() {
+              Some('}') => break,
+              Some(character) => name.push(character),
+              None => return Err(TemplateError::UnclosedPlaceholder),
+            }
+          }
+
+          let name = name.trim();
+          if name.is_empty() {
+            return Err(TemplateError::EmptyPlaceholder);
+          }
+
+          if !literal.is_empty() {
+            parts.push(Part::Literal(std::mem::take(&mut literal)));
+          }
+
+          parts.push(Part::Placeholder(name.to_string()));
+        }
+        '}' => return Err(TemplateError::UnmatchedBrace),
+        character => literal.push(character),
+      }
+    }
+
+    if !literal.is_empty() {
+      parts.push(Part::Literal(literal));
+    }
+
+    Ok(Self { parts })
+  }
+
+  /// Renders the template, replacing every placeholder with its value.
+  pub fn render<V: TemplateVars + ?Sized>(
+    &self,
+    vars: &V,
+  ) -> Result<String, TemplateError> {
+    let mut rendered = String::new();
+    for part in &self.parts {
+      match part {
+        Part::Literal(text) => rendered.push_str(text),
+        Part::Placeholder(name) => rendered.push_str(
+          vars
+            .get(name)
+            .ok_or_else(|| TemplateError::MissingVariable(name.clone()))?,
+        ),
+      }
+    }
+
+    Ok(rendered)
+  }
+}
@@ -0,0 +1,306 @@
+//! Fakes for testing code that uses this library without a network.
+
+use crate::{HttpRequest, HttpResponse, Result, Transport};
+use http::StatusCode;
+use std::{collections::VecDeque, sync::Mutex};
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+  io::{BufRead, BufReader, Write},
+  net::{SocketAddr, TcpListener, TcpStream},
+  sync::Arc,
+  thread,
+};
+
+/// A [`Transport`](../trait.Transport.html) that records the requests it's
+/// given and returns scripted responses instead of making any requests.
+///
+/// Without any scripted responses left, it responds like a successful
+/// message was sent.
+///
+/// ```rust
+/// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+/// # {
+/// use pushover_api::{test_support::FakeTransport, PushoverClient};
+///
+/// let transport = FakeTransport::new();
+/// transport.push_json(400, r#"{"status":0,"errors":["user is invalid"]}"#);
+///
+/// let client = PushoverClient::with_transport("application token", &transport)
+///   .default_user("user key");
+///
+/// assert!(client.notify("First").is_err());
+/// assert!(client.notify("Second").is_ok());
+///
+/// let requests = transport.requests();
+/// assert_eq!(requests.len(), 2);
+/// assert_eq!(requests[1].field("message").as_deref(), Some("Second"));
+/// assert_eq!(requests[1].field("user").as_deref(), Some("user key"));
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct FakeTransport {
+  requests: Mutex<Vec<HttpRequest>>,
+  responses: Mutex<VecDeque<Result<HttpResponse>>>,
+}
+
+impl FakeTransport {
+  /// Creates a transport without any scripted responses.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds a response to return, after any responses added before it.
+  pub fn push_response(&self, response: Result<HttpResponse>) {
+    self.responses.lock().unwrap().push_back(response);
+  }
+
+  /// Adds a JSON response with the given HTTP status code to return.
+  ///
+  /// Panics when the status code is invalid.
+  pub fn push_json(&self, status: u16, body: &str) {
+    let status = StatusCode::from_u16(status).expect("Invalid status code");
+    self.push_response(Ok(HttpResponse::json(status, body)));
+  }
+
+  /// Returns the requests that were made so far.
+  pub fn requests(&self) -> Vec<HttpRequest> {
+    self.requests.lock().unwrap().clone()
+  }
+}
+
+impl Transport for FakeTransport {
+  fn execute(&self, request: HttpRequest) -> Result<HttpResponse> {
+    self.requests.lock().unwrap().push(request);
+    self
+      .responses
+      .lock()
+      .unwrap()
+      .pop_front()
+      .unwrap_or_else(|| Ok(success()))
+  }
+}
+
+/// The response to a successfully sent message.
+fn success() -> HttpResponse {
+  HttpResponse::json(
+    StatusCode::OK,
+    r#"{"status":1,"request":"00000000-0000-0000-0000-000000000000"}"#,
+  )
+}
+
+/// Answers requests to the server, which is called on a thread of its own for
+/// every connection.
+#[cfg(not(target_arch = "wasm32"))]
+type Handler = dyn Fn(&MockRequest) -> HttpResponse + Send + Sync;
+
+/// A local HTTP server with scripted responses, for testing what goes over
+/// the wire and the code that can't be given a
+/// [`FakeTransport`](struct.FakeTransport.html), like the functions using the
+/// global HTTP client.
+///
+/// ```rust
+/// # #[cfg(any(feature = "blocking", feature = "ureq"))]
+/// # {
+/// use pushover_api::{test_support::MockServer, HttpResponse, Message};
+///
+/// let server = MockServer::respond(vec![HttpResponse::json(
+///   http::StatusCode::BAD_REQUEST,
+///   r#"{"status":0,"errors":["user is invalid"]}"#,
+/// )]);
+/// server.set_api_url_var();
+///
+/// let message = Message {
+///   token: "token".into(),
+///   user: "user".into(),
+///   message: "Hello".to_string(),
+///   ..Message::default()
+/// };
+/// assert!(message.send().is_err());
+/// assert!(message.send().is_ok());
+///
+/// let requests = server.requests();
+/// assert_eq!(requests[1].target, "/1/messages.json");
+/// assert_eq!(requests[1].json()["message"], "Hello");
+/// # }
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+#[doc(hidden)]
+pub struct MockServer {
+  address: SocketAddr,
+  requests: Arc<Mutex<Vec<MockRequest>>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl MockServer {
+  /// Starts a server that answers with the given responses in order, and
+  /// like a successful message was sent once they run out.
+  pub fn respond(responses: Vec<HttpResponse>) -> Self {
+    let responses = Mutex::new(VecDeque::from(responses));
+    Self::with_handler(move |_| {
+      responses
+        .lock()
+        .unwrap()
+        .pop_front()
+        .unwrap_or_else(success)
+    })
+  }
+
+  /// Starts a server that answers every request with the response returned
+  /// by `handler`. Connections are handled on threads of their own, so it
+  /// can wait before answering without holding up other requests.
+  pub fn with_handler(
+    handler: impl Fn(&MockRequest) -> HttpResponse + Send + Sync + 'static,
+  ) -> Self {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let server = Self {
+      address: listener.local_addr().unwrap(),
+      requests: Arc::default(),
+    };
+
+    let handler: Arc<Handler> = Arc::new(handler);
+    let requests = server.requests.clone();
+    thread::spawn(move || {
+      for stream in listener.incoming().flatten() {
+        let (handler, requests) = (handler.clone(), requests.clone());
+        thread::spawn(move || serve(stream, &*handler, &requests));
+      }
+    });
+
+    server
+  }
+
+  /// The address the server listens on, to use it as a proxy.
+  pub fn address(&self) -> SocketAddr {
+    self.address
+  }
+
+  /// The base URL to use instead of
+  /// [`PUSHOVER_API`](../constant.PUSHOVER_API.html).
+  pub fn url(&self) -> String {
+    format!("http://{}/1", self.address)
+  }
+
+  /// Sets the [`PUSHOVER_API_URL_VAR`](../constant.PUSHOVER_API_URL_VAR.html)
+  /// environment variable to this server, so requests that don't go through
+  /// a client with its own base URL are sent here.
+  pub fn set_api_url_var(&self) {
+    std::env::set_var(crate::PUSHOVER_API_URL_VAR, self.url());
+  }
+
+  /// Returns the requests that were received so far, in the order they
+  /// arrived.
+  pub fn requests(&self) -> Vec<MockRequest> {
+    self.requests.lock().unwrap().clone()
+  }
+}
+
+/// Answers the requests on a connection until it's closed.
+#[cfg(not(target_arch = "wasm32"))]
+fn serve(
+  stream: TcpStream,
+  handler: &Handler,
+  requests: &Mutex<Vec<MockRequest>>,
+) {
+  let mut reader = match stream.try_clone() {
+    Ok(stream) => BufReader::new(stream),
+    Err(_) => return,
+  };
+  let mut stream = stream;
+
+  while let Some(request) = MockRequest::read(&mut reader) {
+    requests.lock().unwrap().push(request.clone());
+    let response = handler(&request);
+
+    let mut head = format!(
+      "HTTP/1.1 {} {}\r\n",
+      response.status.as_u16(),
+      response.status.canonical_reason().unwrap_or("Status")
+    );
+    for (name, value) in &response.headers {
+      head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str(&format!("content-length: {}\r\n\r\n", response.body.len()));
+
+    let written = stream
+      .write_all(head.as_bytes())
+      .and_then(|_| stream.write_all(response.body.as_bytes()));
+    if written.is_err() {
+      return;
+    }
+  }
+}
+
+/// A request received by a [`MockServer`](struct.MockServer.html).
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone, Debug)]
+#[doc(hidden)]
+pub struct MockRequest {
+  /// The HTTP method, like `POST`.
+  pub method: String,
+  /// The request target, which is the path with its query string or the full
+  /// URL for a proxy.
+  pub target: String,
+  /// The headers, with lowercase names.
+  pub headers: Vec<(String, String)>,
+  /// The body, with any invalid UTF-8 replaced.
+  pub body: String,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl MockRequest {
+  /// Reads a request, returning `None` when the connection is closed or the
+  /// request is malformed.
+  fn read(reader: &mut impl BufRead) -> Option<Self> {
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let mut request_line = line.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let target = request_line.next()?.to_string();
+
+    let mut headers = vec![];
+    loop {
+      let mut line = String::new();
+      if reader.read_line(&mut line).ok()? == 0 {
+        return None;
+      }
+
+      match line.trim_end().split_once(':') {
+        Some((name, value)) => {
+          headers.push((name.to_lowercase(), value.trim().to_string()))
+        }
+        None => break,
+      }
+    }
+
+    let mut request = Self {
+      method,
+      target,
+      headers,
+      body: String::new(),
+    };
+    let length = request
+      .header("content-length")
+      .and_then(|length| length.parse().ok())
+      .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).ok()?;
+    request.body = String::from_utf8_lossy(&body).to_string();
+    Some(request)
+  }
+
+  /// Returns the value of a header, ignoring the case of its name.
+  pub fn header(&self, name: &str) -> Option<&str> {
+    self
+      .headers
+      .iter()
+      .find(|(key, _)| key.eq_ignore_ascii_case(name))
+      .map(|(_, value)| value.as_str())
+  }
+
+  /// Parses the body as JSON.
+  ///
+  /// Panics when it isn't valid JSON.
+  pub fn json(&self) -> serde_json::Value {
+    serde_json::from_str(&self.body).expect("The body isn't valid JSON")
+  }
+}
@@ -0,0 +1,121 @@
+//! Tracing spans and events for requests to the Pushover API.
+
+use crate::{HttpRequest, HttpResponse, MessagePriority, PushoverError};
+use std::error::Error;
+use tracing::Span;
+
+/// The number of characters of a user key that are kept when redacting it.
+const USER_PREFIX_LENGTH: usize = 5;
+
+/// Redacts a user key to its first few characters.
+pub(crate) fn redact(key: &str) -> String {
+  let prefix = key.chars().take(USER_PREFIX_LENGTH).collect::<String>();
+  format!("{}…", prefix)
+}
+
+/// The span for sending a message, with its user key redacted.
+pub(crate) fn message_span(
+  user: &str,
+  priority: Option<MessagePriority>,
+) -> Span {
+  tracing::info_span!(
+    "send",
+    endpoint = "messages.json",
+    user = %redact(user),
+    priority = %priority.unwrap_or_default(),
+  )
+}
+
+/// The span for a single request, with any query string left out of the URL
+/// because it can contain the application token.
+pub(crate) fn request_span(request: &HttpRequest) -> Span {
+  let url = request.url.split('?').next().unwrap_or_default();
+  tracing::debug_span!("request", method = ?request.method, url = %url)
+}
+
+/// Records the status and rate limit headers of a response, and its body at
+/// the trace level.
+pub(crate) fn response(
+  response: &HttpResponse,
+  limit: Option<u32>,
+  remaining: Option<u32>,
+  reset: Option<i64>,
+) {
+  tracing::debug!(
+    http_status = response.status.as_u16(),
+    limit = ?limit,
+    remaining = ?remaining,
+    reset = ?reset,
+    "Received a response from the Pushover API",
+  );
+  tracing::trace!(body = %response.body, "Response body");
+}
+
+/// Records a request that failed.
+pub(crate) fn failure(error: &PushoverError) {
+  tracing::warn!(error = %describe(error), "A Pushover API request failed");
+}
+
+/// Records a failed attempt that is retried after `delay`.
+#[cfg(any(
+  feature = "blocking",
+  feature = "ureq",
+  all(feature = "async", not(target_arch = "wasm32"))
+))]
+pub(crate) fn retry(
+  attempt: u32,
+  delay: std::time::Duration,
+  error: &PushoverError,
+) {
+  tracing::warn!(
+    attempt,
+    delay = ?delay,
+    error = %describe(error),
+    "Retrying a failed Pushover API request",
+  );
+}
+
+/// Describes an error without the URL of Reqwest and ureq errors, which can
+/// contain the application token in its query string.
+fn describe(error: &PushoverError) -> String {
+  match error {
+    #[cfg(feature = "ureq")]
+    PushoverError::Ureq(inner) => {
+      let kind = if error.is_timeout() {
+        "The request timed out"
+      } else {
+        "The request failed"
+      };
+
+      match (inner.message(), inner.source()) {
+        (_, Some(source)) => format!("{}: {}: {}", kind, inner.kind(), source),
+        (Some(message), None) => {
+          format!("{}: {}: {}", kind, inner.kind(), message)
+        }
+        (None, None) => format!("{}: {}", kind, inner.kind()),
+      }
+    }
+    #[cfg(feature = "reqwest")]
+    PushoverError::Transport(inner) | PushoverError::Timeout(inner) => {
+      let kind = if error.is_timeout() {
+        "The request timed out"
+      } else {
+        "The request failed"
+      };
+
+      match inner.source() {
+        Some(source) => format!("{}: {}", kind, source),
+        None => kind.to_string(),
+      }
+    }
+    PushoverError::RetriesExhausted {
+      attempts,
+      last_error,
+    } => format!(
+      "Giving up after {} attempts: {}",
+      attempts,
+      describe(last_error)
+    ),
+    error => error.to_string(),
+  }
+}
@@ -0,0 +1,538 @@
+//! Sending HTTP requests, replaceable to test without a network.
+
+use crate::{Attachment, Result};
+#[cfg(feature = "reqwest")]
+use http::HeaderMap;
+use http::StatusCode;
+#[cfg(feature = "blocking")]
+use reqwest::blocking::{multipart::Form, Client};
+use std::fmt;
+
+/// The HTTP method of a request.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HttpMethod {
+  /// A `GET` request.
+  Get,
+  /// A `POST` request.
+  Post,
+  /// A `DELETE` request.
+  Delete,
+}
+
+/// The body of a request. Its `Debug` output redacts the values of fields
+/// holding a token, key or password.
+#[derive(Clone, PartialEq)]
+pub enum HttpBody {
+  /// No body.
+  Empty,
+  /// A JSON body.
+  Json(String),
+  /// A URL-encoded form.
+  Form(Vec<(String, String)>),
+  /// A multipart form with an attachment.
+  Multipart {
+    /// The text fields of the form.
+    fields: Vec<(String, String)>,
+    /// The attachment.
+    attachment: Attachment,
+  },
+}
+
+/// A request to the Pushover API, ready to be sent by a
+/// [`Transport`](trait.Transport.html). Its `Debug` output redacts the values
+/// of query parameters and fields holding a token, key or password.
+///
+/// ```rust
+/// use pushover_api::{HttpBody, HttpMethod, HttpRequest};
+///
+/// let token = "azGDORePK8gMaC0QOYAMyEEuzJnyUi";
+/// let user = "uQiRzpo4DXghDmr9QzzfQu27cmVRsG";
+///
+/// let get = HttpRequest::new(
+///   HttpMethod::Get,
+///   format!("https://api.pushover.net/1/sounds.json?token={}", token),
+/// );
+/// let form = HttpRequest::new(
+///   HttpMethod::Post,
+///   "https://api.pushover.net/1/users/validate.json".to_string(),
+/// )
+/// .with_body(HttpBody::Form(vec![
+///   ("token".to_string(), token.to_string()),
+///   ("user".to_string(), user.to_string()),
+///   ("device".to_string(), "phone".to_string()),
+/// ]));
+/// let json = HttpRequest::new(
+///   HttpMethod::Post,
+///   "https://api.pushover.net/1/messages.json".to_string(),
+/// )
+/// .with_body(HttpBody::Json(format!(
+///   r#"{{"token":"{}","user":"{}","message":"Hello"}}"#,
+///   token, user
+/// )));
+///
+/// for request in [&get, &form, &json].iter() {
+///   let debug = format!("{:?}", request);
+///   assert!(!debug.contains(token) && !debug.contains(user), "{}", debug);
+///   assert!(debug.contains("[redacted]"), "{}", debug);
+/// }
+///
+/// // Other values are kept.
+/// assert!(format!("{:?}", form).contains("phone"));
+/// assert!(format!("{:?}", json).contains("Hello"));
+/// ```
+#[derive(Clone, PartialEq)]
+pub struct HttpRequest {
+  /// The HTTP method.
+  pub method: HttpMethod,
+  /// The full URL, including any query parameters.
+  pub url: String,
+  /// The body.
+  pub body: HttpBody,
+}
+
+impl HttpRequest {
+  /// Creates a request without a body.
+  pub fn new(method: HttpMethod, url: String) -> Self {
+    Self {
+      method,
+      url,
+      body: HttpBody::Empty,
+    }
+  }
+
+  /// Adds a body to the request.
+  pub fn with_body(mut self, body: HttpBody) -> Self {
+    self.body = body;
+    self
+  }
+
+  /// Returns the value of a field of the request's JSON body or form, which
+  /// is useful for asserting on requests in tests.
+  pub fn field(&self, name: &str) -> Option<String> {
+    match &self.body {
+      HttpBody::Empty => None,
+      HttpBody::Json(json) => {
+        let value = serde_json::from_str::<serde_json::Value>(json).ok()?;
+        match value.get(name)? {
+          serde_json::Value::String(value) => Some(value.clone()),
+          value => Some(value.to_string()),
+        }
+      }
+      HttpBody::Form(fields) | HttpBody::Multipart { fields, .. } => fields
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.clone()),
+    }
+  }
+}
+
+/// The names of the query parameters and fields that hold a token, key or
+/// password.
+const SECRET_FIELDS: &[&str] =
+  &["token", "user", "secret", "password", "twofa"];
+
+/// Returns `value`, or a placeholder when `name` is one of the
+/// [`SECRET_FIELDS`].
+fn redact<'a>(name: &str, value: &'a str) -> &'a str {
+  if SECRET_FIELDS.contains(&name) {
+    "[redacted]"
+  } else {
+    value
+  }
+}
+
+/// Redacts the secret values in the query string of a URL.
+fn redact_url(url: &str) -> String {
+  let (path, query) = match url.split_once('?') {
+    Some(parts) => parts,
+    None => return url.to_string(),
+  };
+
+  let query = query
+    .split('&')
+    .map(|pair| match pair.split_once('=') {
+      Some((name, value)) => format!("{}={}", name, redact(name, value)),
+      None => pair.to_string(),
+    })
+    .collect::<Vec<_>>();
+  format!("{}?{}", path, query.join("&"))
+}
+
+/// Redacts the secret values of form fields.
+fn redact_fields(fields: &[(String, String)]) -> Vec<(&str, &str)> {
+  fields
+    .iter()
+    .map(|(name, value)| (name.as_str(), redact(name, value)))
+    .collect()
+}
+
+/// Redacts the secret values of a JSON object, or all of it when it can't be
+/// parsed.
+fn redact_json(json: &str) -> String {
+  match serde_json::from_str::<serde_json::Value>(json) {
+    Ok(serde_json::Value::Object(mut object)) => {
+      for (name, value) in object.iter_mut() {
+        if SECRET_FIELDS.contains(&name.as_str()) {
+          *value = serde_json::Value::from("[redacted]");
+        }
+      }
+      serde_json::Value::Object(object).to_string()
+    }
+    Ok(value) => value.to_string(),
+    Err(_) => "[redacted]".to_string(),
+  }
+}
+
+impl fmt::Debug for HttpBody {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      HttpBody::Empty => f.write_str("Empty"),
+      HttpBody::Json(json) => {
+        f.debug_tuple("Json").field(&redact_json(json)).finish()
+      }
+      HttpBody::Form(fields) => {
+        f.debug_tuple("Form").field(&redact_fields(fields)).finish()
+      }
+      HttpBody::Multipart { fields, attachment } => f
+        .debug_struct("Multipart")
+        .field("fields", &redact_fields(fields))
+        .field("attachment", attachment)
+        .finish(),
+    }
+  }
+}
+
+impl fmt::Debug for HttpRequest {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("HttpRequest")
+      .field("method", &self.method)
+      .field("url", &redact_url(&self.url))
+      .field("body", &self.body)
+      .finish()
+  }
+}
+
+/// A response from the Pushover API.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HttpResponse {
+  /// The HTTP status code.
+  pub status: StatusCode,
+  /// The headers, with lowercase names.
+  pub headers: Vec<(String, String)>,
+  /// The body.
+  pub body: String,
+}
+
+impl HttpResponse {
+  /// Creates a response with a JSON body.
+  pub fn json(status: StatusCode, body: &str) -> Self {
+    Self {
+      status,
+      headers: vec![(
+        "content-type".to_string(),
+        "application/json".to_string(),
+      )],
+      body: body.to_string(),
+    }
+  }
+
+  /// Adds a header to the response.
+  pub fn with_header(mut self, name: &str, value: &str) -> Self {
+    self.headers.push((name.to_lowercase(), value.to_string()));
+    self
+  }
+
+  /// Returns the value of a header, ignoring the case of its name.
+  pub fn header(&self, name: &str) -> Option<&str> {
+    self
+      .headers
+      .iter()
+      .find(|(key, _)| key.eq_ignore_ascii_case(name))
+      .map(|(_, value)| value.as_str())
+  }
+
+  /// Creates a response from the parts of a Reqwest response, skipping any
+  /// headers that aren't valid strings.
+  #[cfg(feature = "reqwest")]
+  pub(crate) fn from_parts(
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: String,
+  ) -> Self {
+    let headers = headers
+      .iter()
+      .filter_map(|(name, value)| {
+        Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+      })
+      .collect();
+
+    Self {
+      status,
+      headers,
+      body,
+    }
+  }
+}
+
+/// Something that can send HTTP requests to the Pushover API.
+///
+/// It's implemented for Reqwest's blocking `Client` and, with the `ureq`
+/// feature, ureq's `Agent`. One of those is used unless a
+/// [`PushoverClient`](struct.PushoverClient.html) is given another transport,
+/// like a fake one in tests.
+///
+/// Both backends send requests the same way and return every response,
+/// whatever its status code:
+///
+/// ```rust
+/// use http::StatusCode;
+/// use pushover_api::{
+///   Attachment, HttpBody, HttpMethod, HttpRequest, HttpResponse, Transport,
+/// };
+///
+/// # /// Answers requests on a local port, failing those to `error.json`.
+/// # fn serve() -> pushover_api::test_support::MockServer {
+/// #   pushover_api::test_support::MockServer::with_handler(|request| {
+/// #     let status = if request.target.ends_with("/error.json") {
+/// #       StatusCode::BAD_REQUEST
+/// #     } else {
+/// #       StatusCode::OK
+/// #     };
+/// #     HttpResponse::json(status, r#"{"status":1,"request":"5042853c"}"#)
+/// #       .with_header("X-Limit-App-Remaining", "5")
+/// #   })
+/// # }
+/// fn check(transport: impl Transport) {
+///   let server = serve();
+///   let base = server.url();
+///   let send = |method, path: &str, body| -> HttpResponse {
+///     let request = HttpRequest::new(method, format!("{}/{}", base, path));
+///     transport.execute(request.with_body(body)).unwrap()
+///   };
+///   let fields = || {
+///     vec![
+///       ("token".to_string(), "token".to_string()),
+///       ("message".to_string(), "Hello".to_string()),
+///     ]
+///   };
+///
+///   let response =
+///     send(HttpMethod::Get, "sounds.json?token=t", HttpBody::Empty);
+///   assert_eq!(response.status, StatusCode::OK);
+///   assert_eq!(response.body, r#"{"status":1,"request":"5042853c"}"#);
+///
+///   let json = r#"{"message":"Hello"}"#.to_string();
+///   send(HttpMethod::Post, "messages.json", HttpBody::Json(json));
+///   send(HttpMethod::Post, "receipts.json", HttpBody::Form(fields()));
+///   send(HttpMethod::Delete, "subscriptions.json", HttpBody::Empty);
+///
+///   let png = b"\x89PNG\r\n\x1a\n...".to_vec();
+///   let attachment = Attachment::from_bytes("screenshot.png", png, None);
+///   let multipart = HttpBody::Multipart {
+///     fields: fields(),
+///     attachment,
+///   };
+///   send(HttpMethod::Post, "messages.json", multipart);
+///
+///   let response = send(HttpMethod::Post, "error.json", HttpBody::Empty);
+///   assert_eq!(response.status, StatusCode::BAD_REQUEST);
+///   assert_eq!(response.header("X-Limit-App-Remaining"), Some("5"));
+///
+///   let requests = server.requests();
+///   let content_type = |index: usize| requests[index].header("content-type");
+///
+///   assert_eq!(requests[0].method, "GET");
+///   assert_eq!(requests[0].target, "/1/sounds.json?token=t");
+///
+///   assert_eq!(requests[1].method, "POST");
+///   assert_eq!(requests[1].target, "/1/messages.json");
+///   assert_eq!(content_type(1), Some("application/json"));
+///   assert_eq!(requests[1].body, r#"{"message":"Hello"}"#);
+///
+///   assert_eq!(requests[2].target, "/1/receipts.json");
+///   assert_eq!(content_type(2), Some("application/x-www-form-urlencoded"));
+///   assert_eq!(requests[2].body, "token=token&message=Hello");
+///
+///   assert_eq!(requests[3].method, "DELETE");
+///   assert_eq!(requests[3].target, "/1/subscriptions.json");
+///
+///   let multipart = requests[4].body.to_lowercase();
+///   assert!(content_type(4).unwrap().starts_with("multipart/form-data"));
+///   assert!(multipart.contains("name=\"message\"\r\n\r\nhello\r\n"));
+///   assert!(multipart
+///     .contains("name=\"attachment\"; filename=\"screenshot.png\""));
+///   assert!(multipart.contains("content-type: image/png\r\n\r\n"));
+///   assert!(multipart.contains("png\r\n"));
+/// }
+///
+/// # #[cfg(feature = "blocking")]
+/// check(reqwest::blocking::Client::new());
+/// # #[cfg(feature = "ureq")]
+/// check(ureq::agent());
+/// ```
+pub trait Transport {
+  /// Sends a request and returns its response. Only failing to get a response
+  /// at all should be an error, responses with any status code are returned
+  /// as `Ok`.
+  fn execute(&self, request: HttpRequest) -> Result<HttpResponse>;
+}
+
+#[cfg(feature = "blocking")]
+impl Transport for Client {
+  fn execute(&self, request: HttpRequest) -> Result<HttpResponse> {
+    let builder = match request.method {
+      HttpMethod::Get => self.get(&request.url),
+      HttpMethod::Post => self.post(&request.url),
+      HttpMethod::Delete => self.delete(&request.url),
+    };
+
+    let builder = match request.body {
+      HttpBody::Empty => builder,
+      HttpBody::Json(json) => builder
+        .header("content-type", "application/json")
+        .body(json),
+      HttpBody::Form(fields) => builder.form(&fields),
+      HttpBody::Multipart { fields, attachment } => {
+        let form = fields
+          .into_iter()
+          .fold(Form::new(), |form, (key, value)| form.text(key, value));
+        builder.multipart(form.part("attachment", attachment.to_part()?))
+      }
+    };
+
+    let response = builder.send()?;
+    let status = response.status();
+    let headers = response.headers().clone();
+    Ok(HttpResponse::from_parts(status, &headers, response.text()?))
+  }
+}
+
+#[cfg(feature = "ureq")]
+impl Transport for ureq::Agent {
+  fn execute(&self, request: HttpRequest) -> Result<HttpResponse> {
+    let method = match request.method {
+      HttpMethod::Get => "GET",
+      HttpMethod::Post => "POST",
+      HttpMethod::Delete => "DELETE",
+    };
+    let builder = self.request(method, &request.url);
+
+    let result = match request.body {
+      HttpBody::Empty => builder.call(),
+      HttpBody::Json(json) => builder
+        .set("content-type", "application/json")
+        .send_string(&json),
+      HttpBody::Form(fields) => {
+        let fields = fields
+          .iter()
+          .map(|(key, value)| (key.as_str(), value.as_str()))
+          .collect::<Vec<_>>();
+        builder.send_form(&fields)
+      }
+      HttpBody::Multipart { fields, attachment } => {
+        let (content_type, body) = encode_multipart(&fields, &attachment)?;
+        builder.set("content-type", &content_type).send_bytes(&body)
+      }
+    };
+
+    // Unlike Reqwest, ureq returns responses with a 4xx or 5xx status as
+    // errors.
+    let response = match result {
+      Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+      Err(ureq::Error::Transport(error)) => return Err(error.into()),
+    };
+
+    let status = StatusCode::from_u16(response.status()).map_err(|_| {
+      crate::PushoverError::InvalidArgument(format!(
+        "Invalid HTTP status {} in the response",
+        response.status()
+      ))
+    })?;
+    let headers = response
+      .headers_names()
+      .into_iter()
+      .flat_map(|name| {
+        response
+          .all(&name)
+          .into_iter()
+          .map(|value| (name.clone(), value.to_string()))
+          .collect::<Vec<_>>()
+      })
+      .collect();
+
+    Ok(HttpResponse {
+      status,
+      headers,
+      body: response.into_string()?,
+    })
+  }
+}
+
+/// Encodes the fields and attachment of a message as `multipart/form-data`,
+/// returning the content type with its boundary and the encoded body.
+#[cfg(feature = "ureq")]
+fn encode_multipart(
+  fields: &[(String, String)],
+  attachment: &Attachment,
+) -> Result<(String, Vec<u8>)> {
+  let boundary = format!("pushover-api-{:016x}", crate::random_u64());
+
+  let mut body = vec![];
+  for (name, value) in fields {
+    body.extend_from_slice(
+      format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+        boundary, name, value
+      )
+      .as_bytes(),
+    );
+  }
+
+  let (name, bytes, mime) = attachment.contents()?;
+  let name = name.replace('"', "%22").replace(['\r', '\n'], "");
+  let mime = mime.replace(['\r', '\n'], "");
+  body.extend_from_slice(
+    format!(
+      "--{}\r\nContent-Disposition: form-data; name=\"attachment\"; \
+       filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+      boundary, name, mime
+    )
+    .as_bytes(),
+  );
+  body.extend_from_slice(&bytes);
+  body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+
+  Ok((format!("multipart/form-data; boundary={}", boundary), body))
+}
+
+impl<T: Transport + ?Sized> Transport for &T {
+  fn execute(&self, request: HttpRequest) -> Result<HttpResponse> {
+    (**self).execute(request)
+  }
+}
+
+#[cfg(all(test, feature = "ureq"))]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn multipart_headers_cannot_be_injected() {
+    let attachment = Attachment::from_bytes(
+      "image\r\nX-Injected: name.png",
+      vec![0x89, b'P', b'N', b'G'],
+      Some("image/png\r\nX-Injected: mime"),
+    );
+    let (content_type, body) = encode_multipart(&[], &attachment).unwrap();
+    let body = String::from_utf8_lossy(&body);
+
+    assert!(!body.contains("\r\nX-Injected"));
+    assert!(body.contains("filename=\"imageX-Injected: name.png\""));
+    assert!(body.contains("Content-Type: image/pngX-Injected: mime\r\n\r\n"));
+
+    let boundary =
+      content_type.trim_start_matches("multipart/form-data; boundary=");
+    let (other, _) = encode_multipart(&[], &attachment).unwrap();
+    assert_ne!(other, content_type);
+    assert!(body.ends_with(&format!("\r\n--{}--\r\n", boundary)));
+  }
+}
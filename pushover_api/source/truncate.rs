@@ -0,0 +1,92 @@
+//! Truncating text to fit Pushover's limits.
+
+use crate::{
+  Message, MAX_MESSAGE_LENGTH, MAX_TITLE_LENGTH, MAX_URL_TITLE_LENGTH,
+};
+use std::borrow::Cow;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The ellipsis appended to truncated text.
+const ELLIPSIS: &str = "…";
+
+/// Truncates `text` to at most `max_chars` characters, appending an ellipsis
+/// when it had to be truncated. The ellipsis counts towards the limit.
+///
+/// Text is only cut between graphemes, so emoji and characters with
+/// combining marks are never split in half. When nothing has to be truncated
+/// the original text is returned without allocating.
+///
+/// ```rust
+/// use pushover_api::truncate_message;
+///
+/// assert_eq!(truncate_message("Hello, world!", 8), "Hello, …");
+/// assert_eq!(truncate_message("Hello", 5), "Hello");
+///
+/// // "e" followed by a combining acute accent is a single grapheme.
+/// assert_eq!(truncate_message("cafe\u{301}s", 5), "caf…");
+///
+/// // A family emoji is five characters joined with zero-width joiners, it's
+/// // either kept whole or dropped entirely.
+/// let family = "ab\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}cd";
+/// assert_eq!(family.chars().count(), 9);
+/// assert_eq!(truncate_message(family, 9), family);
+/// assert_eq!(
+///   truncate_message(family, 8),
+///   "ab\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}…"
+/// );
+/// assert_eq!(truncate_message(family, 7), "ab…");
+/// assert_eq!(truncate_message(family, 4), "ab…");
+/// assert_eq!(truncate_message(family, 3), "ab…");
+///
+/// // A flag is a pair of regional indicators, which is never split.
+/// let flags = "\u{1F1F3}\u{1F1F1}\u{1F1E7}\u{1F1EA}";
+/// assert_eq!(truncate_message(flags, 4), flags);
+/// assert_eq!(truncate_message(flags, 3), "\u{1F1F3}\u{1F1F1}…");
+/// assert_eq!(truncate_message(flags, 2), "…");
+///
+/// // With room for only the ellipsis, or not even that.
+/// assert_eq!(truncate_message("Hello", 1), "…");
+/// assert_eq!(truncate_message("Hello", 0), "");
+/// assert_eq!(truncate_message("", 0), "");
+/// ```
+pub fn truncate_message(text: &str, max_chars: usize) -> Cow<'_, str> {
+  if text.chars().count() <= max_chars {
+    return Cow::Borrowed(text);
+  }
+
+  let limit = match max_chars.checked_sub(ELLIPSIS.chars().count()) {
+    Some(limit) => limit,
+    None => return Cow::Owned(String::new()),
+  };
+
+  let mut length = 0;
+  let mut end = 0;
+  for (index, grapheme) in text.grapheme_indices(true) {
+    length += grapheme.chars().count();
+    if length > limit {
+      break;
+    }
+
+    end = index + grapheme.len();
+  }
+
+  Cow::Owned(format!("{}{}", &text[..end], ELLIPSIS))
+}
+
+impl Message {
+  /// Returns a copy of this message with the message, title and URL title
+  /// truncated to fit Pushover's limits.
+  pub(crate) fn truncated(&self) -> Message {
+    let mut message = self.clone();
+    message.message =
+      truncate_message(&self.message, MAX_MESSAGE_LENGTH).into_owned();
+    message.title = self
+      .title
+      .as_ref()
+      .map(|title| truncate_message(title, MAX_TITLE_LENGTH).into_owned());
+    message.url_title = self.url_title.as_ref().map(|url_title| {
+      truncate_message(url_title, MAX_URL_TITLE_LENGTH).into_owned()
+    });
+    message
+  }
+}
@@ -0,0 +1,97 @@
+//! The user agent sent to the Pushover API.
+
+use crate::{PushoverError, Result, USER_AGENT};
+use http::HeaderValue;
+use std::{fmt, sync::RwLock};
+
+/// The user agent set with [`set_user_agent()`](fn.set_user_agent.html), if
+/// any.
+static DEFAULT_USER_AGENT: RwLock<Option<UserAgent>> = RwLock::new(None);
+
+/// The `User-Agent` header sent with requests to the Pushover API, which
+/// Pushover asks integrations to use to identify themselves.
+///
+/// [`new()`](#method.new) puts your application in front of the library's own
+/// identifier, [`replace()`](#method.replace) sends only your value. Both
+/// return an [`InvalidArgument`](enum.PushoverError.html#variant.InvalidArgument)
+/// error for values that can't be sent as a header.
+///
+/// ```rust
+/// use pushover_api::UserAgent;
+///
+/// let user_agent = UserAgent::new("Backups/1.2").unwrap();
+/// assert_eq!(user_agent.as_str(), "Backups/1.2 Rust Pushover API Library");
+///
+/// let user_agent = UserAgent::replace("Backups/1.2").unwrap();
+/// assert_eq!(user_agent.as_str(), "Backups/1.2");
+///
+/// assert!(UserAgent::new("Backups\n1.2").is_err());
+/// assert!(UserAgent::replace(" ").is_err());
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct UserAgent(String);
+
+impl UserAgent {
+  /// Creates a user agent of your application followed by the library's
+  /// identifier, like `Backups/1.2 Rust Pushover API Library`.
+  pub fn new<S: AsRef<str>>(application: S) -> Result<Self> {
+    let application = application.as_ref().trim();
+    if application.is_empty() {
+      return Self::replace(application);
+    }
+
+    Self::replace(format!("{} {}", application, USER_AGENT))
+  }
+
+  /// Creates a user agent that is sent as is, without the library's
+  /// identifier.
+  pub fn replace<S: Into<String>>(user_agent: S) -> Result<Self> {
+    let user_agent = user_agent.into().trim().to_string();
+    if user_agent.is_empty() {
+      return Err(PushoverError::InvalidArgument(
+        "The user agent can't be empty".to_string(),
+      ));
+    }
+
+    if HeaderValue::from_str(&user_agent).is_err() {
+      return Err(PushoverError::InvalidArgument(format!(
+        "Invalid user agent {:?}, it must be a valid header value",
+        user_agent
+      )));
+    }
+
+    Ok(Self(user_agent))
+  }
+
+  /// Returns the user agent as it's sent.
+  pub fn as_str(&self) -> &str {
+    &self.0
+  }
+
+  /// Replaces the user agent used by clients that don't set their own.
+  pub(crate) fn set_default(user_agent: UserAgent) {
+    *DEFAULT_USER_AGENT.write().unwrap() = Some(user_agent);
+  }
+
+  /// Returns the user agent used by clients that don't set their own.
+  pub(crate) fn current_default() -> UserAgent {
+    DEFAULT_USER_AGENT
+      .read()
+      .unwrap()
+      .clone()
+      .unwrap_or_default()
+  }
+}
+
+/// The library's own identifier.
+impl Default for UserAgent {
+  fn default() -> Self {
+    Self(USER_AGENT.to_string())
+  }
+}
+
+impl fmt::Display for UserAgent {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&self.0)
+  }
+}
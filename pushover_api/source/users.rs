@@ -0,0 +1,284 @@
+//! Validating user and group keys.
+
+use crate::int_bool;
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+use crate::{
+  api, api_base, http_client, join_url, ApiErrorKind, HttpBody, HttpMethod,
+  HttpRequest, PushoverClient, Result, Transport,
+};
+use serde::Deserialize;
+
+/// A valid user or group key, as returned by
+/// [`validate_user()`](fn.validate_user.html).
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct UserValidation {
+  /// Whether the key is a group key instead of a user key.
+  #[serde(
+    default,
+    rename = "group",
+    deserialize_with = "int_bool::deserialize_flag"
+  )]
+  pub is_group: bool,
+  /// The names of the user's active devices, empty for groups.
+  #[serde(default)]
+  pub devices: Vec<String>,
+  /// The platforms the user has licensed Pushover for, like `iOS`, empty for
+  /// groups.
+  #[serde(default)]
+  pub licenses: Vec<String>,
+}
+
+/// Checks that a user or group key is valid, and when `device` is given that
+/// it's one of the user's active devices.
+///
+/// An invalid key or device is returned as an
+/// [`Api`](enum.PushoverError.html#variant.Api) error, whose
+/// [`ApiError::kind()`](struct.ApiError.html#method.kind) is
+/// [`InvalidUser`](enum.ApiErrorKind.html#variant.InvalidUser) or
+/// [`InvalidDevice`](enum.ApiErrorKind.html#variant.InvalidDevice).
+///
+/// ```rust,no_run
+/// let validation =
+///   pushover_api::validate_user("application token", "user key", None)
+///     .unwrap();
+///
+/// println!("Devices: {}", validation.devices.join(", "));
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn validate_user(
+  token: &str,
+  user: &str,
+  device: Option<&str>,
+) -> Result<UserValidation> {
+  validate_user_using(&http_client()?, &api_base(), token, user, device)
+}
+
+/// Checks whether a user or group key is valid, returning `Ok(false)` instead
+/// of an [`InvalidUser`](enum.ApiErrorKind.html#variant.InvalidUser) error.
+/// Any other error, like an invalid token, is still returned.
+///
+/// ```rust,no_run
+/// if !pushover_api::is_user_valid("application token", "user key").unwrap() {
+///   eprintln!("That user key doesn't exist");
+/// }
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn is_user_valid(token: &str, user: &str) -> Result<bool> {
+  is_user_valid_using(&http_client()?, &api_base(), token, user)
+}
+
+/// Lists the names of a user's active devices, which is empty for groups.
+///
+/// ```rust,no_run
+/// let devices =
+///   pushover_api::list_user_devices("application token", "user key")
+///     .unwrap();
+/// println!("Devices: {}", devices.join(", "));
+/// ```
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+pub fn list_user_devices(token: &str, user: &str) -> Result<Vec<String>> {
+  Ok(validate_user(token, user, None)?.devices)
+}
+
+/// Validates a user or group key with the given transport and base URL.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+fn validate_user_using(
+  transport: &impl Transport,
+  base: &str,
+  token: &str,
+  user: &str,
+  device: Option<&str>,
+) -> Result<UserValidation> {
+  let mut fields = vec![
+    ("token".to_string(), token.to_string()),
+    ("user".to_string(), user.to_string()),
+  ];
+  if let Some(device) = device {
+    fields.push(("device".to_string(), device.to_string()));
+  }
+
+  let request =
+    HttpRequest::new(HttpMethod::Post, join_url(base, "users/validate.json"))
+      .with_body(HttpBody::Form(fields));
+  Ok(api::execute(transport, request)?.response.payload)
+}
+
+/// Checks whether a user or group key is valid with the given transport and
+/// base URL.
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+fn is_user_valid_using(
+  transport: &impl Transport,
+  base: &str,
+  token: &str,
+  user: &str,
+) -> Result<bool> {
+  match validate_user_using(transport, base, token, user, None) {
+    Ok(_) => Ok(true),
+    Err(error)
+      if error
+        .api_errors()
+        .iter()
+        .any(|error| error.kind() == ApiErrorKind::InvalidUser) =>
+    {
+      Ok(false)
+    }
+    Err(error) => Err(error),
+  }
+}
+
+#[cfg(any(feature = "blocking", feature = "ureq"))]
+impl<T: Transport> PushoverClient<T> {
+  /// Checks that a user or group key is valid, and when `device` is given
+  /// that it's one of the user's active devices, see
+  /// [`validate_user()`](fn.validate_user.html).
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, ApiErrorKind, PushoverClient,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"status":1,"group":0,"devices":["iphone","pixel"],
+  ///     "licenses":["iOS","Android"],"request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"status":1,"group":1,"devices":[],"licenses":[],
+  ///     "request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"user":"invalid","errors":["user key is invalid"],"status":0,
+  ///     "request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"device":"invalid for this user",
+  ///     "errors":["device name is not valid for user"],"status":0,
+  ///     "request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport);
+  ///
+  /// let user = client.validate_user("user key", None).unwrap();
+  /// assert!(!user.is_group);
+  /// assert_eq!(user.devices, vec!["iphone", "pixel"]);
+  /// assert_eq!(user.licenses, vec!["iOS", "Android"]);
+  ///
+  /// let group = client.validate_user("group key", None).unwrap();
+  /// assert!(group.is_group);
+  /// assert!(group.devices.is_empty());
+  ///
+  /// let error = client.validate_user("invalid key", None).unwrap_err();
+  /// assert_eq!(error.api_errors()[0].kind(), ApiErrorKind::InvalidUser);
+  ///
+  /// let error = client.validate_user("user key", Some("toaster")).unwrap_err();
+  /// assert!(matches!(
+  ///   error.api_errors()[0].kind(),
+  ///   ApiErrorKind::InvalidDevice(_)
+  /// ));
+  ///
+  /// let requests = transport.requests();
+  /// assert!(requests[0].url.ends_with("/1/users/validate.json"));
+  /// assert_eq!(requests[0].field("device"), None);
+  /// assert_eq!(requests[3].field("device").as_deref(), Some("toaster"));
+  /// ```
+  pub fn validate_user(
+    &self,
+    user: &str,
+    device: Option<&str>,
+  ) -> Result<UserValidation> {
+    validate_user_using(
+      &self.transport,
+      &self.base_url,
+      self.token(),
+      user,
+      device,
+    )
+  }
+
+  /// Checks whether a user or group key is valid, see
+  /// [`is_user_valid()`](fn.is_user_valid.html).
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, ApiErrorKind, PushoverClient,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"status":1,"group":0,"devices":["iphone"],"request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"status":1,"group":1,"devices":[],"request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"user":"invalid","errors":["user key is invalid"],"status":0,
+  ///     "request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"token":"invalid","errors":["application token is invalid"],
+  ///     "status":0,"request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport);
+  ///
+  /// assert!(client.is_user_valid("user key").unwrap());
+  /// assert!(client.is_user_valid("group key").unwrap());
+  /// assert!(!client.is_user_valid("invalid key").unwrap());
+  ///
+  /// let error = client.is_user_valid("user key").unwrap_err();
+  /// assert_eq!(error.api_errors()[0].kind(), ApiErrorKind::InvalidToken);
+  ///
+  /// let request = &transport.requests()[0];
+  /// assert!(request.url.ends_with("/1/users/validate.json"));
+  /// assert_eq!(request.field("user").as_deref(), Some("user key"));
+  /// ```
+  pub fn is_user_valid(&self, user: &str) -> Result<bool> {
+    is_user_valid_using(&self.transport, &self.base_url, self.token(), user)
+  }
+
+  /// Lists the names of a user's active devices, see
+  /// [`list_user_devices()`](fn.list_user_devices.html).
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   test_support::FakeTransport, ApiErrorKind, PushoverClient,
+  /// };
+  ///
+  /// let transport = FakeTransport::new();
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"status":1,"group":0,"devices":["iphone","pixel"],
+  ///     "request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   200,
+  ///   r#"{"status":1,"group":1,"devices":[],"request":"5042853c"}"#,
+  /// );
+  /// transport.push_json(
+  ///   400,
+  ///   r#"{"user":"invalid","errors":["user key is invalid"],"status":0,
+  ///     "request":"5042853c"}"#,
+  /// );
+  ///
+  /// let client = PushoverClient::with_transport("application token", &transport);
+  ///
+  /// let devices = client.list_user_devices("user key").unwrap();
+  /// assert_eq!(devices, vec!["iphone", "pixel"]);
+  ///
+  /// assert!(client.list_user_devices("group key").unwrap().is_empty());
+  ///
+  /// let error = client.list_user_devices("invalid key").unwrap_err();
+  /// assert_eq!(error.api_errors()[0].kind(), ApiErrorKind::InvalidUser);
+  /// ```
+  pub fn list_user_devices(&self, user: &str) -> Result<Vec<String>> {
+    Ok(self.validate_user(user, None)?.devices)
+  }
+}
@@ -0,0 +1,331 @@
+//! Local validation of messages before they're sent.
+
+use std::fmt;
+
+/// The maximum number of characters in a message.
+pub const MAX_MESSAGE_LENGTH: usize = 1024;
+
+/// The maximum number of characters in a message's title.
+pub const MAX_TITLE_LENGTH: usize = 250;
+
+/// The maximum number of characters in a message's supplementary URL.
+pub const MAX_URL_LENGTH: usize = 512;
+
+/// The maximum number of characters in a message's supplementary URL title.
+pub const MAX_URL_TITLE_LENGTH: usize = 100;
+
+/// The maximum number of characters in a device name.
+pub const MAX_DEVICE_NAME_LENGTH: usize = 25;
+
+/// Checks whether a device name is at most
+/// [`MAX_DEVICE_NAME_LENGTH`](constant.MAX_DEVICE_NAME_LENGTH.html)
+/// characters and only contains ASCII letters, numbers, underscores and
+/// dashes.
+pub(crate) fn is_valid_device_name(name: &str) -> bool {
+  !name.is_empty()
+    && name.len() <= MAX_DEVICE_NAME_LENGTH
+    && name
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// A single reason why a [`Message`](struct.Message.html) would be rejected by
+/// the Pushover API.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ValidationError {
+  /// A field has more characters than the Pushover API allows.
+  ///
+  /// Characters are counted rather than bytes, so text with multi-byte
+  /// characters can have more bytes than the limit.
+  ///
+  /// ```rust
+  /// use pushover_api::{
+  ///   Message, ValidationError, MAX_MESSAGE_LENGTH, MAX_TITLE_LENGTH,
+  ///   MAX_URL_LENGTH, MAX_URL_TITLE_LENGTH,
+  /// };
+  ///
+  /// let at_limit = Message {
+  ///   message: "a".repeat(MAX_MESSAGE_LENGTH),
+  ///   title: Some("a".repeat(MAX_TITLE_LENGTH)),
+  ///   url: Some("a".repeat(MAX_URL_LENGTH)),
+  ///   url_title: Some("a".repeat(MAX_URL_TITLE_LENGTH)),
+  ///   ..Message::default()
+  /// };
+  /// assert!(at_limit.validate().is_ok());
+  ///
+  /// let too_long = |field, limit| ValidationError::TooLong {
+  ///   field,
+  ///   length: limit + 1,
+  ///   limit,
+  /// };
+  /// for text in &["a", "é", "🦀"] {
+  ///   let over_limit = Message {
+  ///     message: text.repeat(MAX_MESSAGE_LENGTH + 1),
+  ///     title: Some(text.repeat(MAX_TITLE_LENGTH + 1)),
+  ///     url: Some(text.repeat(MAX_URL_LENGTH + 1)),
+  ///     url_title: Some(text.repeat(MAX_URL_TITLE_LENGTH + 1)),
+  ///     ..Message::default()
+  ///   };
+  ///   assert_eq!(
+  ///     over_limit.validate().unwrap_err().0,
+  ///     vec![
+  ///       too_long("message", 1024),
+  ///       too_long("title", 250),
+  ///       too_long("url", 512),
+  ///       too_long("url_title", 100),
+  ///     ]
+  ///   );
+  /// }
+  ///
+  /// // 1024 crabs are 4096 bytes, but still only 1024 characters.
+  /// let crabs = Message {
+  ///   message: "🦀".repeat(MAX_MESSAGE_LENGTH),
+  ///   title: Some("é".repeat(MAX_TITLE_LENGTH)),
+  ///   ..Message::default()
+  /// };
+  /// assert_eq!(crabs.message.len(), 4096);
+  /// assert!(crabs.validate().is_ok());
+  /// ```
+  TooLong {
+    /// The name of the field.
+    field: &'static str,
+    /// The number of characters in the field.
+    length: usize,
+    /// The maximum number of characters allowed in the field.
+    limit: usize,
+  },
+  /// A URL isn't an absolute `http` or `https` URL or contains whitespace,
+  /// only checked with the `url-validation` feature.
+  ///
+  /// ```rust
+  /// # #[cfg(feature = "url-validation")]
+  /// # {
+  /// use pushover_api::{Message, ValidationError};
+  ///
+  /// let message = Message {
+  ///   message: "Message".to_string(),
+  ///   url: Some("javascript:alert(1)".to_string()),
+  ///   callback: Some("/relative".to_string()),
+  ///   ..Message::default()
+  /// };
+  ///
+  /// assert_eq!(
+  ///   message.validate().unwrap_err().0,
+  ///   vec![
+  ///     ValidationError::InvalidUrl {
+  ///       field: "url",
+  ///       url: "javascript:alert(1)".to_string(),
+  ///     },
+  ///     ValidationError::InvalidUrl {
+  ///       field: "callback",
+  ///       url: "/relative".to_string(),
+  ///     },
+  ///   ]
+  /// );
+  /// # }
+  /// ```
+  InvalidUrl {
+    /// The name of the field.
+    field: &'static str,
+    /// The invalid URL.
+    url: String,
+  },
+  /// A device name is empty, too long or contains characters other than
+  /// ASCII letters, numbers, underscores and dashes.
+  ///
+  /// ```rust
+  /// use pushover_api::{Message, ValidationError, MAX_DEVICE_NAME_LENGTH};
+  ///
+  /// let longest = "a".repeat(MAX_DEVICE_NAME_LENGTH);
+  /// let too_long = "a".repeat(MAX_DEVICE_NAME_LENGTH + 1);
+  /// let message = Message {
+  ///   message: "Message".to_string(),
+  ///   device: vec![
+  ///     "iphone".to_string(),
+  ///     "Work_Laptop-2".to_string(),
+  ///     longest.clone(),
+  ///     too_long.clone(),
+  ///     "my phone".to_string(),
+  ///     "phone,desktop".to_string(),
+  ///     "téléphone".to_string(),
+  ///     String::new(),
+  ///   ],
+  ///   ..Message::default()
+  /// };
+  ///
+  /// assert_eq!(
+  ///   message.validate().unwrap_err().0,
+  ///   vec![
+  ///     ValidationError::InvalidDevice(too_long),
+  ///     ValidationError::InvalidDevice("my phone".to_string()),
+  ///     ValidationError::InvalidDevice("phone,desktop".to_string()),
+  ///     ValidationError::InvalidDevice("téléphone".to_string()),
+  ///     ValidationError::InvalidDevice(String::new()),
+  ///   ]
+  /// );
+  ///
+  /// // Valid devices are joined with commas when the message is sent.
+  /// let message = Message {
+  ///   message: "Message".to_string(),
+  ///   device: vec![
+  ///     "iphone".to_string(),
+  ///     "Work_Laptop-2".to_string(),
+  ///     longest,
+  ///   ],
+  ///   ..Message::default()
+  /// };
+  /// assert!(message.validate().is_ok());
+  /// let json = serde_json::to_value(&message).unwrap();
+  /// assert_eq!(
+  ///   json["device"],
+  ///   format!("iphone,Work_Laptop-2,{}", "a".repeat(MAX_DEVICE_NAME_LENGTH))
+  /// );
+  /// ```
+  InvalidDevice(String),
+  /// An emergency priority message is missing `retry` or `expire`.
+  MissingRetryOrExpire,
+  /// An emergency priority message has a `ttl`.
+  EmergencyWithTtl,
+  /// A tag is empty or contains commas or whitespace.
+  InvalidTag(String),
+  /// Both `attachment` and `attachment_base64` are set.
+  ConflictingAttachments,
+  /// Only one of `attachment_base64` and `attachment_type` is set.
+  IncompleteBase64Attachment,
+  /// Both `html` and `monospace` are enabled.
+  ///
+  /// ```rust
+  /// use pushover_api::{Message, ValidationError};
+  ///
+  /// let message = |html, monospace| Message {
+  ///   message: "<b>Message</b>".to_string(),
+  ///   html,
+  ///   monospace,
+  ///   ..Message::default()
+  /// };
+  ///
+  /// assert!(message(Some(true), None).validate().is_ok());
+  /// assert!(message(None, Some(true)).validate().is_ok());
+  /// assert!(message(Some(true), Some(false)).validate().is_ok());
+  /// assert!(message(Some(false), Some(true)).validate().is_ok());
+  /// assert_eq!(
+  ///   message(Some(true), Some(true)).validate().unwrap_err().0,
+  ///   vec![ValidationError::HtmlAndMonospace]
+  /// );
+  /// ```
+  HtmlAndMonospace,
+}
+
+impl ValidationError {
+  /// Checks whether `value` has more characters than `limit`, counting
+  /// characters rather than bytes like the Pushover API does.
+  pub(crate) fn check_length(
+    field: &'static str,
+    value: &str,
+    limit: usize,
+  ) -> Option<Self> {
+    let length = value.chars().count();
+    if length > limit {
+      Some(ValidationError::TooLong {
+        field,
+        length,
+        limit,
+      })
+    } else {
+      None
+    }
+  }
+}
+
+#[cfg(feature = "url-validation")]
+impl ValidationError {
+  /// Checks whether `value` is an absolute `http` or `https` URL without any
+  /// whitespace.
+  pub(crate) fn check_url(field: &'static str, value: &str) -> Option<Self> {
+    let is_valid = !value.contains(char::is_whitespace)
+      && url::Url::parse(value)
+        .map(|url| url.scheme() == "http" || url.scheme() == "https")
+        .unwrap_or(false);
+
+    if is_valid {
+      None
+    } else {
+      Some(ValidationError::InvalidUrl {
+        field,
+        url: value.to_string(),
+      })
+    }
+  }
+}
+
+impl fmt::Display for ValidationError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ValidationError::TooLong {
+        field,
+        length,
+        limit,
+      } => write!(
+        f,
+        "The {} is {} characters over the limit of {}",
+        field,
+        length - limit,
+        limit
+      ),
+      ValidationError::InvalidUrl { field, url } => {
+        write!(f, "The {} {:?} isn't a valid http or https URL", field, url)
+      }
+      ValidationError::InvalidDevice(device) => write!(
+        f,
+        "Invalid device name {:?}, device names can be at most {} characters \
+          and only contain letters, numbers, underscores and dashes",
+        device, MAX_DEVICE_NAME_LENGTH
+      ),
+      ValidationError::MissingRetryOrExpire => write!(
+        f,
+        "Emergency priority messages require both retry and expire to be set"
+      ),
+      ValidationError::EmergencyWithTtl => {
+        write!(f, "Emergency priority messages can't have a time to live")
+      }
+      ValidationError::InvalidTag(tag) => write!(
+        f,
+        "Invalid tag {:?}, tags can't be empty or contain commas or whitespace",
+        tag
+      ),
+      ValidationError::ConflictingAttachments => write!(
+        f,
+        "Messages can't have both an attachment and a base64 attachment"
+      ),
+      ValidationError::IncompleteBase64Attachment => write!(
+        f,
+        "Base64 attachments require both attachment_base64 and \
+          attachment_type to be set"
+      ),
+      ValidationError::HtmlAndMonospace => {
+        write!(f, "Messages can't use both HTML and monospace formatting")
+      }
+    }
+  }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// All the reasons why a [`Message`](struct.Message.html) would be rejected
+/// by the Pushover API, returned by
+/// [`Message::validate()`](struct.Message.html#method.validate).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ValidationErrors(pub Vec<ValidationError>);
+
+impl fmt::Display for ValidationErrors {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let errors = self
+      .0
+      .iter()
+      .map(ToString::to_string)
+      .collect::<Vec<String>>();
+    write!(f, "{}", errors.join(", "))
+  }
+}
+
+impl std::error::Error for ValidationErrors {}
@@ -0,0 +1,97 @@
+//! Integration tests of the TLS backends, making real handshakes against a
+//! local HTTPS server. They run with whichever of the `native-tls` and
+//! `rustls` features is enabled:
+//!
+//! ```sh
+//! cargo test -p pushover_api --test tls
+//! cargo test -p pushover_api --no-default-features \
+//!   --features blocking,rustls --test tls
+//! ```
+//!
+//! The certificates in `tests/fixtures` are for `localhost`, signed by a test
+//! certificate authority that's only trusted by these tests.
+
+#![cfg(all(
+  feature = "blocking",
+  any(feature = "native-tls", feature = "rustls")
+))]
+
+use pushover_api::{PushoverClient, PushoverError};
+use reqwest::{blocking::Client, Certificate};
+use rustls::{
+  internal::pemfile::{certs, pkcs8_private_keys},
+  NoClientAuth, ServerConfig, ServerSession, StreamOwned,
+};
+use std::{
+  io::{Read, Write},
+  net::TcpListener,
+  sync::Arc,
+  thread,
+};
+
+const CA: &[u8] = include_bytes!("fixtures/ca.pem");
+const CERTIFICATE: &[u8] = include_bytes!("fixtures/localhost.pem");
+const KEY: &[u8] = include_bytes!("fixtures/localhost.key");
+
+/// Starts an HTTPS server that answers a single request with a successful
+/// Pushover API response, returning its base URL.
+fn serve_once() -> String {
+  let mut config = ServerConfig::new(NoClientAuth::new());
+  let key = pkcs8_private_keys(&mut &KEY[..]).unwrap().remove(0);
+  config
+    .set_single_cert(certs(&mut &CERTIFICATE[..]).unwrap(), key)
+    .unwrap();
+  let config = Arc::new(config);
+
+  let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+  let address = listener.local_addr().unwrap();
+  thread::spawn(move || {
+    let (stream, _) = listener.accept().unwrap();
+    let mut stream = StreamOwned::new(ServerSession::new(&config), stream);
+
+    // A failed handshake shows up as an error here, which only the client
+    // has to care about.
+    if stream.read(&mut [0; 4096]).is_err() {
+      return;
+    }
+
+    let body = r#"{"status":1,"request":"5042853c"}"#;
+    let _ = write!(
+      stream,
+      "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+       Connection: close\r\nContent-Length: {}\r\n\r\n{}",
+      body.len(),
+      body
+    );
+    let _ = stream.flush();
+  });
+
+  format!("https://localhost:{}/1", address.port())
+}
+
+#[test]
+fn trusted_certificates_are_accepted() {
+  let client = Client::builder()
+    .add_root_certificate(Certificate::from_pem(CA).unwrap())
+    .build()
+    .unwrap();
+
+  let response = PushoverClient::with_transport("token", client)
+    .default_user("user key")
+    .base_url(&serve_once())
+    .unwrap()
+    .notify("Message")
+    .unwrap();
+  assert_eq!(response.request.as_str(), "5042853c");
+}
+
+#[test]
+fn untrusted_certificates_are_rejected() {
+  let error = PushoverClient::with_transport("token", Client::new())
+    .default_user("user key")
+    .base_url(&serve_once())
+    .unwrap()
+    .notify("Message")
+    .unwrap_err();
+  assert!(matches!(error, PushoverError::Transport(_)), "{:?}", error);
+}
@@ -0,0 +1,44 @@
+//! Smoke tests of the asynchronous API on WebAssembly, run in Node.js with
+//! `wasm-bindgen-test-runner` as the runner:
+//!
+//! ```sh
+//! CARGO_TARGET_WASM32_UNKNOWN_UNKNOWN_RUNNER=wasm-bindgen-test-runner \
+//!   cargo test -p pushover_api --target wasm32-unknown-unknown \
+//!   --no-default-features --features async --test wasm
+//! ```
+
+#![cfg(target_arch = "wasm32")]
+
+use pushover_api::{Message, PushoverError, MAX_TITLE_LENGTH};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+async fn invalid_messages_fail_before_fetching() {
+  let message = Message {
+    token: "application token".into(),
+    user: "user key".into(),
+    message: "Message".to_string(),
+    title: Some("a".repeat(MAX_TITLE_LENGTH + 1)),
+    ..Message::default()
+  };
+
+  let error = message.send_async().await.unwrap_err();
+  assert!(matches!(error, PushoverError::Validation(_)));
+}
+
+#[wasm_bindgen_test]
+async fn messages_are_sent_with_fetch() {
+  let message = Message {
+    token: "invalid application token".into(),
+    user: "invalid user key".into(),
+    message: "Message".to_string(),
+    ..Message::default()
+  };
+
+  // Without a network the request fails, otherwise the Pushover API rejects
+  // the invalid token.
+  match message.send_async().await.unwrap_err() {
+    PushoverError::Api { .. } | PushoverError::Transport(_) => {}
+    error => panic!("Unexpected error: {:?}", error),
+  }
+}
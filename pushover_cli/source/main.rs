@@ -1,7 +1,7 @@
 use clap::{
   crate_authors, crate_description, crate_name, crate_version, App, Arg,
 };
-use pushover_api::{Message, MessagePriority};
+use pushover_api::{Attachment, Message, MessagePriority, Sound};
 
 /// CLI subcommands.
 mod subcommands;
@@ -29,21 +29,50 @@ fn main() {
     let title = sub_cli.value_of("title").map(String::from);
     let url = sub_cli.value_of("url").map(String::from);
     let url_title = sub_cli.value_of("url-title").map(String::from);
-    let sound = sub_cli.value_of("sound").map(String::from);
+    let sound = sub_cli.value_of("sound").map(Sound::from);
+    let callback = sub_cli.value_of("callback").map(String::from);
+    let attachment = sub_cli.value_of("attachment").map(Attachment::from_path);
 
-    // Pushover expects devices to be a comma-separated list.
     let device = sub_cli
       .values_of("device")
-      .map(|values| values.collect::<Vec<&str>>().join(","));
-    let priority = sub_cli.value_of("priority").map(MessagePriority::from);
+      .map(|values| values.map(String::from).collect())
+      .unwrap_or_default();
+    let html = if sub_cli.is_present("html") {
+      Some(true)
+    } else {
+      None
+    };
+    let monospace = if sub_cli.is_present("monospace") {
+      Some(true)
+    } else {
+      None
+    };
+    let tags = sub_cli
+      .values_of("tag")
+      .map(|values| values.map(String::from).collect())
+      .unwrap_or_default();
+    let priority = sub_cli.value_of("priority").map(|value| {
+      value
+        .parse::<MessagePriority>()
+        .expect("Failed to parse priority")
+    });
     let timestamp = sub_cli
       .value_of("timestamp")
       .map(|value| value.parse().expect("Failed to parse timestamp to i64"));
+    let retry = sub_cli
+      .value_of("retry")
+      .map(|value| value.parse().expect("Failed to parse retry to u32"));
+    let expire = sub_cli
+      .value_of("expire")
+      .map(|value| value.parse().expect("Failed to parse expire to u32"));
+    let ttl = sub_cli
+      .value_of("ttl")
+      .map(|value| value.parse().expect("Failed to parse ttl to u32"));
 
     let response = Message {
       message,
-      token,
-      user,
+      token: token.into(),
+      user: user.into(),
       device,
       title,
       url,
@@ -51,6 +80,15 @@ fn main() {
       priority,
       sound,
       timestamp,
+      retry,
+      expire,
+      callback,
+      tags,
+      html,
+      monospace,
+      ttl,
+      attachment,
+      ..Message::default()
     }
     .send()
     .expect("Error sending message");
@@ -37,11 +37,46 @@ pub fn send_message<'a, 'b>() -> App<'a, 'b> {
         .help("The title to use for the supplementary URL.")
         .requires("url")
         .takes_value(true),
+      Arg::with_name("html")
+        .long("html")
+        .help("Render the message with HTML formatting."),
+      Arg::with_name("monospace")
+        .long("monospace")
+        .help("Display the message in a monospace font.")
+        .conflicts_with("html"),
       Arg::with_name("priority")
         .long("priority")
         .help("The message's priority.")
         .takes_value(true)
-        .possible_values(&["lowest", "low", "normal", "high"]),
+        .possible_values(&["lowest", "low", "normal", "high", "emergency"]),
+      Arg::with_name("retry")
+        .long("retry")
+        .help(
+          "How often (in seconds) to retry an emergency priority message, \
+            minimum 30.",
+        )
+        .required_if("priority", "emergency")
+        .takes_value(true),
+      Arg::with_name("expire")
+        .long("expire")
+        .help(
+          "How long (in seconds) to keep retrying an emergency priority \
+            message, maximum 10800.",
+        )
+        .required_if("priority", "emergency")
+        .takes_value(true),
+      Arg::with_name("callback")
+        .long("callback")
+        .help(
+          "A URL to send a request to when an emergency priority message \
+            has been acknowledged.",
+        )
+        .takes_value(true),
+      Arg::with_name("tag")
+        .long("tag")
+        .help("A tag to attach to the message.")
+        .multiple(true)
+        .takes_value(true),
       Arg::with_name("sound")
         .long("sound")
         .help("The sound to play with the notification.")
@@ -53,6 +88,18 @@ pub fn send_message<'a, 'b>() -> App<'a, 'b> {
             time the message is received by the Pushover API.",
         )
         .takes_value(true),
+      Arg::with_name("ttl")
+        .long("ttl")
+        .help(
+          "The number of seconds until the message is deleted from the \
+            user's devices.",
+        )
+        .conflicts_with("retry")
+        .takes_value(true),
+      Arg::with_name("attachment")
+        .long("attachment")
+        .help("The path to an image to attach to the message.")
+        .takes_value(true),
       Arg::with_name("message").required(true),
     ])
 }